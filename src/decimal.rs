@@ -0,0 +1,298 @@
+//! Rounding rules and fixed-point totals for documents that must not show float artifacts.
+//!
+//! Masses and moments are `f64`, so a total of 745 kg can come out as 744.9999999999. The
+//! [`Rounding`] of a [`Locale`](crate::locale::Locale) decides how the table rounds, and
+//! [`Airplane::decimal_totals`] sums the rows as [`Decimal`] values with four decimals so the
+//! totals equal the sum of the rows exactly.
+
+use core::fmt;
+use core::iter::Sum;
+use core::ops::{Add, Sub};
+
+use crate::weight_and_balance::Airplane;
+
+const DECIMALS: u32 = 4;
+const SCALE: i64 = 10_000;
+// Scaled values this close to a half are a tie, the float was meant to end in 5
+const TIE_TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Rounding {
+    /// Ties round away from zero, 2.345 becomes 2.35.
+    #[default]
+    TiesAway,
+    /// Banker's rounding, ties round to the even digit, 2.345 becomes 2.34.
+    TiesEven,
+}
+
+fn pow10(decimals: u32) -> f64 {
+    (0..decimals).fold(1.0, |p, _| p * 10.0)
+}
+
+impl Rounding {
+    // Rounds to a whole number, without std's f64::round
+    fn integer(self, value: f64) -> i64 {
+        let whole = value as i64;
+        let fraction = value - whole as f64;
+        let step = if value < 0.0 { -1 } else { 1 };
+
+        if (fraction.abs() - 0.5).abs() < TIE_TOLERANCE {
+            match self {
+                Rounding::TiesAway => whole + step,
+                Rounding::TiesEven if whole % 2 == 0 => whole,
+                Rounding::TiesEven => whole + step,
+            }
+        } else if fraction.abs() > 0.5 {
+            whole + step
+        } else {
+            whole
+        }
+    }
+
+    pub fn round(self, value: f64, decimals: u32) -> f64 {
+        let scale = pow10(decimals);
+        self.integer(value * scale) as f64 / scale
+    }
+}
+
+/// A fixed-point number with four decimals.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Decimal(i64);
+
+impl Decimal {
+    pub fn from_f64(value: f64, rounding: Rounding) -> Decimal {
+        Decimal(rounding.integer(value * SCALE as f64))
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / SCALE as f64
+    }
+
+    /// Rounds to fewer decimals, more than four keeps the value.
+    pub fn round(self, decimals: u32, rounding: Rounding) -> Decimal {
+        if decimals >= DECIMALS {
+            return self;
+        }
+        let step = pow10(DECIMALS - decimals) as i64;
+        Decimal(rounding.integer(self.0 as f64 / step as f64) * step)
+    }
+
+    /// Rounds to `decimals` by `rounding` for display, at most four.
+    pub fn rounded(self, decimals: u32, rounding: Rounding) -> Rounded {
+        let decimals = decimals.min(DECIMALS);
+        Rounded {
+            value: self.round(decimals, rounding),
+            decimals,
+        }
+    }
+}
+
+impl Add for Decimal {
+    type Output = Decimal;
+
+    fn add(self, other: Decimal) -> Decimal {
+        Decimal(self.0 + other.0)
+    }
+}
+
+impl Sub for Decimal {
+    type Output = Decimal;
+
+    fn sub(self, other: Decimal) -> Decimal {
+        Decimal(self.0 - other.0)
+    }
+}
+
+impl Sum for Decimal {
+    fn sum<I: Iterator<Item = Decimal>>(iter: I) -> Decimal {
+        iter.fold(Decimal::default(), Add::add)
+    }
+}
+
+/// A [`Decimal`] rounded by a [`Rounding`], shown with exactly its decimals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rounded {
+    value: Decimal,
+    decimals: u32,
+}
+
+impl Rounded {
+    pub fn value(&self) -> Decimal {
+        self.value
+    }
+
+    pub fn decimals(&self) -> u32 {
+        self.decimals
+    }
+}
+
+impl fmt::Display for Rounded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.value.0;
+        let sign = if value < 0 { "-" } else { "" };
+        let (whole, fraction) = (value.abs() / SCALE, value.abs() % SCALE);
+
+        if self.decimals == 0 {
+            write!(f, "{}{}", sign, whole)
+        } else {
+            let fraction = fraction / pow10(DECIMALS - self.decimals) as i64;
+            write!(
+                f,
+                "{}{}.{:0width$}",
+                sign,
+                whole,
+                fraction,
+                width = self.decimals as usize
+            )
+        }
+    }
+}
+
+/// All four decimals, a precision such as `{:.2}` rounds by the default [`Rounding`]. Another
+/// rounding displays through [`Decimal::rounded`].
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimals = f.precision().map_or(DECIMALS, |p| p as u32);
+        fmt::Display::fmt(&self.rounded(decimals, Rounding::default()), f)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecimalTotals {
+    mass_kg: Decimal,
+    mass_moment_kgm: Decimal,
+    center_of_gravity_m: Decimal,
+}
+
+impl DecimalTotals {
+    pub fn mass_kg(&self) -> Decimal {
+        self.mass_kg
+    }
+
+    pub fn mass_moment_kgm(&self) -> Decimal {
+        self.mass_moment_kgm
+    }
+
+    pub fn center_of_gravity_m(&self) -> Decimal {
+        self.center_of_gravity_m
+    }
+}
+
+impl Airplane {
+    /// The mass and moment of every row rounded to four decimals and summed exactly, the CG
+    /// from those totals.
+    pub fn decimal_totals(&self, rounding: Rounding) -> DecimalTotals {
+        let mass_kg: Decimal = self
+            .iter()
+            .map(|m| Decimal::from_f64(m.mass().kilo(), rounding))
+            .sum();
+        let mass_moment_kgm: Decimal = self
+            .iter()
+            .map(|m| Decimal::from_f64(m.total().kgm(), rounding))
+            .sum();
+        let center_of_gravity_m = if mass_kg == Decimal::default() {
+            Decimal::default()
+        } else {
+            Decimal::from_f64(mass_moment_kgm.to_f64() / mass_kg.to_f64(), rounding)
+        };
+
+        DecimalTotals {
+            mass_kg,
+            mass_moment_kgm,
+            center_of_gravity_m,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{CenterOfGravity, LeverArm, Limits, Mass, Moment, Volume};
+    use alloc::string::ToString;
+
+    #[test]
+    fn ties_away_and_even() {
+        assert_eq!(2.68, Rounding::TiesAway.round(2.675, 2));
+        assert_eq!(2.68, Rounding::TiesEven.round(2.675, 2));
+        assert_eq!(2.34, Rounding::TiesEven.round(2.345, 2));
+        assert_eq!(-2.35, Rounding::TiesAway.round(-2.345, 2));
+        assert_eq!(745.0, Rounding::TiesAway.round(744.9999999999, 2));
+
+        let d = Decimal::from_f64(0.125, Rounding::TiesEven);
+        assert_eq!("0.13", alloc::format!("{:.2}", d));
+        assert_eq!("0.1200", d.round(2, Rounding::TiesEven).to_string());
+        assert_eq!(
+            "-1.5000",
+            Decimal::from_f64(-1.5, Rounding::TiesAway).to_string()
+        );
+    }
+
+    #[test]
+    fn rounded_ties_follow_the_rounding() {
+        let d = Decimal::from_f64(0.125, Rounding::TiesEven);
+        assert_eq!("0.13", d.rounded(2, Rounding::TiesAway).to_string());
+        assert_eq!("0.12", d.rounded(2, Rounding::TiesEven).to_string());
+
+        let d = Decimal::from_f64(-0.125, Rounding::TiesEven);
+        assert_eq!("-0.13", d.rounded(2, Rounding::TiesAway).to_string());
+        assert_eq!("-0.12", d.rounded(2, Rounding::TiesEven).to_string());
+
+        let d = Decimal::from_f64(2.5, Rounding::TiesEven);
+        assert_eq!("3", d.rounded(0, Rounding::TiesAway).to_string());
+        assert_eq!("2", d.rounded(0, Rounding::TiesEven).to_string());
+        assert_eq!("2.5000", d.rounded(6, Rounding::TiesEven).to_string());
+    }
+
+    #[test]
+    fn totals_are_the_sum_of_the_rows() {
+        let plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            alloc::vec![
+                Moment::new(
+                    "Empty".to_string(),
+                    LeverArm::Meter(0.4294),
+                    Mass::Kilo(517.1),
+                ),
+                Moment::new(
+                    "Pilot".to_string(),
+                    LeverArm::Meter(0.515),
+                    Mass::Kilo(80.2),
+                ),
+                Moment::new(
+                    "Fuel".to_string(),
+                    LeverArm::Meter(0.325),
+                    Mass::Avgas(Volume::Liter(55.0)),
+                ),
+            ],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(17.0),
+        );
+
+        let totals = plane.decimal_totals(Rounding::TiesAway);
+        assert_eq!("636.9000", totals.mass_kg().to_string());
+        assert_eq!(
+            totals.mass_moment_kgm(),
+            plane
+                .iter()
+                .map(|m| Decimal::from_f64(m.total().kgm(), Rounding::TiesAway))
+                .sum()
+        );
+        assert!(
+            (totals.center_of_gravity_m().to_f64() - plane.total_mass_moment().kgm() / 636.9).abs()
+                < 1e-4
+        );
+    }
+}
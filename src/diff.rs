@@ -0,0 +1,261 @@
+//! The differences between two loadings of an airplane, e.g. the planned and the actual one, for
+//! a delta table or an annotated chart.
+//!
+//! Every difference is the other loading minus this one, so `planned.diff(&actual)` is positive
+//! where more was loaded than planned. Stations are matched by name, a station in only one of the
+//! loadings counts as empty in the other.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::weight_and_balance::{
+    Airplane, CenterOfGravity, Mass, MassMoment, Moment, WeightBalanceSummary,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StationDiff {
+    name: String,
+    mass: Option<Mass>,
+    other_mass: Option<Mass>,
+    mass_difference: Mass,
+    mass_moment_difference: MassMoment,
+}
+
+impl StationDiff {
+    fn new(name: &str, moment: Option<&Moment>, other: Option<&Moment>) -> StationDiff {
+        let kilo = |m: Option<&Moment>| m.map_or(0.0, |m| m.mass().kilo());
+        let kgm = |m: Option<&Moment>| m.map_or(0.0, |m| m.total().kgm());
+
+        StationDiff {
+            name: name.to_string(),
+            mass: moment.map(|m| m.mass().clone()),
+            other_mass: other.map(|m| m.mass().clone()),
+            mass_difference: Mass::Kilo(kilo(other) - kilo(moment)),
+            mass_moment_difference: MassMoment::KgM(kgm(other) - kgm(moment)),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// `None` when the station is only in the other loading.
+    pub fn mass(&self) -> Option<&Mass> {
+        self.mass.as_ref()
+    }
+
+    /// `None` when the station is not in the other loading.
+    pub fn other_mass(&self) -> Option<&Mass> {
+        self.other_mass.as_ref()
+    }
+
+    pub fn mass_difference(&self) -> &Mass {
+        &self.mass_difference
+    }
+
+    pub fn mass_moment_difference(&self) -> &MassMoment {
+        &self.mass_moment_difference
+    }
+
+    /// Whether the station has the same mass in both loadings.
+    pub fn unchanged(&self) -> bool {
+        self.mass_difference.kilo() == 0.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SummaryDiff {
+    takeoff_mass: Mass,
+    takeoff_mass_moment: MassMoment,
+    takeoff_center_of_gravity: CenterOfGravity,
+    landing_mass: Option<Mass>,
+    landing_center_of_gravity: Option<CenterOfGravity>,
+    mtow_margin: Mass,
+    within_limits: (bool, bool),
+}
+
+impl SummaryDiff {
+    pub fn takeoff_mass(&self) -> &Mass {
+        &self.takeoff_mass
+    }
+
+    pub fn takeoff_mass_moment(&self) -> &MassMoment {
+        &self.takeoff_mass_moment
+    }
+
+    /// Not a number when either airplane has no mass.
+    pub fn takeoff_center_of_gravity(&self) -> &CenterOfGravity {
+        &self.takeoff_center_of_gravity
+    }
+
+    /// `None` unless both summaries have a landing mass.
+    pub fn landing_mass(&self) -> Option<&Mass> {
+        self.landing_mass.as_ref()
+    }
+
+    /// `None` unless both summaries have a landing CG.
+    pub fn landing_center_of_gravity(&self) -> Option<&CenterOfGravity> {
+        self.landing_center_of_gravity.as_ref()
+    }
+
+    pub fn mtow_margin(&self) -> &Mass {
+        &self.mtow_margin
+    }
+
+    /// Whether this and the other loading are within the limits.
+    pub fn within_limits(&self) -> (bool, bool) {
+        self.within_limits
+    }
+}
+
+impl WeightBalanceSummary {
+    /// The differences of the totals, `other` minus these figures.
+    pub fn diff(&self, other: &WeightBalanceSummary) -> SummaryDiff {
+        let cg = |summary: &WeightBalanceSummary| summary.takeoff_center_of_gravity().meter();
+        let landing_mass = |summary: &WeightBalanceSummary| summary.landing_mass().map(Mass::kilo);
+        let landing_cg = |summary: &WeightBalanceSummary| {
+            summary
+                .landing_center_of_gravity()
+                .map(CenterOfGravity::meter)
+        };
+
+        SummaryDiff {
+            takeoff_mass: Mass::Kilo(other.takeoff_mass().kilo() - self.takeoff_mass().kilo()),
+            takeoff_mass_moment: MassMoment::KgM(
+                other.takeoff_mass_moment().kgm() - self.takeoff_mass_moment().kgm(),
+            ),
+            takeoff_center_of_gravity: CenterOfGravity::Meter(cg(other) - cg(self)),
+            landing_mass: landing_mass(self)
+                .zip(landing_mass(other))
+                .map(|(mass, other)| Mass::Kilo(other - mass)),
+            landing_center_of_gravity: landing_cg(self)
+                .zip(landing_cg(other))
+                .map(|(cg, other)| CenterOfGravity::Meter(other - cg)),
+            mtow_margin: Mass::Kilo(other.mtow_margin().kilo() - self.mtow_margin().kilo()),
+            within_limits: (self.within_limits(), other.within_limits()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoadingDiff {
+    stations: Vec<StationDiff>,
+    totals: SummaryDiff,
+}
+
+impl LoadingDiff {
+    /// The stations of this loading in order, then the ones only in the other loading.
+    pub fn stations(&self) -> &Vec<StationDiff> {
+        &self.stations
+    }
+
+    /// The stations with a different mass.
+    pub fn changed(&self) -> impl Iterator<Item = &StationDiff> {
+        self.stations.iter().filter(|s| !s.unchanged())
+    }
+
+    pub fn totals(&self) -> &SummaryDiff {
+        &self.totals
+    }
+}
+
+impl Airplane {
+    /// The differences per station and of the totals, `other` minus this loading.
+    pub fn diff(&self, other: &Airplane) -> LoadingDiff {
+        fn find<'a>(plane: &'a Airplane, name: &str) -> Option<&'a Moment> {
+            plane.iter().find(|m| m.name() == name)
+        }
+
+        let mut stations: Vec<StationDiff> = self
+            .iter()
+            .map(|m| StationDiff::new(m.name(), Some(m), find(other, m.name())))
+            .collect();
+        stations.extend(
+            other
+                .iter()
+                .filter(|m| find(self, m.name()).is_none())
+                .map(|m| StationDiff::new(m.name(), None, Some(m))),
+        );
+
+        LoadingDiff {
+            stations,
+            totals: self.summary().diff(&other.summary()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{LeverArm, Limits, Volume};
+
+    fn airplane(pilot: f64, baggage: Option<f64>) -> Airplane {
+        let mut moments = alloc::vec![
+            Moment::new(
+                "Empty".to_string(),
+                LeverArm::Meter(0.4294),
+                Mass::Kilo(517.0),
+            ),
+            Moment::new(
+                "Pilot".to_string(),
+                LeverArm::Meter(0.515),
+                Mass::Kilo(pilot)
+            ),
+        ];
+        if let Some(baggage) = baggage {
+            moments.push(Moment::new(
+                "Baggage".to_string(),
+                LeverArm::Meter(1.3),
+                Mass::Kilo(baggage),
+            ));
+        }
+        moments.push(Moment::new(
+            "Fuel".to_string(),
+            LeverArm::Meter(0.325),
+            Mass::Avgas(Volume::Liter(62.0)),
+        ));
+
+        Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            moments,
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(17.0),
+        )
+    }
+
+    #[test]
+    fn planned_and_actual() {
+        let planned = airplane(80.0, None);
+        let actual = airplane(85.0, Some(5.0));
+        let diff = planned.diff(&actual);
+
+        let names: Vec<&str> = diff.stations().iter().map(StationDiff::name).collect();
+        assert_eq!(alloc::vec!["Empty", "Pilot", "Fuel", "Baggage"], names);
+        let changed: Vec<&str> = diff.changed().map(StationDiff::name).collect();
+        assert_eq!(alloc::vec!["Pilot", "Baggage"], changed);
+
+        let baggage = &diff.stations()[3];
+        assert_eq!(None, baggage.mass());
+        assert_eq!(Some(&Mass::Kilo(5.0)), baggage.other_mass());
+        assert!((baggage.mass_moment_difference().kgm() - 6.5).abs() < 1e-9);
+
+        let totals = diff.totals();
+        assert!((totals.takeoff_mass().kilo() - 10.0).abs() < 1e-9);
+        assert!((totals.mtow_margin().kilo() + 10.0).abs() < 1e-9);
+        assert!(totals.takeoff_center_of_gravity().meter() > 0.0);
+        assert!((totals.landing_mass().unwrap().kilo() - 10.0).abs() < 1e-9);
+
+        let same = planned.diff(&planned);
+        assert_eq!(0, same.changed().count());
+        assert_eq!(0.0, same.totals().takeoff_mass().kilo());
+    }
+}
@@ -0,0 +1,588 @@
+//! Flat structs for web services, every quantity a number with a unit string next to it:
+//!
+//! ```json
+//! {
+//!   "callsign": "PH-DHA",
+//!   "moments": [
+//!     { "name": "Empty", "arm": 0.4294, "arm_unit": "m", "mass": 517.0, "mass_unit": "kg" },
+//!     { "name": "Fuel", "arm": 0.325, "arm_unit": "m", "mass": 55.0, "mass_unit": "l", "fuel": "avgas" }
+//!   ],
+//!   "limits": {
+//!     "minimum_weight": 558.0, "mtow": 750.0, "mass_unit": "kg",
+//!     "forward_cg": 427.0, "rearward_cg": 523.0, "cg_unit": "mm"
+//!   },
+//!   "trip_fuel": 17.0,
+//!   "trip_fuel_unit": "l"
+//! }
+//! ```
+//!
+//! Arms are `m` or `in`, masses `kg` or `lb`, or `l` or `gal` together with the `fuel`, centers
+//! of gravity `m`, `mm` or `in` and volumes `l` or `gal`. Unlike the internal enums the DTOs
+//! don't change when the crate's model does.
+//!
+//! Units of both systems are converted as they come, [`AirplaneDto::into_airplane_strict`]
+//! rejects an airplane that mixes them, e.g. an arm in inches among arms in meters. An airplane
+//! converts to meters, kilograms and liters, so its DTO always passes the strict conversion, and
+//! [`Airplane::check_units`] catches volumes in liters among volumes in gallons.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::decimal::Rounding;
+use crate::registration::Registration;
+use crate::weight_and_balance::{
+    Airplane, CenterOfGravity, LeverArm, Limits, Mass, Moment, Volume, WeightBalanceSummary,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitSystem {
+    /// `m`, `mm`, `kg` and `l`.
+    Metric,
+    /// `in`, `lb` and `gal`.
+    Imperial,
+}
+
+impl fmt::Display for UnitSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitSystem::Metric => write!(f, "metric"),
+            UnitSystem::Imperial => write!(f, "imperial"),
+        }
+    }
+}
+
+/// A unit of another system than the first unit of the airplane, the arm of the first moment of
+/// a DTO and the first volume of an [`Airplane`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitMismatch {
+    /// The field with the unit, e.g. `moments[1].mass_unit` of a DTO or `trip_fuel` of an
+    /// airplane.
+    pub field: String,
+    pub unit: String,
+    pub expected: UnitSystem,
+}
+
+impl fmt::Display for UnitMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is {} instead of {}",
+            self.field, self.unit, self.expected
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DtoError {
+    UnknownUnit(String),
+    UnknownFuel(String),
+    /// The units that need converting before a strict conversion, in the order of the fields.
+    MixedUnits(Vec<UnitMismatch>),
+}
+
+impl fmt::Display for DtoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DtoError::UnknownUnit(unit) => write!(f, "unknown unit {}", unit),
+            DtoError::UnknownFuel(fuel) => write!(f, "unknown fuel {}", fuel),
+            DtoError::MixedUnits(mismatches) => {
+                write!(f, "mixed units")?;
+                for (i, mismatch) in mismatches.iter().enumerate() {
+                    write!(f, "{} {}", if i == 0 { ":" } else { "," }, mismatch)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for DtoError {}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MomentDto {
+    pub name: String,
+    pub arm: f64,
+    pub arm_unit: String,
+    pub mass: f64,
+    pub mass_unit: String,
+    /// `avgas` or `mogas` when the mass is a fuel volume.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fuel: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LimitsDto {
+    pub minimum_weight: f64,
+    pub mtow: f64,
+    pub mass_unit: String,
+    pub forward_cg: f64,
+    pub rearward_cg: f64,
+    pub cg_unit: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AirplaneDto {
+    pub callsign: Registration,
+    pub moments: Vec<MomentDto>,
+    pub limits: LimitsDto,
+    pub trip_fuel: f64,
+    pub trip_fuel_unit: String,
+}
+
+/// Results are always in kilograms and meters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SummaryDto {
+    pub takeoff_mass: f64,
+    pub takeoff_mass_moment: f64,
+    /// `None` when the airplane has no mass.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub takeoff_cg: Option<f64>,
+    pub landing_mass: Option<f64>,
+    pub landing_mass_moment: Option<f64>,
+    pub mass_unit: String,
+    pub mass_moment_unit: String,
+    pub cg_unit: String,
+    pub within_limits: bool,
+}
+
+fn volume_unit(volume: &Volume) -> &'static str {
+    match volume {
+        Volume::Liter(_) => "l",
+        Volume::Gallon(_) => "gal",
+    }
+}
+
+fn volume(value: f64, unit: &str) -> Result<Volume, DtoError> {
+    match unit {
+        "l" => Ok(Volume::Liter(value)),
+        "gal" => Ok(Volume::Gallon(value)),
+        _ => Err(DtoError::UnknownUnit(unit.to_string())),
+    }
+}
+
+fn mass(value: f64, unit: &str) -> Result<Mass, DtoError> {
+    match unit {
+        "kg" => Ok(Mass::Kilo(value)),
+        "lb" => Ok(Mass::from_pound(value)),
+        _ => Err(DtoError::UnknownUnit(unit.to_string())),
+    }
+}
+
+fn center_of_gravity(value: f64, unit: &str) -> Result<CenterOfGravity, DtoError> {
+    match unit {
+        "m" => Ok(CenterOfGravity::Meter(value)),
+        "mm" => Ok(CenterOfGravity::Millimeter(value)),
+        "in" => Ok(CenterOfGravity::Millimeter(
+            LeverArm::from_inch(value).meter() * 1000.0,
+        )),
+        _ => Err(DtoError::UnknownUnit(unit.to_string())),
+    }
+}
+
+fn unit_system(unit: &str) -> Option<UnitSystem> {
+    match unit {
+        "m" | "mm" | "kg" | "l" => Some(UnitSystem::Metric),
+        "in" | "lb" | "gal" => Some(UnitSystem::Imperial),
+        _ => None,
+    }
+}
+
+fn center_of_gravity_dto(cg: &CenterOfGravity) -> (f64, &'static str) {
+    match cg {
+        CenterOfGravity::Meter(m) => (*m, "m"),
+        CenterOfGravity::Millimeter(mm) => (*mm, "mm"),
+    }
+}
+
+impl From<&Moment> for MomentDto {
+    fn from(moment: &Moment) -> MomentDto {
+        let (mass, mass_unit, fuel) = match moment.mass() {
+            Mass::Kilo(kg) => (*kg, "kg".to_string(), None),
+            Mass::Avgas(v) => (v.to_liter(), "l".to_string(), Some("avgas".to_string())),
+            Mass::Mogas(v) => (v.to_liter(), "l".to_string(), Some("mogas".to_string())),
+        };
+
+        MomentDto {
+            name: moment.name().to_string(),
+            arm: moment.lever_arm().meter(),
+            arm_unit: "m".to_string(),
+            mass,
+            mass_unit,
+            fuel,
+        }
+    }
+}
+
+impl TryFrom<MomentDto> for Moment {
+    type Error = DtoError;
+
+    fn try_from(dto: MomentDto) -> Result<Moment, DtoError> {
+        let lever_arm = match dto.arm_unit.as_str() {
+            "m" => LeverArm::Meter(dto.arm),
+            "in" => LeverArm::from_inch(dto.arm),
+            _ => return Err(DtoError::UnknownUnit(dto.arm_unit)),
+        };
+        let mass = match dto.fuel.as_deref() {
+            None => mass(dto.mass, &dto.mass_unit)?,
+            Some("avgas") => Mass::Avgas(volume(dto.mass, &dto.mass_unit)?),
+            Some("mogas") => Mass::Mogas(volume(dto.mass, &dto.mass_unit)?),
+            Some(fuel) => return Err(DtoError::UnknownFuel(fuel.to_string())),
+        };
+
+        Ok(Moment::new(dto.name, lever_arm, mass))
+    }
+}
+
+impl From<&Limits> for LimitsDto {
+    fn from(limits: &Limits) -> LimitsDto {
+        let (forward_cg, cg_unit) = center_of_gravity_dto(limits.forward_cg_limit());
+        let rearward_cg = match cg_unit {
+            "mm" => limits.rearward_cg_limit().meter() * 1000.0,
+            _ => limits.rearward_cg_limit().meter(),
+        };
+
+        LimitsDto {
+            minimum_weight: limits.minimum_weight().kilo(),
+            mtow: limits.mtow().kilo(),
+            mass_unit: "kg".to_string(),
+            forward_cg,
+            rearward_cg,
+            cg_unit: cg_unit.to_string(),
+        }
+    }
+}
+
+impl TryFrom<LimitsDto> for Limits {
+    type Error = DtoError;
+
+    fn try_from(dto: LimitsDto) -> Result<Limits, DtoError> {
+        Ok(Limits::new(
+            mass(dto.minimum_weight, &dto.mass_unit)?,
+            mass(dto.mtow, &dto.mass_unit)?,
+            center_of_gravity(dto.forward_cg, &dto.cg_unit)?,
+            center_of_gravity(dto.rearward_cg, &dto.cg_unit)?,
+        ))
+    }
+}
+
+impl From<&Airplane> for AirplaneDto {
+    fn from(plane: &Airplane) -> AirplaneDto {
+        AirplaneDto {
+            callsign: plane.registration().clone(),
+            moments: plane.iter().map(MomentDto::from).collect(),
+            limits: LimitsDto::from(plane.limits()),
+            trip_fuel: plane.fuel_consumption_trip().to_liter(),
+            trip_fuel_unit: "l".to_string(),
+        }
+    }
+}
+
+impl TryFrom<AirplaneDto> for Airplane {
+    type Error = DtoError;
+
+    fn try_from(dto: AirplaneDto) -> Result<Airplane, DtoError> {
+        Ok(Airplane::new(
+            dto.callsign,
+            dto.moments
+                .into_iter()
+                .map(Moment::try_from)
+                .collect::<Result<_, _>>()?,
+            Limits::try_from(dto.limits)?,
+            volume(dto.trip_fuel, &dto.trip_fuel_unit)?,
+        ))
+    }
+}
+
+// The units of another system than the first known unit
+fn mismatches<'a>(units: impl Iterator<Item = (String, &'a str)>) -> Vec<UnitMismatch> {
+    let mut expected = None;
+    let mut mismatches = vec![];
+    for (field, unit) in units {
+        let Some(system) = unit_system(unit) else {
+            continue;
+        };
+        match expected {
+            None => expected = Some(system),
+            Some(expected) if expected != system => mismatches.push(UnitMismatch {
+                field,
+                unit: unit.to_string(),
+                expected,
+            }),
+            Some(_) => {}
+        }
+    }
+    mismatches
+}
+
+impl Airplane {
+    /// The volumes in another unit than the first volume: the fuel moments, the trip, diversion
+    /// and landing fuel and the tanks. Arms and masses are kept in meters and kilograms.
+    pub fn unit_mismatches(&self) -> Vec<UnitMismatch> {
+        let fuel = self.iter().enumerate().filter_map(|(i, m)| {
+            let volume = m.mass().volume()?;
+            Some((format!("moments[{}].mass", i), volume_unit(volume)))
+        });
+        let trip = [(
+            "trip_fuel".to_string(),
+            volume_unit(self.fuel_consumption_trip()),
+        )];
+        let diversion = self
+            .fuel_consumption_diversion()
+            .map(|v| ("diversion_fuel".to_string(), volume_unit(v)));
+        let landing = self
+            .landing_fuel()
+            .map(|v| ("landing_fuel".to_string(), volume_unit(v)));
+        let tanks = self.tanks().iter().enumerate().flat_map(|(i, t)| {
+            [
+                (format!("tanks[{}].usable", i), volume_unit(t.usable())),
+                (format!("tanks[{}].total", i), volume_unit(t.total())),
+            ]
+        });
+
+        mismatches(
+            fuel.chain(trip)
+                .chain(diversion)
+                .chain(landing)
+                .chain(tanks),
+        )
+    }
+
+    /// Fails with every volume in another unit than the first, for the strict checks of
+    /// [`AirplaneDto::into_airplane_strict`] on an airplane built in code.
+    pub fn check_units(&self) -> Result<(), DtoError> {
+        let mismatches = self.unit_mismatches();
+        match mismatches.is_empty() {
+            true => Ok(()),
+            false => Err(DtoError::MixedUnits(mismatches)),
+        }
+    }
+}
+
+impl AirplaneDto {
+    /// The units of another system than the first one, unknown units are left to the conversion.
+    pub fn unit_mismatches(&self) -> Vec<UnitMismatch> {
+        let units = self
+            .moments
+            .iter()
+            .enumerate()
+            .flat_map(|(i, moment)| {
+                [
+                    (format!("moments[{}].arm_unit", i), moment.arm_unit.as_str()),
+                    (
+                        format!("moments[{}].mass_unit", i),
+                        moment.mass_unit.as_str(),
+                    ),
+                ]
+            })
+            .chain([
+                (
+                    "limits.mass_unit".to_string(),
+                    self.limits.mass_unit.as_str(),
+                ),
+                ("limits.cg_unit".to_string(), self.limits.cg_unit.as_str()),
+                ("trip_fuel_unit".to_string(), self.trip_fuel_unit.as_str()),
+            ]);
+        mismatches(units)
+    }
+
+    /// Like [`Airplane::try_from`], with units of both systems an error instead of converted so
+    /// data entered in the wrong unit is caught before it is used.
+    pub fn into_airplane_strict(self) -> Result<Airplane, DtoError> {
+        let mismatches = self.unit_mismatches();
+        if !mismatches.is_empty() {
+            return Err(DtoError::MixedUnits(mismatches));
+        }
+        Airplane::try_from(self)
+    }
+}
+
+impl From<&WeightBalanceSummary> for SummaryDto {
+    fn from(summary: &WeightBalanceSummary) -> SummaryDto {
+        SummaryDto {
+            takeoff_mass: summary.takeoff_mass().kilo(),
+            takeoff_mass_moment: summary.takeoff_mass_moment().kgm(),
+            takeoff_cg: Some(summary.takeoff_center_of_gravity().meter())
+                .filter(|cg| cg.is_finite()),
+            landing_mass: summary.landing_mass().map(Mass::kilo),
+            landing_mass_moment: summary.landing_mass_moment().map(|m| m.kgm()),
+            mass_unit: "kg".to_string(),
+            mass_moment_unit: "kg m".to_string(),
+            cg_unit: "m".to_string(),
+            within_limits: summary.within_limits(),
+        }
+    }
+}
+
+impl SummaryDto {
+    /// Every number rounded to `decimals`, with the same rule as the tables.
+    pub fn rounded(mut self, decimals: u32, rounding: Rounding) -> SummaryDto {
+        let round = |value: f64| rounding.round(value, decimals);
+        self.takeoff_mass = round(self.takeoff_mass);
+        self.takeoff_mass_moment = round(self.takeoff_mass_moment);
+        self.takeoff_cg = self.takeoff_cg.map(round);
+        self.landing_mass = self.landing_mass.map(round);
+        self.landing_mass_moment = self.landing_mass_moment.map(round);
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn airplane() -> Airplane {
+        Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![
+                Moment::new(
+                    "Empty".to_string(),
+                    LeverArm::Meter(0.4294),
+                    Mass::Kilo(517.0),
+                ),
+                Moment::new(
+                    "Fuel".to_string(),
+                    LeverArm::Meter(0.325),
+                    Mass::Avgas(Volume::Liter(55.0)),
+                ),
+            ],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(17.0),
+        )
+    }
+
+    #[test]
+    fn round_trip() {
+        let dto = AirplaneDto::from(&airplane());
+        assert_eq!(Some("avgas".to_string()), dto.moments[1].fuel);
+        assert_eq!("l", dto.moments[1].mass_unit);
+        assert_eq!("mm", dto.limits.cg_unit);
+        assert!((dto.limits.rearward_cg - 523.0).abs() < 1e-9);
+
+        let plane = Airplane::try_from(dto.clone()).unwrap();
+        assert_eq!(dto, AirplaneDto::from(&plane));
+
+        let summary = SummaryDto::from(&plane.summary());
+        assert_eq!(
+            Some(plane.total_mass_landing().unwrap().kilo()),
+            summary.landing_mass
+        );
+
+        let rounded = summary.rounded(4, Rounding::TiesAway);
+        assert_eq!(Some(0.422), rounded.takeoff_cg);
+
+        let empty = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![],
+            plane.limits().clone(),
+            Volume::Liter(17.0),
+        );
+        let summary = SummaryDto::from(&empty.summary());
+        assert_eq!(None, summary.takeoff_cg);
+    }
+
+    #[test]
+    fn imperial_input() {
+        let moment = Moment::try_from(MomentDto {
+            name: "Pilot".to_string(),
+            arm: 20.28,
+            arm_unit: "in".to_string(),
+            mass: 176.37,
+            mass_unit: "lb".to_string(),
+            fuel: None,
+        })
+        .unwrap();
+        assert!((moment.lever_arm().meter() - 0.5151).abs() < 0.001);
+        assert!((moment.mass().kilo() - 80.0).abs() < 0.01);
+
+        let mut dto = AirplaneDto::from(&airplane());
+        dto.moments[1].fuel = Some("jet-a".to_string());
+        assert_eq!(
+            Some(DtoError::UnknownFuel("jet-a".to_string())),
+            Airplane::try_from(dto).err()
+        );
+    }
+
+    #[test]
+    fn strict_units() {
+        let dto = AirplaneDto::from(&airplane());
+        assert!(dto.unit_mismatches().is_empty());
+        assert!(dto.clone().into_airplane_strict().is_ok());
+
+        let mut mixed = dto;
+        mixed.moments[1].mass = 14.5;
+        mixed.moments[1].mass_unit = "gal".to_string();
+        mixed.limits.mass_unit = "lb".to_string();
+        assert!(Airplane::try_from(mixed.clone()).is_ok());
+
+        let error = mixed.into_airplane_strict().unwrap_err();
+        assert_eq!(
+            DtoError::MixedUnits(vec![
+                UnitMismatch {
+                    field: "moments[1].mass_unit".to_string(),
+                    unit: "gal".to_string(),
+                    expected: UnitSystem::Metric,
+                },
+                UnitMismatch {
+                    field: "limits.mass_unit".to_string(),
+                    unit: "lb".to_string(),
+                    expected: UnitSystem::Metric,
+                },
+            ]),
+            error
+        );
+        assert_eq!(
+            "mixed units: moments[1].mass_unit is gal instead of metric, \
+             limits.mass_unit is lb instead of metric",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn strict_round_trip_in_gallons() {
+        let mut plane = Airplane::new(
+            Registration::parse("N172SP").unwrap(),
+            vec![
+                Moment::new(
+                    "Empty".to_string(),
+                    LeverArm::from_inch(39.0),
+                    Mass::from_pound(1663.0),
+                ),
+                Moment::new(
+                    "Fuel".to_string(),
+                    LeverArm::from_inch(48.0),
+                    Mass::Avgas(Volume::Gallon(40.0)),
+                ),
+            ],
+            airplane().limits().clone(),
+            Volume::Gallon(9.0),
+        );
+        assert_eq!(Ok(()), plane.check_units());
+
+        let strict = AirplaneDto::from(&plane).into_airplane_strict().unwrap();
+        assert!((strict.total_mass().kilo() - plane.total_mass().kilo()).abs() < 1e-9);
+        assert!(
+            (strict.fuel_consumption_trip().to_liter() - Volume::Gallon(9.0).to_liter()).abs()
+                < 1e-9
+        );
+
+        plane.add_tank(crate::weight_and_balance::Tank::new(
+            "Fuel".to_string(),
+            Volume::Gallon(53.0),
+            Volume::Liter(212.0),
+        ));
+        assert_eq!(
+            Err(DtoError::MixedUnits(vec![UnitMismatch {
+                field: "tanks[0].total".to_string(),
+                unit: "l".to_string(),
+                expected: UnitSystem::Imperial,
+            }])),
+            plane.check_units()
+        );
+    }
+}
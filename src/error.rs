@@ -0,0 +1,52 @@
+use alloc::string::String;
+
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum WeightBalanceError {
+    /// The airplane has no moments to compute the weight and balance of.
+    #[error("airplane has no moments")]
+    NoMoments,
+    /// The landing figures need the fuel on board as the last moment.
+    #[error("last moment {0} is not the fuel on board")]
+    NotFuel(String),
+    /// The table needs `required` (width, height) pixels but only `available` were given.
+    #[error(
+        "table needs {}x{} pixels but the canvas is {}x{}",
+        required.0, required.1, available.0, available.1
+    )]
+    TableDoesNotFit {
+        required: (u32, u32),
+        available: (u32, u32),
+    },
+    #[error("duplicate moment {0}")]
+    DuplicateMoment(String),
+    #[error("fuel in tank {0} is above its usable capacity")]
+    AboveTankCapacity(String),
+    #[error("no diversion fuel for the alternate landing")]
+    NoDiversionFuel,
+    /// A tank of [`crate::weight_and_balance::FuelBurn::Sequence`] is not the name of a fuel
+    /// moment.
+    #[error("no fuel moment {0} to burn")]
+    UnknownTank(String),
+    /// The tanks of the fuel burn run dry before the trip fuel is burned.
+    #[error("trip fuel is above the usable fuel on board")]
+    TripFuelAboveUsable,
+    #[error("landing fuel is above the fuel on board")]
+    LandingFuelAboveOnBoard,
+    /// The landing fuel and the fuel on board less the trip fuel are further apart than the
+    /// tolerance.
+    #[error("landing fuel does not match the fuel on board less the trip fuel")]
+    InconsistentLandingFuel,
+    #[error("limits {0} are not policy limits")]
+    NotPolicyLimits(usize),
+    #[error("a limit override needs a justification")]
+    NoJustification,
+    /// A mass, moment or CG that would be drawn is not a number or infinite, e.g. a station
+    /// with an infinite arm.
+    #[error("{0} is not a finite number")]
+    NotFinite(String),
+    #[error("cannot draw {what}: {reason}")]
+    Drawing { what: &'static str, reason: String },
+}
@@ -0,0 +1,199 @@
+//! The maximum baggage for every combination of occupant mass and fuel on board.
+//!
+//! The classic loading matrix of a flight school: the rows are the total mass of the pilot and
+//! passengers at one arm, the columns the fuel states, and every cell the most baggage that keeps
+//! the take-off mass and CG within the limits.
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::weight_and_balance::{Airplane, LeverArm, Mass, Moment};
+
+impl Airplane {
+    /// The most baggage at `arm` that keeps the mass and CG within the limits, `None` when no
+    /// amount of baggage does. Baggage at an arm behind the CG can bring a CG in front of the
+    /// forward limit within the limits, so the airplane itself needn't be within the limits.
+    pub fn max_baggage(&self, arm: &LeverArm) -> Option<Mass> {
+        self.max_mass_within_limits(arm)
+            .map(|(kg, _)| Mass::Kilo(kg))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Feasibility {
+    occupant_arm: LeverArm,
+    fuel_arm: LeverArm,
+    baggage_arm: LeverArm,
+    baggage_limit: Option<Mass>,
+    occupant_masses: Vec<Mass>,
+    fuel_states: Vec<Mass>,
+}
+
+impl Feasibility {
+    pub fn new(occupant_arm: LeverArm, fuel_arm: LeverArm, baggage_arm: LeverArm) -> Feasibility {
+        Feasibility {
+            occupant_arm,
+            fuel_arm,
+            baggage_arm,
+            baggage_limit: None,
+            occupant_masses: Vec::new(),
+            fuel_states: Vec::new(),
+        }
+    }
+
+    /// The structural limit of the baggage compartment.
+    pub fn with_baggage_limit(mut self, limit: Mass) -> Feasibility {
+        self.baggage_limit = Some(limit);
+        self
+    }
+
+    /// The total mass of the pilot and passengers of every row.
+    pub fn with_occupant_masses(mut self, masses: Vec<Mass>) -> Feasibility {
+        self.occupant_masses = masses;
+        self
+    }
+
+    /// The fuel on board of every column, as avgas or mogas.
+    pub fn with_fuel_states(mut self, fuel: Vec<Mass>) -> Feasibility {
+        self.fuel_states = fuel;
+        self
+    }
+
+    /// The matrix for `plane`, the empty aircraft with any fixed equipment.
+    pub fn matrix(&self, plane: &Airplane) -> FeasibilityMatrix {
+        let max_baggage = self
+            .occupant_masses
+            .iter()
+            .map(|occupants| {
+                self.fuel_states
+                    .iter()
+                    .map(|fuel| {
+                        let mut plane = plane.clone();
+                        plane.add_moment(Moment::new(
+                            "Occupants".to_string(),
+                            self.occupant_arm.clone(),
+                            occupants.clone(),
+                        ));
+                        plane.add_moment(Moment::new(
+                            "Fuel".to_string(),
+                            self.fuel_arm.clone(),
+                            fuel.clone(),
+                        ));
+
+                        let baggage = plane.max_baggage(&self.baggage_arm)?;
+                        match &self.baggage_limit {
+                            Some(limit) if limit.kilo() < baggage.kilo() => Some(limit.clone()),
+                            _ => Some(baggage),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        FeasibilityMatrix {
+            occupant_masses: self.occupant_masses.clone(),
+            fuel_states: self.fuel_states.clone(),
+            max_baggage,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeasibilityMatrix {
+    occupant_masses: Vec<Mass>,
+    fuel_states: Vec<Mass>,
+    max_baggage: Vec<Vec<Option<Mass>>>,
+}
+
+impl FeasibilityMatrix {
+    pub fn occupant_masses(&self) -> &Vec<Mass> {
+        &self.occupant_masses
+    }
+
+    pub fn fuel_states(&self) -> &Vec<Mass> {
+        &self.fuel_states
+    }
+
+    /// One row per occupant mass with one cell per fuel state.
+    pub fn rows(&self) -> &Vec<Vec<Option<Mass>>> {
+        &self.max_baggage
+    }
+
+    /// `None` when the combination is outside the limits or the indices outside the matrix.
+    pub fn max_baggage(&self, occupant: usize, fuel: usize) -> Option<&Mass> {
+        self.max_baggage.get(occupant)?.get(fuel)?.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{CenterOfGravity, Limits, Volume};
+
+    fn empty_airplane() -> Airplane {
+        Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            alloc::vec![Moment::new(
+                "Empty".to_string(),
+                LeverArm::Meter(0.4294),
+                Mass::Kilo(517.0),
+            )],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(17.0),
+        )
+    }
+
+    #[test]
+    fn baggage_up_to_mtow_and_rearward_limit() {
+        let mut plane = empty_airplane();
+        plane.add_moment(Moment::new(
+            "Pilot".to_string(),
+            LeverArm::Meter(0.515),
+            Mass::Kilo(80.0),
+        ));
+
+        // The MTOW limits baggage close to the CG
+        let near = plane.max_baggage(&LeverArm::Meter(0.5)).unwrap();
+        assert!((near.kilo() - 153.0).abs() < 1e-9);
+
+        // Far aft the rearward limit comes first
+        let aft = plane.max_baggage(&LeverArm::Meter(2.0)).unwrap();
+        plane.add_moment(Moment::new(
+            "Baggage".to_string(),
+            LeverArm::Meter(2.0),
+            aft.clone(),
+        ));
+        assert!(aft.kilo() < 153.0);
+        assert!(plane.within_limits());
+    }
+
+    #[test]
+    fn matrix_of_occupants_and_fuel() {
+        let matrix = Feasibility::new(
+            LeverArm::Meter(0.515),
+            LeverArm::Meter(0.325),
+            LeverArm::Meter(1.3),
+        )
+        .with_baggage_limit(Mass::Kilo(20.0))
+        .with_occupant_masses(alloc::vec![Mass::Kilo(80.0), Mass::Kilo(200.0)])
+        .with_fuel_states(alloc::vec![
+            Mass::Avgas(Volume::Liter(20.0)),
+            Mass::Avgas(Volume::Liter(80.0)),
+        ])
+        .matrix(&empty_airplane());
+
+        assert_eq!(2, matrix.rows().len());
+        assert_eq!(Some(&Mass::Kilo(20.0)), matrix.max_baggage(0, 0));
+        // 517 + 200 + 57.6 kg is above the MTOW without baggage
+        assert_eq!(None, matrix.max_baggage(1, 1));
+        assert_eq!(None, matrix.max_baggage(2, 0));
+    }
+}
@@ -0,0 +1,150 @@
+//! The released calculation of a flight: the airplane as loaded, its summary, the crate version
+//! and when it was finalized, the artifact an operator archives.
+//!
+//! A [`FinalizedCalculation`] is only made from an airplane whose figures are finite and keep the
+//! [`invariants`], and cannot be changed afterwards. With the `json` feature it is written in
+//! its canonical form: compact JSON with the fields in a fixed order, so the same calculation
+//! always gives the same bytes.
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use crate::error::WeightBalanceError;
+use crate::invariants::{self, InvariantError};
+use crate::weight_and_balance::{Airplane, WeightBalanceSummary};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FinalizeError {
+    /// See [`Airplane::check_finite`].
+    Calculation(WeightBalanceError),
+    Invariant(InvariantError),
+}
+
+impl fmt::Display for FinalizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FinalizeError::Calculation(e) => write!(f, "cannot finalize the calculation: {}", e),
+            FinalizeError::Invariant(e) => write!(f, "cannot finalize the calculation: {}", e),
+        }
+    }
+}
+
+impl core::error::Error for FinalizeError {}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FinalizedCalculation {
+    airplane: Airplane,
+    summary: WeightBalanceSummary,
+    crate_version: String,
+    timestamp_ms: Option<u64>,
+}
+
+impl FinalizedCalculation {
+    /// Finalized now, without a timestamp without the `std` feature as there is no clock.
+    pub fn new(plane: &Airplane) -> Result<FinalizedCalculation, FinalizeError> {
+        FinalizedCalculation::at(plane, crate::journal::now_ms())
+    }
+
+    /// Finalized at `timestamp_ms`, milliseconds since the Unix epoch.
+    pub fn at(
+        plane: &Airplane,
+        timestamp_ms: Option<u64>,
+    ) -> Result<FinalizedCalculation, FinalizeError> {
+        plane.check_finite().map_err(FinalizeError::Calculation)?;
+        invariants::check(plane).map_err(FinalizeError::Invariant)?;
+
+        Ok(FinalizedCalculation {
+            airplane: plane.unobserved(),
+            summary: plane.summary(),
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp_ms,
+        })
+    }
+
+    /// The airplane with its moments, limits and journal as it was finalized.
+    pub fn airplane(&self) -> &Airplane {
+        &self.airplane
+    }
+
+    pub fn summary(&self) -> &WeightBalanceSummary {
+        &self.summary
+    }
+
+    pub fn crate_version(&self) -> &str {
+        &self.crate_version
+    }
+
+    pub fn timestamp_ms(&self) -> Option<u64> {
+        self.timestamp_ms
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{CenterOfGravity, LeverArm, Limits, Mass, Moment, Volume};
+
+    fn airplane(arm: f64) -> Airplane {
+        Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            alloc::vec![
+                Moment::new(
+                    "Empty".to_string(),
+                    LeverArm::Meter(0.4294),
+                    Mass::Kilo(517.0),
+                ),
+                Moment::new("Pilot".to_string(), LeverArm::Meter(arm), Mass::Kilo(80.0)),
+                Moment::new(
+                    "Fuel".to_string(),
+                    LeverArm::Meter(0.325),
+                    Mass::Avgas(Volume::Liter(62.0)),
+                ),
+            ],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(17.0),
+        )
+    }
+
+    #[test]
+    fn finalized_figures() {
+        let plane = airplane(0.515);
+        let finalized = FinalizedCalculation::at(&plane, Some(1_792_000_000_000)).unwrap();
+        assert_eq!(&plane.summary(), finalized.summary());
+        assert_eq!(&plane, finalized.airplane());
+        assert_eq!(env!("CARGO_PKG_VERSION"), finalized.crate_version());
+        assert_eq!(Some(1_792_000_000_000), finalized.timestamp_ms());
+
+        assert!(matches!(
+            FinalizedCalculation::new(&airplane(f64::NAN)),
+            Err(FinalizeError::Calculation(WeightBalanceError::NotFinite(_)))
+        ));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn canonical_json() {
+        let finalized = FinalizedCalculation::at(&airplane(0.515), None).unwrap();
+        let json = finalized.to_canonical_json().unwrap();
+        assert_eq!(
+            json,
+            FinalizedCalculation::at(&airplane(0.515), None)
+                .unwrap()
+                .to_canonical_json()
+                .unwrap()
+        );
+        assert!(!json.contains(' '));
+        let read = FinalizedCalculation::from_json(&json).unwrap();
+        assert_eq!(finalized.airplane(), read.airplane());
+        assert_eq!(
+            finalized.summary().within_limits(),
+            read.summary().within_limits()
+        );
+    }
+}
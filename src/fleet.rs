@@ -0,0 +1,197 @@
+//! The aircraft of a flight school or club, looked up by registration.
+//!
+//! Every airplane is the empty aircraft of its profile. Registrations match regardless of case
+//! and hyphens, so `PH-DHA` finds the airplane registered as `PHDHA`.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::error::WeightBalanceError;
+use crate::invariants::{self, InvariantError};
+use crate::loading::{Loading, Manifest};
+use crate::weight_and_balance::{Airplane, Limits};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// The limits at the index of the tagged limits, `None` for the certified limits, contradict
+    /// each other.
+    InvalidLimits(Option<usize>),
+    Tank(WeightBalanceError),
+    Invariant(InvariantError),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::InvalidLimits(None) => write!(f, "invalid limits"),
+            ValidationError::InvalidLimits(Some(index)) => {
+                write!(f, "invalid tagged limits {}", index)
+            }
+            ValidationError::Tank(e) => write!(f, "{}", e),
+            ValidationError::Invariant(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl core::error::Error for ValidationError {}
+
+fn key(registration: &str) -> String {
+    registration
+        .chars()
+        .filter(|c| *c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+fn consistent(limits: &Limits) -> bool {
+    limits.minimum_weight().kilo() <= limits.mtow().kilo()
+        && limits.forward_cg_limit().meter() <= limits.rearward_cg_limit().meter()
+}
+
+/// Every problem with the profile of `plane`.
+pub fn validate(plane: &Airplane) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    if !consistent(plane.limits()) {
+        errors.push(ValidationError::InvalidLimits(None));
+    }
+    for (i, limits) in plane.tagged_limits().iter().enumerate() {
+        if !consistent(limits) {
+            errors.push(ValidationError::InvalidLimits(Some(i)));
+        }
+    }
+    if let Err(e) = plane.check_tank_capacity() {
+        errors.push(ValidationError::Tank(e));
+    }
+    if let Err(e) = invariants::check(plane) {
+        errors.push(ValidationError::Invariant(e));
+    }
+    errors
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fleet {
+    airplanes: BTreeMap<String, Airplane>,
+}
+
+impl Fleet {
+    pub fn new() -> Fleet {
+        Fleet::default()
+    }
+
+    /// Adds `plane` under its registration, returns the airplane it replaces.
+    pub fn add(&mut self, plane: Airplane) -> Option<Airplane> {
+        self.airplanes
+            .insert(key(plane.registration().compact()), plane)
+    }
+
+    pub fn remove(&mut self, registration: &str) -> Option<Airplane> {
+        self.airplanes.remove(&key(registration))
+    }
+
+    pub fn get(&self, registration: &str) -> Option<&Airplane> {
+        self.airplanes.get(&key(registration))
+    }
+
+    /// The airplanes by registration.
+    pub fn iter(&self) -> impl Iterator<Item = &Airplane> {
+        self.airplanes.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.airplanes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.airplanes.is_empty()
+    }
+
+    /// The registrations of the airplanes with problems and what they are, see [`validate`].
+    pub fn validate(&self) -> BTreeMap<String, Vec<ValidationError>> {
+        self.iter()
+            .map(|plane| (plane.registration().compact().into(), validate(plane)))
+            .filter(|(_, errors)| !errors.is_empty())
+            .collect()
+    }
+
+    /// The manifest of every airplane with `loading` on board, by registration.
+    pub fn manifests(&self, loading: &Loading) -> Vec<Manifest> {
+        self.iter()
+            .map(|plane| Manifest::new(plane.clone(), loading.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::loading::Occupant;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{CenterOfGravity, LeverArm, Mass, Moment, Volume};
+    use alloc::string::ToString;
+
+    fn airplane(registration: &str, forward_cg: f64) -> Airplane {
+        Airplane::new(
+            Registration::parse(registration).unwrap(),
+            alloc::vec![Moment::new(
+                "Empty".to_string(),
+                LeverArm::Meter(0.4294),
+                Mass::Kilo(517.0),
+            )],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Meter(forward_cg),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(17.0),
+        )
+    }
+
+    #[test]
+    fn lookup_by_registration() {
+        let mut fleet = Fleet::new();
+        assert!(fleet.add(airplane("PHDHA", 0.427)).is_none());
+        fleet.add(airplane("PH-ABC", 0.427));
+        assert!(fleet.add(airplane("phdha", 0.43)).is_some());
+
+        assert_eq!(2, fleet.len());
+        assert_eq!(airplane("phdha", 0.43), *fleet.get("PH-DHA").unwrap());
+        assert!(fleet.get("PHXYZ").is_none());
+        assert!(fleet.remove("phabc").is_some());
+        assert_eq!(1, fleet.len());
+    }
+
+    #[test]
+    fn validate_and_report_every_airplane() {
+        let mut fleet = Fleet::new();
+        fleet.add(airplane("PHDHA", 0.427));
+        fleet.add(airplane("PHABC", 0.6));
+
+        let problems = fleet.validate();
+        assert_eq!(1, problems.len());
+        assert_eq!(
+            alloc::vec![ValidationError::InvalidLimits(None)],
+            problems["PHABC"]
+        );
+
+        let mut loading = Loading::new();
+        loading.add_occupant(Occupant::new(
+            "J. Doe".to_string(),
+            "Pilot".to_string(),
+            LeverArm::Meter(0.515),
+            Mass::Kilo(80.0),
+        ));
+        let manifests = fleet.manifests(&loading);
+        assert_eq!(
+            alloc::vec!["PHABC", "PHDHA"],
+            manifests
+                .iter()
+                .map(|m| m.airplane().registration().compact())
+                .collect::<Vec<&str>>()
+        );
+        assert_eq!(597.0, manifests[0].summary().takeoff_mass().kilo());
+    }
+}
@@ -0,0 +1,108 @@
+//! CGs in the frames of maintenance records and POHs besides meters aft of datum.
+//!
+//! Transport category and many older POHs give the CG in percent of the mean aerodynamic chord
+//! or as the distance aft of its leading edge, [`Mac`], and American manuals use station numbers
+//! in inches from a station zero that needn't be the datum, [`Stations`].
+
+use crate::weight_and_balance::{CenterOfGravity, LeverArm};
+
+/// The mean aerodynamic chord with its leading edge (LEMAC) aft of datum.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mac {
+    leading_edge: LeverArm,
+    length_m: f64,
+}
+
+impl Mac {
+    pub fn new(leading_edge: LeverArm, length_m: f64) -> Mac {
+        Mac {
+            leading_edge,
+            length_m,
+        }
+    }
+
+    pub fn leading_edge(&self) -> &LeverArm {
+        &self.leading_edge
+    }
+
+    pub fn length_m(&self) -> f64 {
+        self.length_m
+    }
+
+    /// The distance of `cg` aft of the leading edge, negative in front of it.
+    pub fn aft_of_leading_edge(&self, cg: &CenterOfGravity) -> CenterOfGravity {
+        CenterOfGravity::Meter(cg.meter() - self.leading_edge.meter())
+    }
+
+    pub fn percent(&self, cg: &CenterOfGravity) -> f64 {
+        self.aft_of_leading_edge(cg).meter() / self.length_m * 100.0
+    }
+
+    /// The CG aft of datum at `percent` MAC.
+    pub fn center_of_gravity(&self, percent: f64) -> CenterOfGravity {
+        CenterOfGravity::Meter(self.leading_edge.meter() + percent / 100.0 * self.length_m)
+    }
+}
+
+/// Station numbers in inches aft of station zero.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stations {
+    zero: LeverArm,
+}
+
+impl Stations {
+    /// Station zero at `zero` aft of datum.
+    pub fn new(zero: LeverArm) -> Stations {
+        Stations { zero }
+    }
+
+    /// Station zero at the datum, the station is the arm in inches.
+    pub fn datum() -> Stations {
+        Stations::new(LeverArm::Meter(0.0))
+    }
+
+    pub fn zero(&self) -> &LeverArm {
+        &self.zero
+    }
+
+    pub fn station(&self, cg: &CenterOfGravity) -> f64 {
+        cg.inch() - self.zero.inch()
+    }
+
+    pub fn center_of_gravity(&self, station: f64) -> CenterOfGravity {
+        CenterOfGravity::Meter(self.lever_arm(station).meter())
+    }
+
+    /// The arm of an item at `station`, for stations from weighing reports and equipment lists.
+    pub fn lever_arm(&self, station: f64) -> LeverArm {
+        LeverArm::from_inch(self.zero.inch() + station)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn percent_mac() {
+        let mac = Mac::new(LeverArm::Meter(0.2), 1.2);
+        let cg = CenterOfGravity::Millimeter(500.0);
+
+        assert!((mac.aft_of_leading_edge(&cg).meter() - 0.3).abs() < 1e-9);
+        assert!((mac.percent(&cg) - 25.0).abs() < 1e-9);
+        assert!((mac.center_of_gravity(25.0).meter() - 0.5).abs() < 1e-9);
+        assert!(mac.percent(&CenterOfGravity::Meter(0.1)) < 0.0);
+    }
+
+    #[test]
+    fn station_numbers() {
+        let stations = Stations::new(LeverArm::from_inch(-20.0));
+        let cg = CenterOfGravity::Meter(1.0);
+
+        assert!((stations.station(&cg) - (1.0 / 0.0254 + 20.0)).abs() < 1e-9);
+        assert!((stations.center_of_gravity(stations.station(&cg)).meter() - 1.0).abs() < 1e-9);
+        assert!((Stations::datum().lever_arm(40.0).inch() - 40.0).abs() < 1e-9);
+    }
+}
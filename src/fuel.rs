@@ -0,0 +1,182 @@
+//! The fuel of a flight in one block, for the fuel section of apps and reports.
+//!
+//! Every figure is given in liters, US gallons, kilograms and hours of endurance at the burn
+//! rate. Kilograms are at the density of the fuel on board, of avgas without fuel.
+
+use alloc::vec::Vec;
+
+use crate::weight_and_balance::{Airplane, Mass, Volume};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FuelFigure {
+    liter: f64,
+    gallon: f64,
+    kilo: f64,
+    endurance_h: f64,
+}
+
+impl FuelFigure {
+    fn new(liter: f64, kg_per_liter: f64, burn_rate: &Volume) -> FuelFigure {
+        let volume = Volume::Liter(liter);
+        FuelFigure {
+            liter,
+            gallon: volume.to_gallon(),
+            kilo: liter * kg_per_liter,
+            endurance_h: liter / burn_rate.to_liter(),
+        }
+    }
+
+    pub fn liter(&self) -> f64 {
+        self.liter
+    }
+
+    pub fn gallon(&self) -> f64 {
+        self.gallon
+    }
+
+    pub fn kilo(&self) -> f64 {
+        self.kilo
+    }
+
+    /// Hours at the burn rate of the summary.
+    pub fn endurance_h(&self) -> f64 {
+        self.endurance_h
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FuelSummary {
+    on_board: FuelFigure,
+    usable: FuelFigure,
+    taxi: FuelFigure,
+    trip: FuelFigure,
+    reserve: FuelFigure,
+    landing: FuelFigure,
+}
+
+impl FuelSummary {
+    /// Every fuel moment of the airplane.
+    pub fn on_board(&self) -> &FuelFigure {
+        &self.on_board
+    }
+
+    /// The fuel on board less the unusable fuel of the tanks with the names of the fuel moments.
+    pub fn usable(&self) -> &FuelFigure {
+        &self.usable
+    }
+
+    pub fn taxi(&self) -> &FuelFigure {
+        &self.taxi
+    }
+
+    /// The trip fuel of the airplane, or with a [`Airplane::landing_fuel`] the fuel on board less
+    /// the taxi and landing fuel.
+    pub fn trip(&self) -> &FuelFigure {
+        &self.trip
+    }
+
+    pub fn reserve(&self) -> &FuelFigure {
+        &self.reserve
+    }
+
+    /// The usable fuel less the taxi and trip fuel, negative when it does not suffice.
+    pub fn landing(&self) -> &FuelFigure {
+        &self.landing
+    }
+
+    /// Whether the fuel at landing is at least the reserve.
+    pub fn reserve_met(&self) -> bool {
+        self.landing.liter >= self.reserve.liter
+    }
+}
+
+impl Airplane {
+    /// The fuel figures with `taxi` fuel burned before take-off and `reserve` to be left at
+    /// landing, the endurance at `burn_rate` per hour.
+    pub fn fuel_summary(&self, burn_rate: &Volume, taxi: &Volume, reserve: &Volume) -> FuelSummary {
+        let fuel: Vec<(f64, f64, f64)> = self
+            .iter()
+            .filter_map(|m| {
+                let liter = m.mass().volume()?.to_liter();
+                let unusable = self.tank(m.name()).map_or(0.0, |t| t.unusable().to_liter());
+                Some((liter, (liter - unusable).max(0.0), m.mass().kilo()))
+            })
+            .collect();
+
+        let on_board: f64 = fuel.iter().map(|f| f.0).sum();
+        let usable: f64 = fuel.iter().map(|f| f.1).sum();
+        let kg_per_liter = match on_board > 0.0 {
+            true => fuel.iter().map(|f| f.2).sum::<f64>() / on_board,
+            false => Mass::Avgas(Volume::Liter(1.0)).kilo(),
+        };
+        let figure = |liter: f64| FuelFigure::new(liter, kg_per_liter, burn_rate);
+        let trip = match self.landing_fuel() {
+            Some(landing) => on_board - taxi.to_liter() - landing.to_liter(),
+            None => self.fuel_consumption_trip().to_liter(),
+        };
+
+        FuelSummary {
+            on_board: figure(on_board),
+            usable: figure(usable),
+            taxi: figure(taxi.to_liter()),
+            trip: figure(trip),
+            reserve: figure(reserve.to_liter()),
+            landing: figure(usable - taxi.to_liter() - trip),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{CenterOfGravity, LeverArm, Limits, Moment, Tank};
+    use alloc::string::ToString;
+
+    #[test]
+    fn fuel_figures() {
+        let mut plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            alloc::vec![
+                Moment::new(
+                    "Empty".to_string(),
+                    LeverArm::Meter(0.4294),
+                    Mass::Kilo(517.0),
+                ),
+                Moment::new(
+                    "Fuel".to_string(),
+                    LeverArm::Meter(0.325),
+                    Mass::Avgas(Volume::Liter(60.0)),
+                ),
+            ],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(30.0),
+        );
+        plane.add_tank(Tank::new(
+            "Fuel".to_string(),
+            Volume::Liter(109.0),
+            Volume::Liter(113.0),
+        ));
+
+        let (burn_rate, taxi) = (Volume::Liter(20.0), Volume::Liter(2.0));
+        let summary = plane.fuel_summary(&burn_rate, &taxi, &Volume::Liter(15.0));
+        assert_eq!(60.0, summary.on_board().liter());
+        assert!((summary.on_board().kilo() - 43.2).abs() < 1e-9);
+        assert!((summary.on_board().gallon() - 60.0 / 3.78541).abs() < 1e-9);
+        assert_eq!(56.0, summary.usable().liter());
+        assert_eq!(24.0, summary.landing().liter());
+        assert_eq!(1.2, summary.landing().endurance_h());
+        assert_eq!(0.75, summary.reserve().endurance_h());
+        assert!(summary.reserve_met());
+
+        let short = plane.fuel_summary(&burn_rate, &taxi, &Volume::Liter(30.0));
+        assert!(!short.reserve_met());
+    }
+}
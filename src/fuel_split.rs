@@ -0,0 +1,214 @@
+//! Splits the fuel for a flight over the tanks of aircraft with tip or auxiliary tanks.
+//!
+//! The tanks are burned empty one after the other in the order they are given. Within a tank the
+//! CG moves steadily in one direction, so the CG is furthest from the middle of the CG limits
+//! either before the burn or when a tank runs empty, and only those points are compared.
+
+use alloc::vec::Vec;
+
+use crate::types::FuelType;
+use crate::weight_and_balance::{Airplane, CenterOfGravity, LeverArm, Mass, Moment, Tank, Volume};
+
+// The total fuel is split in steps of this fraction
+const STEPS: usize = 50;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FuelSplit {
+    moments: Vec<Moment>,
+    largest_cg_offset: CenterOfGravity,
+}
+
+impl FuelSplit {
+    /// The fuel of every tank, in the order and with the names of the tanks.
+    pub fn moments(&self) -> &Vec<Moment> {
+        &self.moments
+    }
+
+    /// The largest distance of the CG from the middle of the CG limits during the burn.
+    pub fn largest_cg_offset(&self) -> &CenterOfGravity {
+        &self.largest_cg_offset
+    }
+}
+
+struct Search {
+    plane_mass: f64,
+    plane_moment: f64,
+    center: f64,
+    arms: Vec<f64>,
+    capacities: Vec<f64>,
+    density: f64,
+    step: f64,
+    best: Option<(f64, Vec<f64>)>,
+}
+
+impl Search {
+    // The largest CG offset from the center with `liters` in every tank, burned in order
+    fn offset(&self, liters: &[f64]) -> f64 {
+        let (mut mass, mut moment) = liters.iter().zip(&self.arms).fold(
+            (self.plane_mass, self.plane_moment),
+            |(mass, moment), (l, arm)| (mass + l * self.density, moment + l * self.density * arm),
+        );
+
+        let mut largest = (moment / mass - self.center).abs();
+        for (l, arm) in liters.iter().zip(&self.arms) {
+            mass -= l * self.density;
+            moment -= l * self.density * arm;
+            largest = largest.max((moment / mass - self.center).abs());
+        }
+        largest
+    }
+
+    fn split(&mut self, liters: &mut Vec<f64>, remaining: f64) {
+        let tank = liters.len();
+        if tank + 1 == self.arms.len() {
+            if remaining > self.capacities[tank] + 1e-9 {
+                return;
+            }
+            liters.push(remaining.max(0.0));
+            let offset = self.offset(liters);
+            if self.best.as_ref().is_none_or(|(best, _)| offset < *best) {
+                self.best = Some((offset, liters.clone()));
+            }
+            liters.pop();
+            return;
+        }
+
+        // The steps and a full tank, which needn't be a whole number of steps
+        let full = self.capacities[tank].min(remaining);
+        let steps = (0..=STEPS)
+            .map(|step| step as f64 * self.step)
+            .take_while(|l| *l < full)
+            .chain([full]);
+        for l in steps.collect::<Vec<f64>>() {
+            liters.push(l);
+            self.split(liters, remaining - l);
+            liters.pop();
+        }
+    }
+}
+
+/// The split of `total` fuel over `tanks`, each with its arm, that keeps the CG of `plane`
+/// without fuel closest to the middle of the CG limits from take-off until the last tank is
+/// empty. The volumes are in the unit of `total`. `None` without tanks or when the tanks cannot
+/// hold `total`.
+pub fn split_fuel(
+    plane: &Airplane,
+    tanks: &[(Tank, LeverArm)],
+    fuel: FuelType,
+    total: Volume,
+) -> Option<FuelSplit> {
+    let capacity: f64 = tanks.iter().map(|(t, _)| t.usable().to_liter()).sum();
+    if tanks.is_empty() || total.to_liter() > capacity + 1e-9 {
+        return None;
+    }
+
+    let limits = plane.limits();
+    let mut search = Search {
+        plane_mass: plane.total_mass().kilo(),
+        plane_moment: plane.total_mass_moment().kgm(),
+        center: (limits.forward_cg_limit().meter() + limits.rearward_cg_limit().meter()) / 2.0,
+        arms: tanks.iter().map(|(_, arm)| arm.meter()).collect(),
+        capacities: tanks.iter().map(|(t, _)| t.usable().to_liter()).collect(),
+        density: match fuel {
+            FuelType::Avgas => Mass::Avgas(Volume::Liter(1.0)).kilo(),
+            FuelType::Mogas => Mass::Mogas(Volume::Liter(1.0)).kilo(),
+        },
+        step: total.to_liter() / STEPS as f64,
+        best: None,
+    };
+    search.split(&mut Vec::new(), total.to_liter());
+
+    let (offset, liters) = search.best?;
+    let moments = tanks
+        .iter()
+        .zip(liters)
+        .map(|((tank, arm), l)| {
+            let volume = match total {
+                Volume::Liter(_) => Volume::Liter(l),
+                Volume::Gallon(_) => Volume::Gallon(Volume::Liter(l).to_gallon()),
+            };
+            let mass = match fuel {
+                FuelType::Avgas => Mass::Avgas(volume),
+                FuelType::Mogas => Mass::Mogas(volume),
+            };
+            Moment::new(tank.name().into(), arm.clone(), mass)
+        })
+        .collect();
+
+    Some(FuelSplit {
+        moments,
+        largest_cg_offset: CenterOfGravity::Meter(offset),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::Limits;
+    use alloc::string::ToString;
+
+    fn airplane() -> Airplane {
+        Airplane::new(
+            Registration::parse("PHABC").unwrap(),
+            alloc::vec![Moment::new(
+                "Empty".to_string(),
+                LeverArm::Meter(2.3),
+                Mass::Kilo(800.0),
+            )],
+            Limits::new(
+                Mass::Kilo(800.0),
+                Mass::Kilo(1200.0),
+                CenterOfGravity::Meter(2.1),
+                CenterOfGravity::Meter(2.4),
+            ),
+            Volume::Liter(30.0),
+        )
+    }
+
+    fn tank(name: &str, liter: f64, arm: f64) -> (Tank, LeverArm) {
+        (
+            Tank::new(name.to_string(), Volume::Liter(liter), Volume::Liter(liter)),
+            LeverArm::Meter(arm),
+        )
+    }
+
+    #[test]
+    fn split_keeps_cg_closest_to_center() {
+        let tanks = [tank("Aux", 60.0, 2.8), tank("Main", 100.0, 1.9)];
+        let split = split_fuel(&airplane(), &tanks, FuelType::Avgas, Volume::Liter(100.0)).unwrap();
+
+        let liters: Vec<f64> = split
+            .moments()
+            .iter()
+            .map(|m| m.mass().volume().unwrap().to_liter())
+            .collect();
+        assert!((liters.iter().sum::<f64>() - 100.0).abs() < 1e-9);
+        assert!(liters[0] <= 60.0 + 1e-9);
+
+        let main_only = split_fuel(
+            &airplane(),
+            &tanks[1..],
+            FuelType::Avgas,
+            Volume::Liter(100.0),
+        )
+        .unwrap();
+        assert!(split.largest_cg_offset().meter() <= main_only.largest_cg_offset().meter() + 1e-12);
+    }
+
+    #[test]
+    fn full_tanks_and_too_much_fuel() {
+        let tanks = [tank("Left", 25.5, 2.0), tank("Right", 25.5, 2.0)];
+
+        assert!(split_fuel(&airplane(), &tanks, FuelType::Mogas, Volume::Liter(51.0)).is_some());
+        assert_eq!(
+            None,
+            split_fuel(&airplane(), &tanks, FuelType::Mogas, Volume::Liter(52.0))
+        );
+        assert_eq!(
+            None,
+            split_fuel(&airplane(), &[], FuelType::Mogas, Volume::Liter(1.0))
+        );
+    }
+}
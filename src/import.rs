@@ -0,0 +1,409 @@
+//! Importers for weight and balance profiles exported from ForeFlight and Garmin Pilot.
+//!
+//! Neither format is publicly specified. The importers read the fields listed on
+//! [`foreflight`] and [`garmin_pilot`] and ignore everything else, so check the result against a
+//! real export of your profile before relying on it.
+//!
+//! Both apps describe the CG envelope as a polygon while [`Limits`] is a rectangle. The imported
+//! limits are the largest rectangle within the polygon's forward and aft edges: the most aft
+//! point of the forward edge and the most forward point of the aft edge, between the lowest and
+//! highest envelope weight (or the given maximum takeoff weight).
+
+use std::fmt;
+
+use serde::Deserialize;
+
+use crate::registration::{Registration, RegistrationError};
+use crate::types::FuelType;
+use crate::weight_and_balance::{
+    Airplane, CenterOfGravity, LeverArm, Limits, Mass, Moment, Volume,
+};
+use crate::xml;
+
+#[derive(Debug)]
+pub enum ImportError {
+    Json(serde_json::Error),
+    Xml(String),
+    MissingField(&'static str),
+    InvalidNumber(String),
+    UnsupportedFuel(String),
+    InvalidRegistration(RegistrationError),
+    /// The envelope needs at least three points.
+    InvalidEnvelope,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Json(e) => write!(f, "invalid JSON profile: {}", e),
+            ImportError::Xml(e) => write!(f, "invalid XML profile: {}", e),
+            ImportError::MissingField(field) => write!(f, "missing field {}", field),
+            ImportError::InvalidNumber(value) => write!(f, "invalid number {}", value),
+            ImportError::UnsupportedFuel(fuel) => write!(f, "unsupported fuel type {}", fuel),
+            ImportError::InvalidRegistration(e) => write!(f, "{}", e),
+            ImportError::InvalidEnvelope => write!(f, "envelope needs at least three points"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Units {
+    /// Pounds, inches and US gallons.
+    Imperial,
+    /// Kilograms, meters and liters.
+    Metric,
+}
+
+impl Units {
+    fn mass(&self, value: f64) -> Mass {
+        match self {
+            Units::Imperial => Mass::from_pound(value),
+            Units::Metric => Mass::Kilo(value),
+        }
+    }
+
+    fn arm(&self, value: f64) -> LeverArm {
+        match self {
+            Units::Imperial => LeverArm::from_inch(value),
+            Units::Metric => LeverArm::Meter(value),
+        }
+    }
+
+    fn volume(&self, value: f64) -> Volume {
+        match self {
+            Units::Imperial => Volume::Gallon(value),
+            Units::Metric => Volume::Liter(value),
+        }
+    }
+}
+
+fn fuel_type(fuel: &str) -> Result<FuelType, ImportError> {
+    match fuel.to_ascii_lowercase().as_str() {
+        "100ll" | "avgas" | "100ul" => Ok(FuelType::Avgas),
+        "mogas" | "ul91" | "mogas/ul91" => Ok(FuelType::Mogas),
+        _ => Err(ImportError::UnsupportedFuel(fuel.to_string())),
+    }
+}
+
+/// Common shape both formats are mapped onto before building the airplane.
+struct ImportedProfile {
+    registration: String,
+    units: Units,
+    empty_weight: f64,
+    empty_arm: f64,
+    max_takeoff_weight: Option<f64>,
+    stations: Vec<(String, f64, f64)>,
+    tanks: Vec<(String, f64, FuelType)>,
+    envelope: Vec<(f64, f64)>,
+}
+
+impl ImportedProfile {
+    fn airplane(self) -> Result<Airplane, ImportError> {
+        if self.envelope.len() < 3 {
+            return Err(ImportError::InvalidEnvelope);
+        }
+        let registration =
+            Registration::parse(&self.registration).map_err(ImportError::InvalidRegistration)?;
+
+        let units = self.units;
+        let min_arm = self.envelope.iter().map(|p| p.1).fold(f64::MAX, f64::min);
+        let max_arm = self.envelope.iter().map(|p| p.1).fold(f64::MIN, f64::max);
+        let middle = (min_arm + max_arm) / 2.0;
+
+        let forward = self
+            .envelope
+            .iter()
+            .filter(|p| p.1 <= middle)
+            .map(|p| p.1)
+            .fold(f64::MIN, f64::max);
+        let rearward = self
+            .envelope
+            .iter()
+            .filter(|p| p.1 > middle)
+            .map(|p| p.1)
+            .fold(f64::MAX, f64::min);
+        let minimum_weight = self.envelope.iter().map(|p| p.0).fold(f64::MAX, f64::min);
+        let mtow = self
+            .max_takeoff_weight
+            .unwrap_or_else(|| self.envelope.iter().map(|p| p.0).fold(f64::MIN, f64::max));
+
+        let mut moments = vec![Moment::new(
+            "Empty aircraft".to_string(),
+            units.arm(self.empty_arm),
+            units.mass(self.empty_weight),
+        )];
+        moments.extend(
+            self.stations
+                .into_iter()
+                .map(|(name, arm, weight)| Moment::new(name, units.arm(arm), units.mass(weight))),
+        );
+        moments.extend(self.tanks.into_iter().map(|(name, arm, fuel)| {
+            let empty = units.volume(0.0);
+            let mass = match fuel {
+                FuelType::Avgas => Mass::Avgas(empty),
+                FuelType::Mogas => Mass::Mogas(empty),
+            };
+            Moment::new(name, units.arm(arm), mass)
+        }));
+
+        Ok(Airplane::new(
+            registration,
+            moments,
+            Limits::new(
+                units.mass(minimum_weight),
+                units.mass(mtow),
+                CenterOfGravity::Meter(units.arm(forward).meter()),
+                CenterOfGravity::Meter(units.arm(rearward).meter()),
+            ),
+            units.volume(0.0),
+        ))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ForeFlightProfile {
+    tail_number: String,
+    units: Units,
+    empty_weight: f64,
+    empty_arm: f64,
+    max_takeoff_weight: Option<f64>,
+    #[serde(default)]
+    stations: Vec<ForeFlightStation>,
+    #[serde(default)]
+    fuel_tanks: Vec<ForeFlightTank>,
+    envelope: Vec<ForeFlightEnvelopePoint>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ForeFlightStation {
+    name: String,
+    arm: f64,
+    #[serde(default)]
+    default_weight: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ForeFlightTank {
+    name: String,
+    arm: f64,
+    fuel_type: String,
+}
+
+#[derive(Deserialize)]
+struct ForeFlightEnvelopePoint {
+    weight: f64,
+    arm: f64,
+}
+
+/// Reads a ForeFlight JSON profile with the fields `tailNumber`, `units` (`imperial` or
+/// `metric`), `emptyWeight`, `emptyArm`, optional `maxTakeoffWeight`, `stations` (`name`, `arm`,
+/// optional `defaultWeight`), `fuelTanks` (`name`, `arm`, `fuelType`) and `envelope` (`weight`,
+/// `arm`). Tanks are imported empty.
+pub fn foreflight(json: &str) -> Result<Airplane, ImportError> {
+    let profile: ForeFlightProfile = serde_json::from_str(json).map_err(ImportError::Json)?;
+
+    ImportedProfile {
+        registration: profile.tail_number,
+        units: profile.units,
+        empty_weight: profile.empty_weight,
+        empty_arm: profile.empty_arm,
+        max_takeoff_weight: profile.max_takeoff_weight,
+        stations: profile
+            .stations
+            .into_iter()
+            .map(|s| (s.name, s.arm, s.default_weight))
+            .collect(),
+        tanks: profile
+            .fuel_tanks
+            .into_iter()
+            .map(|t| Ok((t.name, t.arm, fuel_type(&t.fuel_type)?)))
+            .collect::<Result<_, ImportError>>()?,
+        envelope: profile
+            .envelope
+            .into_iter()
+            .map(|p| (p.weight, p.arm))
+            .collect(),
+    }
+    .airplane()
+}
+
+fn attribute<'a>(element: &'a xml::Element, name: &'static str) -> Result<&'a str, ImportError> {
+    element
+        .attribute(name)
+        .ok_or(ImportError::MissingField(name))
+}
+
+fn number(element: &xml::Element, name: &'static str) -> Result<f64, ImportError> {
+    let value = attribute(element, name)?;
+    value
+        .trim()
+        .parse()
+        .map_err(|_| ImportError::InvalidNumber(value.to_string()))
+}
+
+/// Reads a Garmin Pilot XML profile: a `WeightAndBalanceProfile` element with `tailNumber` and
+/// `units` attributes, containing `EmptyWeight` (`weight`, `arm`), optional `MaxTakeoffWeight`
+/// (`weight`), `Station` (`name`, `arm`, optional `weight`), `FuelTank` (`name`, `arm`,
+/// `fuelType`) and `EnvelopePoint` (`weight`, `arm`) elements. Tanks are imported empty.
+pub fn garmin_pilot(xml: &str) -> Result<Airplane, ImportError> {
+    let elements = xml::elements(xml).map_err(ImportError::Xml)?;
+
+    let root = elements
+        .iter()
+        .find(|e| e.name == "WeightAndBalanceProfile")
+        .ok_or(ImportError::MissingField("WeightAndBalanceProfile"))?;
+    let units = match attribute(root, "units")? {
+        "metric" => Units::Metric,
+        "imperial" => Units::Imperial,
+        other => return Err(ImportError::Xml(format!("unknown units {}", other))),
+    };
+    let empty = elements
+        .iter()
+        .find(|e| e.name == "EmptyWeight")
+        .ok_or(ImportError::MissingField("EmptyWeight"))?;
+
+    let mut profile = ImportedProfile {
+        registration: attribute(root, "tailNumber")?.to_string(),
+        units,
+        empty_weight: number(empty, "weight")?,
+        empty_arm: number(empty, "arm")?,
+        max_takeoff_weight: None,
+        stations: vec![],
+        tanks: vec![],
+        envelope: vec![],
+    };
+
+    for e in elements.iter() {
+        match e.name.as_str() {
+            "MaxTakeoffWeight" => profile.max_takeoff_weight = Some(number(e, "weight")?),
+            "Station" => profile.stations.push((
+                attribute(e, "name")?.to_string(),
+                number(e, "arm")?,
+                if e.attribute("weight").is_some() {
+                    number(e, "weight")?
+                } else {
+                    0.0
+                },
+            )),
+            "FuelTank" => profile.tanks.push((
+                attribute(e, "name")?.to_string(),
+                number(e, "arm")?,
+                fuel_type(attribute(e, "fuelType")?)?,
+            )),
+            "EnvelopePoint" => profile
+                .envelope
+                .push((number(e, "weight")?, number(e, "arm")?)),
+            _ => {}
+        }
+    }
+
+    profile.airplane()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_close(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < 1e-9,
+            "{} != {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn foreflight_json_profile() {
+        let plane = foreflight(
+            r#"{
+                "tailNumber": "N12345",
+                "units": "imperial",
+                "emptyWeight": 1680.0,
+                "emptyArm": 39.8,
+                "maxTakeoffWeight": 2550.0,
+                "stations": [{"name": "Front Seats", "arm": 37.0, "defaultWeight": 170.0}],
+                "fuelTanks": [{"name": "Main", "arm": 48.0, "fuelType": "100LL"}],
+                "envelope": [
+                    {"weight": 1500.0, "arm": 35.0},
+                    {"weight": 1950.0, "arm": 35.0},
+                    {"weight": 2550.0, "arm": 41.0},
+                    {"weight": 2550.0, "arm": 47.3},
+                    {"weight": 1500.0, "arm": 47.3}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!("N12345", plane.registration().to_string());
+        assert_eq!(3, plane.len());
+        assert_close(
+            0.0254 * 37.0,
+            plane.iter().nth(1).unwrap().lever_arm().meter(),
+        );
+        assert!(plane.iter().nth(2).unwrap().mass().is_fuel());
+        assert_close(2550.0 * 0.45359237, plane.limits().mtow().kilo());
+        assert_close(0.0254 * 41.0, plane.limits().forward_cg_limit().meter());
+        assert_close(0.0254 * 47.3, plane.limits().rearward_cg_limit().meter());
+    }
+
+    #[test]
+    fn garmin_pilot_xml_profile() {
+        let plane = garmin_pilot(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<WeightAndBalanceProfile tailNumber="PH-DHA" units="metric">
+  <EmptyWeight weight="517" arm="0.4294"/>
+  <Station name="Pilot" arm="0.515"/>
+  <FuelTank name="Main" arm="0.325" fuelType="Mogas"/>
+  <EnvelopePoint weight="558" arm="0.427"/>
+  <EnvelopePoint weight="750" arm="0.427"/>
+  <EnvelopePoint weight="750" arm="0.523"/>
+  <EnvelopePoint weight="558" arm="0.523"/>
+</WeightAndBalanceProfile>"#,
+        )
+        .unwrap();
+
+        assert_eq!("PH-DHA", plane.registration().to_string());
+        assert_close(750.0, plane.limits().mtow().kilo());
+        assert_close(558.0, plane.limits().minimum_weight().kilo());
+        assert!(matches!(
+            plane.iter().nth(2).unwrap().mass(),
+            Mass::Mogas(_)
+        ));
+        assert!(plane.within_limits());
+    }
+
+    #[test]
+    fn invalid_profiles() {
+        assert!(matches!(
+            garmin_pilot(r#"<WeightAndBalanceProfile tailNumber="X" units="metric"/>"#),
+            Err(ImportError::MissingField("EmptyWeight"))
+        ));
+        assert!(matches!(
+            garmin_pilot(
+                r#"<WeightAndBalanceProfile tailNumber="X" units="metric">
+                <EmptyWeight weight="500" arm="0.4"/>
+                <EnvelopePoint weight="500" arm="0.4"/>
+                </WeightAndBalanceProfile>"#
+            ),
+            Err(ImportError::InvalidEnvelope)
+        ));
+        assert!(matches!(
+            garmin_pilot(
+                r#"<WeightAndBalanceProfile tailNumber="X" units="metric">
+                <EmptyWeight weight="500" arm="0.4"/>
+                <EnvelopePoint weight="500" arm="0.4"/>
+                <EnvelopePoint weight="750" arm="0.4"/>
+                <EnvelopePoint weight="750" arm="0.5"/>
+                </WeightAndBalanceProfile>"#
+            ),
+            Err(ImportError::InvalidRegistration(_))
+        ));
+    }
+}
@@ -0,0 +1,435 @@
+//! Content hashes and signatures of aircraft profiles, so a club can tell whether a member's app
+//! still uses the current empty weight record.
+//!
+//! The hash covers a canonical form of every value of the airplane that the figures depend on:
+//! callsign, every moment's name, arm in meters and mass in kilograms, the limits with their kind
+//! and hot-and-high MTOWs, the tanks, the trip, diversion and landing fuel in liters, the fuel
+//! burn order, the limit overrides and the warning thresholds. Entering the same values in other
+//! units (millimeters, gallons) therefore gives the same hash. The signature is an HMAC-SHA256 of
+//! the canonical form with a key shared between the club and its members.
+//!
+//! Hashes and signatures saved before the tanks, tagged limits and the other fuel figures were
+//! covered are [`HashVersion::V1`], which only covers the callsign, the moments, the limits and
+//! the trip fuel. Both versions give the same hash for a profile without those values.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use hmac::{Hmac, KeyInit, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::weight_and_balance::{Airplane, FuelBurn, LimitKind, Limits, WarningThresholds};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProfileHash([u8; 32]);
+
+/// The values a hash covers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HashVersion {
+    /// The callsign, the moments, the limits with their hot-and-high MTOWs and the trip fuel.
+    V1,
+    /// Every value the figures depend on.
+    #[default]
+    V2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProfileSignature([u8; 32]);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityError {
+    /// Hashes and signatures are 64 hexadecimal digits.
+    InvalidHex(String),
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityError::InvalidHex(hex) => write!(f, "invalid hash or signature {}", hex),
+        }
+    }
+}
+
+impl core::error::Error for IntegrityError {}
+
+fn to_hex(bytes: &[u8; 32], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for b in bytes {
+        write!(f, "{:02x}", b)?;
+    }
+    Ok(())
+}
+
+fn from_hex(hex: &str) -> Result<[u8; 32], IntegrityError> {
+    let invalid = || IntegrityError::InvalidHex(hex.to_string());
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+
+    let mut bytes = [0; 32];
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).map_err(|_| invalid())?;
+    }
+    Ok(bytes)
+}
+
+impl fmt::Display for ProfileHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        to_hex(&self.0, f)
+    }
+}
+
+impl ProfileHash {
+    pub fn from_hex(hex: &str) -> Result<ProfileHash, IntegrityError> {
+        from_hex(hex).map(ProfileHash)
+    }
+
+    pub fn bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl fmt::Display for ProfileSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        to_hex(&self.0, f)
+    }
+}
+
+impl ProfileSignature {
+    pub fn from_hex(hex: &str) -> Result<ProfileSignature, IntegrityError> {
+        from_hex(hex).map(ProfileSignature)
+    }
+
+    pub fn bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+fn push_limits(canonical: &mut String, prefix: &str, limits: &Limits) {
+    canonical.push_str(&format!(
+        "{}:{:?}:{:?}:{:?}:{:?}\n",
+        prefix,
+        limits.minimum_weight().kilo(),
+        limits.mtow().kilo(),
+        limits.forward_cg_limit().meter(),
+        limits.rearward_cg_limit().meter()
+    ));
+    for (altitude, mtow) in limits.altitude_mtows() {
+        canonical.push_str(&format!("altitude_mtow:{:?}:{:?}\n", altitude, mtow.kilo()));
+    }
+}
+
+// One field per line, floats in their shortest round-trip form. Values at their defaults are
+// left out, so profiles hashed before those values existed keep their hash.
+fn canonical(plane: &Airplane, version: HashVersion) -> String {
+    let mut canonical = format!(
+        "callsign:{}\n",
+        plane.registration().compact().escape_default()
+    );
+
+    for m in plane.iter() {
+        canonical.push_str(&format!(
+            "moment:{}:{:?}:{:?}:{}\n",
+            m.name().escape_default(),
+            m.lever_arm().meter(),
+            m.mass().kilo(),
+            m.mass().is_fuel()
+        ));
+    }
+
+    push_limits(&mut canonical, "limits", plane.limits());
+    if version == HashVersion::V1 {
+        canonical.push_str(&format!(
+            "trip_fuel:{:?}\n",
+            plane.fuel_consumption_trip().to_liter()
+        ));
+        return canonical;
+    }
+    if plane.limits().kind() != LimitKind::Structural {
+        canonical.push_str(&format!("limits_kind:{:?}\n", plane.limits().kind()));
+    }
+    for limits in plane.tagged_limits() {
+        push_limits(
+            &mut canonical,
+            &format!("tagged_limits:{:?}", limits.kind()),
+            limits,
+        );
+    }
+    for o in plane.limit_overrides() {
+        canonical.push_str(&format!(
+            "limit_override:{}:{}\n",
+            o.index(),
+            o.justification().escape_default()
+        ));
+        push_limits(&mut canonical, "original_limits", o.original());
+    }
+
+    for t in plane.tanks() {
+        canonical.push_str(&format!(
+            "tank:{}:{:?}:{:?}\n",
+            t.name().escape_default(),
+            t.usable().to_liter(),
+            t.total().to_liter()
+        ));
+    }
+    match plane.fuel_burn() {
+        FuelBurn::Last => {}
+        FuelBurn::Proportional => canonical.push_str("fuel_burn:proportional\n"),
+        FuelBurn::Sequence(tanks) => {
+            canonical.push_str("fuel_burn:sequence\n");
+            for tank in tanks {
+                canonical.push_str(&format!("fuel_burn_tank:{}\n", tank.escape_default()));
+            }
+        }
+    }
+
+    canonical.push_str(&format!(
+        "trip_fuel:{:?}\n",
+        plane.fuel_consumption_trip().to_liter()
+    ));
+    if let Some(diversion) = plane.fuel_consumption_diversion() {
+        canonical.push_str(&format!("diversion_fuel:{:?}\n", diversion.to_liter()));
+    }
+    if let Some(landing) = plane.landing_fuel() {
+        canonical.push_str(&format!("landing_fuel:{:?}\n", landing.to_liter()));
+    }
+
+    let thresholds = plane.warning_thresholds();
+    if *thresholds != WarningThresholds::default() {
+        canonical.push_str(&format!(
+            "warning_thresholds:{:?}:{:?}\n",
+            thresholds.mtow_fraction(),
+            thresholds.cg_margin().meter()
+        ));
+        if let Some((minutes, burn_rate)) = thresholds.reserve() {
+            canonical.push_str(&format!(
+                "warning_reserve:{:?}:{:?}\n",
+                minutes,
+                burn_rate.to_liter()
+            ));
+        }
+    }
+
+    canonical
+}
+
+fn mac(plane: &Airplane, key: &[u8], version: HashVersion) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(canonical(plane, version).as_bytes());
+    mac
+}
+
+impl Airplane {
+    pub fn profile_hash(&self) -> ProfileHash {
+        self.profile_hash_version(HashVersion::default())
+    }
+
+    pub fn profile_hash_version(&self, version: HashVersion) -> ProfileHash {
+        ProfileHash(Sha256::digest(canonical(self, version).as_bytes()).into())
+    }
+
+    pub fn sign_profile(&self, key: &[u8]) -> ProfileSignature {
+        self.sign_profile_version(key, HashVersion::default())
+    }
+
+    pub fn sign_profile_version(&self, key: &[u8], version: HashVersion) -> ProfileSignature {
+        ProfileSignature(mac(self, key, version).finalize().into_bytes().into())
+    }
+
+    /// Compares in constant time.
+    pub fn verify_profile(&self, key: &[u8], signature: &ProfileSignature) -> bool {
+        self.verify_profile_version(key, signature, HashVersion::default())
+    }
+
+    /// Verifies a signature of an earlier version, compared in constant time.
+    pub fn verify_profile_version(
+        &self,
+        key: &[u8],
+        signature: &ProfileSignature,
+        version: HashVersion,
+    ) -> bool {
+        mac(self, key, version)
+            .verify_slice(signature.bytes())
+            .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{CenterOfGravity, LeverArm, Mass, Moment, Tank, Volume};
+
+    fn airplane(empty_mass: f64, forward_cg_limit: CenterOfGravity) -> Airplane {
+        Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![Moment::new(
+                "Empty".to_string(),
+                LeverArm::Meter(0.4294),
+                Mass::Kilo(empty_mass),
+            )],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                forward_cg_limit,
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(17.0),
+        )
+    }
+
+    #[test]
+    fn hash_ignores_units_but_not_values() {
+        let plane = airplane(517.0, CenterOfGravity::Millimeter(427.0));
+
+        assert_eq!(
+            plane.profile_hash(),
+            airplane(517.0, CenterOfGravity::Meter(0.427)).profile_hash()
+        );
+        assert_ne!(
+            plane.profile_hash(),
+            airplane(517.5, CenterOfGravity::Millimeter(427.0)).profile_hash()
+        );
+
+        let hex = plane.profile_hash().to_string();
+        assert_eq!(64, hex.len());
+        assert_eq!(plane.profile_hash(), ProfileHash::from_hex(&hex).unwrap());
+        assert!(ProfileHash::from_hex("abc").is_err());
+    }
+
+    // `changed` has another hash and fails the signature of `plane`
+    fn assert_differs(plane: &Airplane, changed: &Airplane) {
+        let key = b"club key";
+        assert_ne!(plane.profile_hash(), changed.profile_hash());
+        assert!(!changed.verify_profile(key, &plane.sign_profile(key)));
+    }
+
+    fn with_tanks(mut plane: Airplane, left_usable: f64) -> Airplane {
+        plane.add_tank(Tank::new(
+            "Left".to_string(),
+            Volume::Liter(left_usable),
+            Volume::Liter(60.0),
+        ));
+        plane.add_tank(Tank::new(
+            "Right".to_string(),
+            Volume::Liter(55.0),
+            Volume::Liter(60.0),
+        ));
+        plane
+    }
+
+    fn with_limits(mut plane: Airplane, kind: LimitKind) -> Airplane {
+        plane.add_limits(
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(720.0),
+                CenterOfGravity::Millimeter(435.0),
+                CenterOfGravity::Millimeter(515.0),
+            )
+            .with_kind(kind),
+        );
+        plane
+    }
+
+    fn with_burn(plane: Airplane, tanks: [&str; 2]) -> Airplane {
+        plane.with_fuel_burn(FuelBurn::Sequence(
+            tanks.iter().map(|t| t.to_string()).collect(),
+        ))
+    }
+
+    #[test]
+    fn hash_covers_tanks_and_fuel() {
+        let plane = airplane(517.0, CenterOfGravity::Meter(0.427));
+        let tanks = with_tanks(plane.clone(), 55.0);
+
+        assert_differs(&plane, &tanks);
+        assert_differs(&tanks, &with_tanks(plane.clone(), 50.0));
+        assert_differs(
+            &tanks,
+            &tanks.clone().with_fuel_burn(FuelBurn::Proportional),
+        );
+        assert_differs(
+            &with_burn(tanks.clone(), ["Left", "Right"]),
+            &with_burn(tanks, ["Right", "Left"]),
+        );
+        assert_differs(
+            &plane,
+            &plane.clone().with_landing_fuel(Volume::Liter(30.0)),
+        );
+        assert_differs(
+            &plane,
+            &plane
+                .clone()
+                .with_fuel_consumption_diversion(Volume::Liter(10.0)),
+        );
+    }
+
+    #[test]
+    fn hash_covers_limits_and_warnings() {
+        let plane = airplane(517.0, CenterOfGravity::Meter(0.427));
+        let policy = with_limits(plane.clone(), LimitKind::Policy);
+
+        assert_differs(&plane, &policy);
+        assert_differs(&policy, &with_limits(plane.clone(), LimitKind::Operational));
+        assert_differs(
+            &plane,
+            &Airplane::new(
+                plane.registration().clone(),
+                plane.iter().cloned().collect(),
+                plane.limits().clone().with_kind(LimitKind::Operational),
+                plane.fuel_consumption_trip().clone(),
+            ),
+        );
+
+        let mut overridden = policy.clone();
+        let relaxed = plane.limits().clone();
+        overridden
+            .override_limits(0, relaxed.clone(), "Checkride".to_string())
+            .unwrap();
+        let mut relaxed_policy = plane.clone();
+        relaxed_policy.add_limits(relaxed.with_kind(LimitKind::Policy));
+        assert_differs(&relaxed_policy, &overridden);
+
+        assert_differs(
+            &plane,
+            &plane
+                .clone()
+                .with_warning_thresholds(WarningThresholds::default().with_mtow_fraction(0.05)),
+        );
+        assert_differs(
+            &plane,
+            &plane.clone().with_warning_thresholds(
+                WarningThresholds::default().with_reserve(30.0, Volume::Liter(20.0)),
+            ),
+        );
+    }
+
+    #[test]
+    fn earlier_versions_keep_their_hash() {
+        let key = b"club key";
+        let plane = airplane(517.0, CenterOfGravity::Meter(0.427));
+        assert_eq!(
+            plane.profile_hash(),
+            plane.profile_hash_version(HashVersion::V1)
+        );
+
+        // A profile with tanks signed before they were hashed still verifies as V1
+        let tanks = with_tanks(plane.clone(), 55.0);
+        let signature = tanks.sign_profile_version(key, HashVersion::V1);
+        assert_eq!(signature, plane.sign_profile_version(key, HashVersion::V1));
+        assert!(tanks.verify_profile_version(key, &signature, HashVersion::V1));
+        assert!(!tanks.verify_profile(key, &signature));
+    }
+
+    #[test]
+    fn signature_detects_tampering() {
+        let key = b"club key";
+        let signature = airplane(517.0, CenterOfGravity::Meter(0.427)).sign_profile(key);
+
+        assert!(airplane(517.0, CenterOfGravity::Meter(0.427)).verify_profile(key, &signature));
+        assert!(!airplane(510.0, CenterOfGravity::Meter(0.427)).verify_profile(key, &signature));
+        assert!(
+            !airplane(517.0, CenterOfGravity::Meter(0.427)).verify_profile(b"other", &signature)
+        );
+    }
+}
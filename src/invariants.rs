@@ -0,0 +1,374 @@
+//! Properties every calculation must keep, for tests and fuzzing of the calculations.
+//!
+//! With the `proptest` feature the model types implement `proptest::arbitrary::Arbitrary`, so
+//! `fn holds(plane: Airplane)` in a `proptest!` block gets airplanes with up to six stations of
+//! non-negative mass and the fuel last.
+
+use core::fmt;
+
+use crate::types::{FuelType, VolumeType};
+use crate::weight_and_balance::{Airplane, LeverArm};
+
+// Relative tolerance for sums that are computed in a different order
+const TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InvariantError {
+    /// The total mass in kg and the sum of the rows.
+    MassMismatch(f64, f64),
+    /// The total mass moment in kg m and the sum of the rows.
+    MomentMismatch(f64, f64),
+    /// The CG in meters and the most forward and rearward arm with mass.
+    CgOutsideStations(f64, f64, f64),
+    /// The landing mass in kg is above the take-off mass.
+    LandingAboveTakeoff(f64, f64),
+    /// The airplane was within the limits but is not after adding the maximum fuel.
+    MaxFuelOutsideLimits,
+}
+
+impl fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantError::MassMismatch(total, rows) => {
+                write!(
+                    f,
+                    "total mass {} kg is not the sum of the rows {} kg",
+                    total, rows
+                )
+            }
+            InvariantError::MomentMismatch(total, rows) => write!(
+                f,
+                "total moment {} kg m is not the sum of the rows {} kg m",
+                total, rows
+            ),
+            InvariantError::CgOutsideStations(cg, forward, rearward) => write!(
+                f,
+                "CG {} m is outside the stations from {} m to {} m",
+                cg, forward, rearward
+            ),
+            InvariantError::LandingAboveTakeoff(landing, takeoff) => write!(
+                f,
+                "landing mass {} kg is above the take-off mass {} kg",
+                landing, takeoff
+            ),
+            InvariantError::MaxFuelOutsideLimits => {
+                write!(f, "maximum fuel puts the airplane outside the limits")
+            }
+        }
+    }
+}
+
+impl core::error::Error for InvariantError {}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= TOLERANCE * a.abs().max(b.abs()).max(1.0)
+}
+
+pub fn total_mass_is_sum_of_rows(plane: &Airplane) -> Result<(), InvariantError> {
+    let total = plane.total_mass().kilo();
+    let rows = plane.iter().fold(0.0, |sum, m| sum + m.mass().kilo());
+    if close(total, rows) {
+        Ok(())
+    } else {
+        Err(InvariantError::MassMismatch(total, rows))
+    }
+}
+
+pub fn total_moment_is_sum_of_rows(plane: &Airplane) -> Result<(), InvariantError> {
+    let total = plane.total_mass_moment().kgm();
+    let rows = plane
+        .iter()
+        .fold(0.0, |sum, m| sum + m.mass().kilo() * m.lever_arm().meter());
+    if close(total, rows) {
+        Ok(())
+    } else {
+        Err(InvariantError::MomentMismatch(total, rows))
+    }
+}
+
+/// With only non-negative masses the CG lies between the stations that carry mass.
+pub fn cg_within_stations(plane: &Airplane) -> Result<(), InvariantError> {
+    let mass = plane.total_mass().kilo();
+    if mass <= 0.0 || plane.iter().any(|m| m.mass().kilo() < 0.0) {
+        return Ok(());
+    }
+
+    let (forward, rearward) = plane
+        .iter()
+        .filter(|m| m.mass().kilo() > 0.0)
+        .map(|m| m.lever_arm().meter())
+        .fold(
+            (f64::INFINITY, f64::NEG_INFINITY),
+            |(forward, rearward), arm| (forward.min(arm), rearward.max(arm)),
+        );
+    let cg = plane.total_mass_moment().kgm() / mass;
+
+    let margin = TOLERANCE * forward.abs().max(rearward.abs()).max(1.0);
+    if cg >= forward - margin && cg <= rearward + margin {
+        Ok(())
+    } else {
+        Err(InvariantError::CgOutsideStations(cg, forward, rearward))
+    }
+}
+
+/// Burning trip fuel never makes the airplane heavier.
+pub fn landing_not_above_takeoff(plane: &Airplane) -> Result<(), InvariantError> {
+    let takeoff = plane.total_mass().kilo();
+    match plane.total_mass_landing() {
+        Ok(landing) if plane.trip_fuel().to_liter() >= 0.0 => {
+            if landing.kilo() <= takeoff + TOLERANCE * takeoff.abs().max(1.0) {
+                Ok(())
+            } else {
+                Err(InvariantError::LandingAboveTakeoff(landing.kilo(), takeoff))
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+// Within the limits up to the float error of a CG or mass the solver put on a limit
+fn within_limits_close(plane: &Airplane) -> bool {
+    let limits = plane.limits();
+    let (mass, mtow) = (plane.total_mass().kilo(), limits.mtow().kilo());
+    let cg = plane.total_mass_moment().kgm() / mass;
+    let (forward, rearward) = (
+        limits.forward_cg_limit().meter(),
+        limits.rearward_cg_limit().meter(),
+    );
+    let margin = TOLERANCE * forward.abs().max(rearward.abs()).max(1.0);
+
+    (mass <= mtow || close(mass, mtow)) && cg >= forward - margin && cg <= rearward + margin
+}
+
+/// An airplane within the limits stays within them after adding the maximum fuel at `arm`, up to
+/// the float error of fuel that puts it on a limit.
+pub fn max_fuel_within_limits(plane: &Airplane, arm: &LeverArm) -> Result<(), InvariantError> {
+    if !plane.within_limits() {
+        return Ok(());
+    }
+
+    let mut fueled = plane.clone();
+    fueled.add_max_fuel_within_limits(
+        "Fuel".into(),
+        arm.clone(),
+        FuelType::Avgas,
+        VolumeType::Liter,
+        None,
+    );
+    if within_limits_close(&fueled) {
+        Ok(())
+    } else {
+        Err(InvariantError::MaxFuelOutsideLimits)
+    }
+}
+
+/// All invariants of a single airplane.
+pub fn check(plane: &Airplane) -> Result<(), InvariantError> {
+    total_mass_is_sum_of_rows(plane)?;
+    total_moment_is_sum_of_rows(plane)?;
+    cg_within_stations(plane)?;
+    landing_not_above_takeoff(plane)
+}
+
+#[cfg(feature = "proptest")]
+mod arbitrary {
+    use proptest::prelude::*;
+
+    use crate::registration::Registration;
+
+    use crate::weight_and_balance::{
+        Airplane, CenterOfGravity, LeverArm, Limits, Mass, Moment, Volume,
+    };
+
+    impl Arbitrary for LeverArm {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<LeverArm>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            (-1.0..3.0).prop_map(LeverArm::Meter).boxed()
+        }
+    }
+
+    impl Arbitrary for Volume {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Volume>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            prop_oneof![
+                (0.0..200.0).prop_map(Volume::Liter),
+                (0.0..50.0).prop_map(Volume::Gallon),
+            ]
+            .boxed()
+        }
+    }
+
+    impl Arbitrary for Mass {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Mass>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            prop_oneof![
+                (0.0..600.0).prop_map(Mass::Kilo),
+                any::<Volume>().prop_map(Mass::Avgas),
+                any::<Volume>().prop_map(Mass::Mogas),
+            ]
+            .boxed()
+        }
+    }
+
+    impl Arbitrary for Moment {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Moment>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            ("[A-Za-z ]{1,12}", any::<LeverArm>(), any::<Mass>())
+                .prop_map(|(name, arm, mass)| Moment::new(name, arm, mass))
+                .boxed()
+        }
+    }
+
+    impl Arbitrary for Limits {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Limits>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            (200.0..800.0, 100.0..1500.0, -0.5..2.0, 0.01..0.5)
+                .prop_map(|(minimum, range, forward, length)| {
+                    Limits::new(
+                        Mass::Kilo(minimum),
+                        Mass::Kilo(minimum + range),
+                        CenterOfGravity::Meter(forward),
+                        CenterOfGravity::Meter(forward + length),
+                    )
+                })
+                .boxed()
+        }
+    }
+
+    impl Arbitrary for Airplane {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Airplane>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            (
+                "[A-Z]{2}-[A-Z]{3}",
+                proptest::collection::vec((any::<LeverArm>(), 0.0..300.0), 0..7),
+                any::<LeverArm>(),
+                any::<Volume>(),
+                any::<Limits>(),
+                0.0..60.0,
+            )
+                .prop_map(|(registration, stations, fuel_arm, fuel, limits, trip)| {
+                    let mut moments: Vec<Moment> = stations
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, (arm, kg))| {
+                            Moment::new(format!("Station {}", i + 1), arm, Mass::Kilo(kg))
+                        })
+                        .collect();
+                    moments.push(Moment::new("Fuel".to_string(), fuel_arm, Mass::Avgas(fuel)));
+                    let registration = Registration::parse(&registration).unwrap();
+                    Airplane::new(registration, moments, limits, Volume::Liter(trip))
+                })
+                .boxed()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{CenterOfGravity, Limits, Mass, Moment, Volume};
+    use alloc::string::ToString;
+
+    #[test]
+    fn example_airplane_keeps_invariants() {
+        let plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            alloc::vec![
+                Moment::new(
+                    "Empty".to_string(),
+                    LeverArm::Meter(0.4294),
+                    Mass::Kilo(517.0),
+                ),
+                Moment::new(
+                    "Pilot".to_string(),
+                    LeverArm::Meter(0.515),
+                    Mass::Kilo(80.0),
+                ),
+                Moment::new(
+                    "Fuel".to_string(),
+                    LeverArm::Meter(0.325),
+                    Mass::Avgas(Volume::Liter(55.0)),
+                ),
+            ],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(17.0),
+        );
+
+        assert_eq!(Ok(()), check(&plane));
+        assert_eq!(
+            Ok(()),
+            max_fuel_within_limits(&plane, &LeverArm::Meter(0.325))
+        );
+    }
+
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        #[test]
+        fn arbitrary_airplanes_keep_invariants(plane: Airplane) {
+            proptest::prop_assert_eq!(Ok(()), check(&plane));
+        }
+
+        #[test]
+        fn max_fuel_keeps_invariants(plane: Airplane, arm: LeverArm) {
+            proptest::prop_assert_eq!(Ok(()), max_fuel_within_limits(&plane, &arm));
+
+            let mut fueled = plane.clone();
+            let fuel = fueled
+                .add_max_fuel_within_limits(
+                    "Max fuel".to_string(),
+                    arm,
+                    FuelType::Avgas,
+                    VolumeType::Liter,
+                    None,
+                )
+                .clone();
+            proptest::prop_assert!(fuel.mass().kilo() >= 0.0);
+            proptest::prop_assert_eq!(Ok(()), check(&fueled));
+        }
+
+        #[test]
+        fn summary_keeps_invariants(plane: Airplane) {
+            let summary = plane.summary();
+            let takeoff = summary.takeoff_mass().kilo();
+            proptest::prop_assert!(close(plane.total_mass().kilo(), takeoff));
+            proptest::prop_assert!(close(
+                plane.total_mass_moment().kgm(),
+                summary.takeoff_mass_moment().kgm()
+            ));
+
+            // Within the limits is having no negative margin
+            let margins = [
+                summary.mtow_margin().kilo(),
+                summary.forward_cg_margin().meter(),
+                summary.rearward_cg_margin().meter(),
+            ];
+            proptest::prop_assert_eq!(
+                summary.within_limits(),
+                margins.iter().all(|margin| *margin >= 0.0)
+            );
+
+            if let Some(landing) = summary.landing_mass() {
+                proptest::prop_assert!(landing.kilo() <= takeoff + TOLERANCE * takeoff.max(1.0));
+                proptest::prop_assert_eq!(Ok(landing.clone()), plane.total_mass_landing());
+            }
+        }
+    }
+}
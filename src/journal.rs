@@ -0,0 +1,273 @@
+//! A journal of the changes to the moments and limits of an airplane, for operators who must keep
+//! how the final figures were produced.
+//!
+//! The journal is off until [`Airplane::with_journal`] and is serialized with the airplane, so a
+//! saved [`Manifest`](crate::loading::Manifest) carries it next to the results.
+//!
+//! The same changes go to the observers of [`Airplane::with_observer`] as they happen, e.g. to
+//! re-render a chart or table in a GUI when the model changes.
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(doc)]
+use crate::weight_and_balance::Airplane;
+
+use crate::weight_and_balance::{LimitOverride, Limits, Mass, Moment};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Change {
+    MomentAdded(Moment),
+    MomentUpdated {
+        from: Moment,
+        to: Moment,
+    },
+    /// Fuel added to or changed in `tank`, `from` is `None` for a new fuel moment.
+    FuelChanged {
+        tank: String,
+        from: Option<Mass>,
+        to: Mass,
+    },
+    /// The policy limits at `index` relaxed from `from` to `to`.
+    LimitsOverridden {
+        index: usize,
+        from: Limits,
+        to: Limits,
+        justification: String,
+    },
+}
+
+impl Change {
+    /// The change of `from` into `to`, a fuel change when either is fuel.
+    pub(crate) fn moment(from: Option<&Moment>, to: &Moment) -> Change {
+        if to.mass().is_fuel() || from.is_some_and(|m| m.mass().is_fuel()) {
+            Change::FuelChanged {
+                tank: to.name().into(),
+                from: from.map(|m| m.mass().clone()),
+                to: to.mass().clone(),
+            }
+        } else {
+            match from {
+                Some(from) => Change::MomentUpdated {
+                    from: from.clone(),
+                    to: to.clone(),
+                },
+                None => Change::MomentAdded(to.clone()),
+            }
+        }
+    }
+
+    pub(crate) fn limits_overridden(limit_override: &LimitOverride) -> Change {
+        Change::LimitsOverridden {
+            index: limit_override.index(),
+            from: limit_override.original().clone(),
+            to: limit_override.relaxed().clone(),
+            justification: limit_override.justification().into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JournalEntry {
+    timestamp_ms: Option<u64>,
+    change: Change,
+}
+
+impl JournalEntry {
+    /// Milliseconds since the Unix epoch, `None` without the `std` feature as there is no clock.
+    pub fn timestamp_ms(&self) -> Option<u64> {
+        self.timestamp_ms
+    }
+
+    pub fn change(&self) -> &Change {
+        &self.change
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Journal {
+    entries: Vec<JournalEntry>,
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn now_ms() -> Option<u64> {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_millis() as u64)
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn now_ms() -> Option<u64> {
+    None
+}
+
+impl Journal {
+    pub fn entries(&self) -> &Vec<JournalEntry> {
+        &self.entries
+    }
+
+    pub(crate) fn record(&mut self, change: Change) {
+        self.entries.push(JournalEntry {
+            timestamp_ms: now_ms(),
+            change,
+        });
+    }
+}
+
+/// Called with every change of an airplane after it is made.
+pub type Observer = Arc<dyn Fn(&Change) + Send + Sync>;
+
+// The observers are not part of the value of an airplane, they are left out of comparisons and
+// serialization
+#[derive(Clone, Default)]
+pub(crate) struct Observers(Vec<Observer>);
+
+impl Observers {
+    pub(crate) fn push(&mut self, observer: Observer) {
+        self.0.push(observer);
+    }
+
+    pub(crate) fn notify(&self, change: &Change) {
+        for observer in self.0.iter() {
+            observer(change);
+        }
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} observers", self.0.len())
+    }
+}
+
+impl PartialEq for Observers {
+    fn eq(&self, _: &Observers) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{Airplane, CenterOfGravity, LeverArm, Limits, Moment, Volume};
+    use alloc::string::ToString;
+
+    #[test]
+    fn records_changes_in_order() {
+        let mut plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            alloc::vec![Moment::new(
+                "Empty".to_string(),
+                LeverArm::Meter(0.4294),
+                Mass::Kilo(517.0),
+            )],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(17.0),
+        );
+        let pilot = Moment::new(
+            "Pilot".to_string(),
+            LeverArm::Meter(0.515),
+            Mass::Kilo(80.0),
+        );
+        plane.add_moment(pilot.clone());
+        assert!(plane.journal().is_none());
+
+        let mut plane = plane.with_journal();
+        plane.add_moment(Moment::new(
+            "Fuel".to_string(),
+            LeverArm::Meter(0.325),
+            Mass::Avgas(Volume::Liter(40.0)),
+        ));
+        let heavier = Moment::new(
+            "Pilot".to_string(),
+            LeverArm::Meter(0.515),
+            Mass::Kilo(90.0),
+        );
+        assert_eq!(
+            Some(pilot.clone()),
+            plane.update_moment("Pilot", heavier.clone())
+        );
+        assert_eq!(None, plane.update_moment("Baggage", heavier.clone()));
+
+        let changes: Vec<&Change> = plane
+            .journal()
+            .unwrap()
+            .entries()
+            .iter()
+            .map(JournalEntry::change)
+            .collect();
+        assert_eq!(
+            alloc::vec![
+                &Change::FuelChanged {
+                    tank: "Fuel".to_string(),
+                    from: None,
+                    to: Mass::Avgas(Volume::Liter(40.0)),
+                },
+                &Change::MomentUpdated {
+                    from: pilot,
+                    to: heavier,
+                },
+            ],
+            changes
+        );
+        #[cfg(feature = "std")]
+        assert!(plane.journal().unwrap().entries()[0]
+            .timestamp_ms()
+            .is_some());
+    }
+
+    #[test]
+    fn observers_get_every_change() {
+        let changes: Arc<std::sync::Mutex<Vec<Change>>> = Arc::default();
+        let observed = changes.clone();
+        let mut plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            alloc::vec![],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(17.0),
+        )
+        .with_observer(move |change| observed.lock().unwrap().push(change.clone()));
+
+        let pilot = Moment::new(
+            "Pilot".to_string(),
+            LeverArm::Meter(0.515),
+            Mass::Kilo(80.0),
+        );
+        plane.add_moment(pilot.clone());
+        plane.update_moment("Pilot", pilot.clone());
+        assert_eq!(plane.clone(), plane);
+        assert!(plane.journal().is_none());
+
+        assert_eq!(
+            alloc::vec![
+                Change::MomentAdded(pilot.clone()),
+                Change::MomentUpdated {
+                    from: pilot.clone(),
+                    to: pilot,
+                },
+            ],
+            *changes.lock().unwrap()
+        );
+    }
+}
@@ -1,8 +1,129 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod prelude;
+
+pub mod stable;
+
+#[allow(dead_code)]
+pub mod error;
+
 #[allow(dead_code)]
 pub mod weight_and_balance;
 
+#[cfg(feature = "visualizer")]
 #[allow(dead_code)]
 pub mod visualizer;
 
 #[allow(dead_code)]
 pub mod types;
+
+#[allow(dead_code)]
+pub mod loading;
+
+#[allow(dead_code)]
+pub mod locale;
+
+#[allow(dead_code)]
+pub mod decimal;
+
+#[allow(dead_code)]
+pub mod invariants;
+
+#[allow(dead_code)]
+pub mod frames;
+
+#[allow(dead_code)]
+pub mod journal;
+
+#[allow(dead_code)]
+pub mod weighing;
+
+#[allow(dead_code)]
+pub mod feasibility;
+
+#[allow(dead_code)]
+pub mod seating;
+
+#[allow(dead_code)]
+pub mod fuel;
+
+#[allow(dead_code)]
+pub mod fuel_split;
+
+#[allow(dead_code)]
+pub mod trip;
+
+#[allow(dead_code)]
+pub mod trend;
+
+#[allow(dead_code)]
+pub mod diff;
+
+#[allow(dead_code)]
+pub mod registration;
+
+#[allow(dead_code)]
+pub mod fleet;
+
+#[allow(dead_code)]
+pub mod report;
+
+#[allow(dead_code)]
+pub mod finalized;
+
+#[cfg(feature = "std")]
+#[allow(dead_code)]
+pub mod route;
+
+#[cfg(feature = "std")]
+#[allow(dead_code)]
+pub mod performance;
+
+#[cfg(feature = "std")]
+#[allow(dead_code)]
+pub mod wind;
+
+#[cfg(feature = "serde")]
+#[allow(dead_code)]
+pub mod profile;
+
+#[cfg(feature = "serde")]
+#[allow(dead_code)]
+pub mod dto;
+
+#[cfg(feature = "registry")]
+#[allow(dead_code)]
+pub mod registry;
+
+#[cfg(feature = "import")]
+#[allow(dead_code)]
+pub mod import;
+
+#[cfg(feature = "std")]
+mod xml;
+
+#[cfg(feature = "std")]
+#[allow(dead_code)]
+pub mod snapshot;
+
+#[cfg(feature = "visualizer")]
+#[allow(dead_code)]
+mod svg;
+
+#[cfg(feature = "visualizer")]
+#[allow(dead_code)]
+mod vector;
+
+#[cfg(feature = "integrity")]
+#[allow(dead_code)]
+pub mod integrity;
+
+#[cfg(feature = "wasm")]
+#[allow(dead_code)]
+pub mod wasm;
+
+#[cfg(feature = "uom")]
+#[allow(dead_code)]
+pub mod uom;
@@ -0,0 +1,1191 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::trip::{FlightProfile, Phase};
+use crate::types::FuelType;
+use crate::weight_and_balance::{Airplane, LeverArm, Mass, Moment, Volume, WeightBalanceSummary};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Occupant {
+    name: String,
+    seat: String,
+    lever_arm: LeverArm,
+    mass: Mass,
+}
+
+impl Occupant {
+    pub fn new(name: String, seat: String, lever_arm: LeverArm, mass: Mass) -> Occupant {
+        Occupant {
+            name,
+            seat,
+            lever_arm,
+            mass,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn seat(&self) -> &str {
+        &self.seat
+    }
+
+    pub fn lever_arm(&self) -> &LeverArm {
+        &self.lever_arm
+    }
+
+    pub fn mass(&self) -> &Mass {
+        &self.mass
+    }
+
+    pub fn moment(&self) -> Moment {
+        Moment::new(self.seat.clone(), self.lever_arm.clone(), self.mass.clone())
+    }
+}
+
+/// One of the things loaded at a baggage station, e.g. the tow bar or the cockpit cover.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StationItem {
+    description: String,
+    mass: Mass,
+}
+
+impl StationItem {
+    pub fn new(description: String, mass: Mass) -> StationItem {
+        StationItem { description, mass }
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn mass(&self) -> &Mass {
+        &self.mass
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BaggageItem {
+    description: String,
+    lever_arm: LeverArm,
+    mass: Mass,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    items: Vec<StationItem>,
+}
+
+impl BaggageItem {
+    pub fn new(description: String, lever_arm: LeverArm, mass: Mass) -> BaggageItem {
+        BaggageItem {
+            description,
+            lever_arm,
+            mass,
+            items: Vec::new(),
+        }
+    }
+
+    /// A station loaded with `items`, its mass the sum of theirs in kilograms.
+    pub fn itemized(
+        description: String,
+        lever_arm: LeverArm,
+        items: Vec<StationItem>,
+    ) -> BaggageItem {
+        BaggageItem {
+            description,
+            lever_arm,
+            mass: Mass::Kilo(items.iter().map(|i| i.mass.kilo()).sum()),
+            items,
+        }
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn lever_arm(&self) -> &LeverArm {
+        &self.lever_arm
+    }
+
+    pub fn mass(&self) -> &Mass {
+        &self.mass
+    }
+
+    /// The items the mass is composed of, empty when it was given as one number.
+    pub fn items(&self) -> &Vec<StationItem> {
+        &self.items
+    }
+
+    pub fn moment(&self) -> Moment {
+        Moment::new(
+            self.description.clone(),
+            self.lever_arm.clone(),
+            self.mass.clone(),
+        )
+    }
+}
+
+/// A station with the mass it is loaded with unless an occupant sits in the seat or a baggage
+/// item has the description of its name, e.g. the club headset bag of 2 kg at 1.3 m.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StationDefault {
+    name: String,
+    lever_arm: LeverArm,
+    mass: Mass,
+}
+
+impl StationDefault {
+    pub fn new(name: String, lever_arm: LeverArm, mass: Mass) -> StationDefault {
+        StationDefault {
+            name,
+            lever_arm,
+            mass,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn lever_arm(&self) -> &LeverArm {
+        &self.lever_arm
+    }
+
+    pub fn mass(&self) -> &Mass {
+        &self.mass
+    }
+
+    pub fn moment(&self) -> Moment {
+        Moment::new(self.name.clone(), self.lever_arm.clone(), self.mass.clone())
+    }
+}
+
+/// Fuel on board as read from a dipstick, the fuel gauge or a totalizer.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum FuelQuantity {
+    Volume(Volume),
+    /// The fraction of a tank of the given capacity, 0.75 for three quarter tanks.
+    Fraction(f64, Volume),
+    /// The mass from a totalizer.
+    Mass(Mass),
+}
+
+impl FuelQuantity {
+    /// The volume of `fuel`, a fraction in the unit of the capacity and a mass in liters.
+    pub fn volume(&self, fuel: &FuelType) -> Volume {
+        match self {
+            FuelQuantity::Volume(volume) => volume.clone(),
+            FuelQuantity::Fraction(fraction, Volume::Liter(l)) => Volume::Liter(fraction * l),
+            FuelQuantity::Fraction(fraction, Volume::Gallon(g)) => Volume::Gallon(fraction * g),
+            FuelQuantity::Mass(mass) => match fuel {
+                FuelType::Avgas => mass.to_avgas(),
+                FuelType::Mogas => mass.to_mogas(),
+            }
+            .volume()
+            .cloned()
+            .unwrap_or(Volume::Liter(0.0)),
+        }
+    }
+
+    pub fn mass(&self, fuel: &FuelType) -> Mass {
+        match fuel {
+            FuelType::Avgas => Mass::Avgas(self.volume(fuel)),
+            FuelType::Mogas => Mass::Mogas(self.volume(fuel)),
+        }
+    }
+}
+
+// The float error of a tank filled to its capacity in other units
+const CAPACITY_TOLERANCE_LITER: f64 = 1e-9;
+
+/// Fuel added to a tank on top of what was already in it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FuelUplift {
+    tank: String,
+    lever_arm: LeverArm,
+    fuel: FuelType,
+    remaining: Volume,
+    uplift: Volume,
+    #[cfg_attr(feature = "serde", serde(default))]
+    capacity: Option<Volume>,
+}
+
+// `liter` in the unit of `unit`
+fn volume_in_unit_of(liter: f64, unit: &Volume) -> Volume {
+    match unit {
+        Volume::Liter(_) => Volume::Liter(liter),
+        Volume::Gallon(_) => Volume::Gallon(Volume::Liter(liter).to_gallon()),
+    }
+}
+
+impl FuelUplift {
+    pub fn new(
+        tank: String,
+        lever_arm: LeverArm,
+        fuel: FuelType,
+        remaining: Volume,
+        uplift: Volume,
+    ) -> FuelUplift {
+        FuelUplift {
+            tank,
+            lever_arm,
+            fuel,
+            remaining,
+            uplift,
+            capacity: None,
+        }
+    }
+
+    /// The remaining fuel and uplift as read from a gauge or totalizer. A remaining fraction of
+    /// a tank also gives the capacity of the tank.
+    pub fn from_quantities(
+        tank: String,
+        lever_arm: LeverArm,
+        fuel: FuelType,
+        remaining: &FuelQuantity,
+        uplift: &FuelQuantity,
+    ) -> FuelUplift {
+        let capacity = match remaining {
+            FuelQuantity::Fraction(_, capacity) => Some(capacity.clone()),
+            _ => None,
+        };
+        FuelUplift {
+            remaining: remaining.volume(&fuel),
+            uplift: uplift.volume(&fuel),
+            tank,
+            lever_arm,
+            fuel,
+            capacity,
+        }
+    }
+
+    /// The usable fuel of the tank when full.
+    pub fn with_capacity(mut self, capacity: Volume) -> FuelUplift {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    pub fn tank(&self) -> &str {
+        &self.tank
+    }
+
+    pub fn lever_arm(&self) -> &LeverArm {
+        &self.lever_arm
+    }
+
+    pub fn fuel(&self) -> &FuelType {
+        &self.fuel
+    }
+
+    pub fn remaining(&self) -> &Volume {
+        &self.remaining
+    }
+
+    pub fn uplift(&self) -> &Volume {
+        &self.uplift
+    }
+
+    pub fn capacity(&self) -> Option<&Volume> {
+        self.capacity.as_ref()
+    }
+
+    /// The fuel to add to the remaining fuel to get `target` on board, in the unit of the uplift
+    /// the fueler uses. Nothing when the remaining fuel is already at or above `target`.
+    pub fn required_uplift(&self, target: Volume) -> Volume {
+        let liter = (target.to_liter() - self.remaining.to_liter()).max(0.0);
+        volume_in_unit_of(liter, &self.uplift)
+    }
+
+    /// The fuel to add to fill the tank, `None` without a capacity.
+    pub fn uplift_to_full(&self) -> Option<Volume> {
+        Some(self.required_uplift(self.capacity.clone()?))
+    }
+
+    /// Fuel on board after the uplift, expressed in the unit of the uplift.
+    pub fn on_board(&self) -> Mass {
+        let liter = self.remaining.to_liter() + self.uplift.to_liter();
+        let volume = volume_in_unit_of(liter, &self.uplift);
+
+        match self.fuel {
+            FuelType::Avgas => Mass::Avgas(volume),
+            FuelType::Mogas => Mass::Mogas(volume),
+        }
+    }
+
+    pub fn moment(&self) -> Moment {
+        Moment::new(self.tank.clone(), self.lever_arm.clone(), self.on_board())
+    }
+
+    /// The uplift rounded up to what a pump delivers in `increment`s, e.g. whole liters or
+    /// 0.5 gal, so the mass and moment are those of the fuel actually added. Rounded down instead
+    /// when rounding up would overfill the capacity.
+    pub fn rounded_to(mut self, increment: &Volume) -> FuelUplift {
+        let up = self.uplift.rounded_up_to(increment);
+        let overfills = self.capacity.as_ref().is_some_and(|capacity| {
+            self.remaining.to_liter() + up.to_liter()
+                > capacity.to_liter() + CAPACITY_TOLERANCE_LITER
+        });
+        self.uplift = match overfills {
+            true => self.uplift.rounded_down_to(increment),
+            false => up,
+        };
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Loading {
+    occupants: Vec<Occupant>,
+    baggage: Vec<BaggageItem>,
+    fuel: Vec<FuelUplift>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    defaults: Vec<StationDefault>,
+}
+
+impl Loading {
+    pub fn new() -> Loading {
+        Loading::default()
+    }
+
+    pub fn add_occupant(&mut self, occupant: Occupant) {
+        self.occupants.push(occupant);
+    }
+
+    pub fn add_baggage(&mut self, item: BaggageItem) {
+        self.baggage.push(item);
+    }
+
+    pub fn add_fuel(&mut self, fuel: FuelUplift) {
+        self.fuel.push(fuel);
+    }
+
+    pub fn add_default(&mut self, default: StationDefault) {
+        self.defaults.push(default);
+    }
+
+    pub fn with_default(mut self, default: StationDefault) -> Loading {
+        self.add_default(default);
+        self
+    }
+
+    pub fn with_occupant(mut self, occupant: Occupant) -> Loading {
+        self.add_occupant(occupant);
+        self
+    }
+
+    pub fn with_baggage(mut self, item: BaggageItem) -> Loading {
+        self.add_baggage(item);
+        self
+    }
+
+    pub fn with_fuel(mut self, fuel: FuelUplift) -> Loading {
+        self.add_fuel(fuel);
+        self
+    }
+
+    /// The loading with the occupant in `seat` replaced by `occupant`, who is added when the
+    /// seat is empty.
+    pub fn with_seat(mut self, seat: &str, occupant: Occupant) -> Loading {
+        match self.occupants.iter_mut().find(|o| o.seat == seat) {
+            Some(existing) => *existing = occupant,
+            None => self.occupants.push(occupant),
+        }
+        self
+    }
+
+    /// The loading with every fuel uplift rounded to `increment`, see [`FuelUplift::rounded_to`].
+    pub fn with_uplifts_rounded_to(mut self, increment: &Volume) -> Loading {
+        self.fuel = self
+            .fuel
+            .into_iter()
+            .map(|fuel| fuel.rounded_to(increment))
+            .collect();
+        self
+    }
+
+    /// The loading with the fuel uplift of `tank` replaced by `fuel`, which is added when there
+    /// is none.
+    pub fn with_tank(mut self, tank: &str, fuel: FuelUplift) -> Loading {
+        match self.fuel.iter_mut().find(|f| f.tank == tank) {
+            Some(existing) => *existing = fuel,
+            None => self.fuel.push(fuel),
+        }
+        self
+    }
+
+    pub fn occupants(&self) -> &Vec<Occupant> {
+        &self.occupants
+    }
+
+    pub fn baggage(&self) -> &Vec<BaggageItem> {
+        &self.baggage
+    }
+
+    pub fn fuel(&self) -> &Vec<FuelUplift> {
+        &self.fuel
+    }
+
+    pub fn defaults(&self) -> &Vec<StationDefault> {
+        &self.defaults
+    }
+
+    /// The defaults that no occupant or baggage item overrides.
+    pub fn applied_defaults(&self) -> impl Iterator<Item = &StationDefault> {
+        self.defaults.iter().filter(|d| {
+            !self.occupants.iter().any(|o| o.seat == d.name)
+                && !self.baggage.iter().any(|b| b.description == d.name)
+        })
+    }
+
+    /// Occupants first, then baggage, the applied defaults and fuel last, so the landing
+    /// calculation finds the fuel moment at the end.
+    pub fn moments(&self) -> Vec<Moment> {
+        self.occupants
+            .iter()
+            .map(Occupant::moment)
+            .chain(self.baggage.iter().map(BaggageItem::moment))
+            .chain(self.applied_defaults().map(StationDefault::moment))
+            .chain(self.fuel.iter().map(FuelUplift::moment))
+            .collect()
+    }
+
+    pub fn apply(&self, plane: &mut Airplane) {
+        for moment in self.moments() {
+            plane.add_moment(moment);
+        }
+    }
+
+    /// `profile`, the empty aircraft, with this loading applied, without changing either or
+    /// notifying the observers of `profile`.
+    pub fn loaded(&self, profile: &Airplane) -> Airplane {
+        let mut plane = profile.unobserved();
+        self.apply(&mut plane);
+        plane
+    }
+}
+
+/// The summary of `profile`, the empty aircraft, with `loading` applied. Nothing is changed, so
+/// an immediate-mode or Elm-style interface can keep the loading as its state and compute the
+/// figures on every frame or update.
+pub fn compute(profile: &Airplane, loading: &Loading) -> WeightBalanceSummary {
+    loading.loaded(profile).summary()
+}
+
+/// The summary of `plane`, the empty aircraft, with each loading applied, in the order of the
+/// loadings. With the `parallel` feature the loadings are evaluated on the rayon thread pool.
+pub fn evaluate_scenarios(plane: &Airplane, loadings: &[Loading]) -> Vec<WeightBalanceSummary> {
+    let evaluate = |loading: &Loading| compute(plane, loading);
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        loadings.par_iter().map(evaluate).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        loadings.iter().map(evaluate).collect()
+    }
+}
+
+/// A named recurring loading, e.g. "Instructor + student, half fuel".
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoadingTemplate {
+    name: String,
+    loading: Loading,
+}
+
+impl LoadingTemplate {
+    pub fn new(name: String, loading: Loading) -> LoadingTemplate {
+        LoadingTemplate { name, loading }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn loading(&self) -> &Loading {
+        &self.loading
+    }
+
+    /// The manifest of `plane`, the empty aircraft, loaded with this template.
+    pub fn manifest(&self, plane: Airplane) -> Manifest {
+        Manifest::new(plane, self.loading.clone())
+    }
+}
+
+/// Loading templates in the order they were added, as offered to the user.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoadingTemplates {
+    templates: Vec<LoadingTemplate>,
+}
+
+impl LoadingTemplates {
+    pub fn new() -> LoadingTemplates {
+        LoadingTemplates::default()
+    }
+
+    /// Replaces a template with the same name in its place, otherwise adds it at the end.
+    pub fn add(&mut self, template: LoadingTemplate) {
+        match self.templates.iter_mut().find(|t| t.name == template.name) {
+            Some(existing) => *existing = template,
+            None => self.templates.push(template),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<LoadingTemplate> {
+        let index = self.templates.iter().position(|t| t.name == name)?;
+        Some(self.templates.remove(index))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LoadingTemplate> {
+        self.templates.iter().find(|t| t.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &LoadingTemplate> {
+        self.templates.iter()
+    }
+
+    /// The manifest of `plane` loaded with the template `name`, `None` without that template.
+    pub fn manifest(&self, name: &str, plane: Airplane) -> Option<Manifest> {
+        Some(self.get(name)?.manifest(plane))
+    }
+}
+
+/// Per-flight loading manifest: who sits where, what is carried and how much fuel was taken,
+/// together with the resulting weight and balance summary.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Manifest {
+    airplane: Airplane,
+    loading: Loading,
+    summary: WeightBalanceSummary,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    flight_profile: Option<FlightProfile>,
+}
+
+impl Manifest {
+    /// `plane` holds the empty aircraft; the loading is added to it.
+    pub fn new(mut plane: Airplane, loading: Loading) -> Manifest {
+        loading.apply(&mut plane);
+        let summary = plane.summary();
+
+        Manifest {
+            airplane: plane,
+            loading,
+            summary,
+            flight_profile: None,
+        }
+    }
+
+    /// The trip fuel and landing figures from `profile`, with its phases in the report.
+    pub fn with_flight_profile(mut self, profile: FlightProfile) -> Manifest {
+        self.airplane = profile.apply(self.airplane);
+        self.summary = self.airplane.summary();
+        self.flight_profile = Some(profile);
+        self
+    }
+
+    pub fn flight_profile(&self) -> Option<&FlightProfile> {
+        self.flight_profile.as_ref()
+    }
+
+    pub fn airplane(&self) -> &Airplane {
+        &self.airplane
+    }
+
+    pub fn loading(&self) -> &Loading {
+        &self.loading
+    }
+
+    pub fn summary(&self) -> &WeightBalanceSummary {
+        &self.summary
+    }
+
+    pub fn into_airplane(self) -> Airplane {
+        self.airplane
+    }
+
+    /// A file name for the report of this manifest, e.g. `PHDHA-manifest.pdf` for `"pdf"`.
+    pub fn file_name(&self, extension: &str) -> String {
+        alloc::format!(
+            "{}-manifest.{}",
+            self.airplane.registration().compact(),
+            extension
+        )
+    }
+}
+
+// The CG in meters, a dash for an airplane without mass as in the totals row of the table
+fn center_of_gravity(meter: f64) -> String {
+    if meter.is_finite() {
+        alloc::format!("{:.4} m", meter)
+    } else {
+        "-".to_string()
+    }
+}
+
+impl fmt::Display for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Loading manifest {}", self.airplane.registration())?;
+
+        writeln!(f)?;
+        writeln!(f, "Occupants")?;
+        for o in self.loading.occupants() {
+            writeln!(
+                f,
+                "  {:<16} {:<20} {:>8.2} kg",
+                o.seat(),
+                o.name(),
+                o.mass().kilo()
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Baggage")?;
+        for b in self.loading.baggage() {
+            writeln!(f, "  {:<37} {:>8.2} kg", b.description(), b.mass().kilo())?;
+            for i in b.items() {
+                writeln!(f, "    {:<35} {:>8.2} kg", i.description(), i.mass().kilo())?;
+            }
+        }
+
+        if self.loading.applied_defaults().next().is_some() {
+            writeln!(f)?;
+            writeln!(f, "Defaults")?;
+            for d in self.loading.applied_defaults() {
+                writeln!(f, "  {:<37} {:>8.2} kg", d.name(), d.mass().kilo())?;
+            }
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Fuel")?;
+        for u in self.loading.fuel() {
+            let fuel = match u.fuel() {
+                FuelType::Avgas => "Avgas",
+                FuelType::Mogas => "Mogas",
+            };
+            let on_board = u.on_board();
+            writeln!(
+                f,
+                "  {:<16} {:<5} uplift {:>10} on board {:>10} {:>8.2} kg",
+                u.tank(),
+                fuel,
+                u.uplift().to_string(),
+                on_board
+                    .volume()
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+                on_board.kilo()
+            )?;
+        }
+
+        if let Some(profile) = &self.flight_profile {
+            writeln!(f)?;
+            writeln!(f, "Trip fuel")?;
+            for p in profile.breakdown() {
+                let phase = match p.phase() {
+                    Phase::StartTaxi => "Start and taxi",
+                    Phase::Climb => "Climb",
+                    Phase::Cruise => "Cruise",
+                    Phase::Descent => "Descent",
+                };
+                writeln!(
+                    f,
+                    "  {:<16} {:>5.0} min {:>10}",
+                    phase,
+                    p.minutes(),
+                    p.fuel().to_string()
+                )?;
+            }
+            writeln!(
+                f,
+                "  {:<26} {:>10}",
+                "Total",
+                profile.trip_fuel().to_string()
+            )?;
+        }
+
+        let notes: Vec<&Moment> = self
+            .airplane
+            .iter()
+            .filter(|m| m.note().is_some())
+            .collect();
+        if !notes.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Notes")?;
+            for m in notes {
+                writeln!(f, "  {:<16} {}", m.name(), m.note().unwrap_or_default())?;
+            }
+        }
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Take-off mass {:.2} kg, moment {:.2} kg m, CG {}",
+            self.summary.takeoff_mass().kilo(),
+            self.summary.takeoff_mass_moment().kgm(),
+            center_of_gravity(self.summary.takeoff_center_of_gravity().meter())
+        )?;
+        if let (Some(mass), Some(moment)) = (
+            self.summary.landing_mass(),
+            self.summary.landing_mass_moment(),
+        ) {
+            writeln!(
+                f,
+                "Landing mass {:.2} kg, moment {:.2} kg m, CG {}",
+                mass.kilo(),
+                moment.kgm(),
+                center_of_gravity(moment.kgm() / mass.kilo())
+            )?;
+        }
+        if let (Some(mass), Some(moment), Some(within_limits)) = (
+            self.summary.alternate_landing_mass(),
+            self.summary.alternate_landing_mass_moment(),
+            self.summary.alternate_landing_within_limits(),
+        ) {
+            writeln!(
+                f,
+                "Alternate landing mass {:.2} kg, moment {:.2} kg m, CG {}, within limits: {}",
+                mass.kilo(),
+                moment.kgm(),
+                center_of_gravity(moment.kgm() / mass.kilo()),
+                if within_limits { "yes" } else { "no" }
+            )?;
+        }
+        for o in self.summary.limit_overrides() {
+            writeln!(
+                f,
+                "Policy limits {} overridden: {}",
+                o.index() + 1,
+                o.justification()
+            )?;
+        }
+        if let Some(within_policy_limits) = self.summary.within_policy_limits() {
+            writeln!(
+                f,
+                "Within policy limits: {}",
+                if within_policy_limits { "yes" } else { "no" }
+            )?;
+        }
+        write!(
+            f,
+            "Within limits: {}",
+            if self.summary.within_limits() {
+                "yes"
+            } else {
+                "no"
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{CenterOfGravity, Limits};
+
+    fn empty_airplane() -> Airplane {
+        Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![Moment::new(
+                "Empty".to_string(),
+                LeverArm::Meter(0.4294),
+                Mass::Kilo(517.0),
+            )],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(17.0),
+        )
+    }
+
+    fn loading() -> Loading {
+        let mut loading = Loading::new();
+        loading.add_fuel(FuelUplift::new(
+            "Main".to_string(),
+            LeverArm::Meter(0.325),
+            FuelType::Avgas,
+            Volume::Liter(40.0),
+            Volume::Liter(22.0),
+        ));
+        loading.add_occupant(Occupant::new(
+            "J. Doe".to_string(),
+            "Pilot".to_string(),
+            LeverArm::Meter(0.515),
+            Mass::Kilo(80.0),
+        ));
+        loading.add_baggage(BaggageItem::new(
+            "Headset bag".to_string(),
+            LeverArm::Meter(1.3),
+            Mass::Kilo(5.0),
+        ));
+        loading
+    }
+
+    #[test]
+    fn fuel_on_board_includes_remaining() {
+        let uplift = FuelUplift::new(
+            "Main".to_string(),
+            LeverArm::Meter(0.325),
+            FuelType::Avgas,
+            Volume::Liter(10.0),
+            Volume::Gallon(5.0),
+        );
+
+        match uplift.on_board() {
+            Mass::Avgas(Volume::Gallon(g)) => {
+                assert!((g - (5.0 + Volume::Liter(10.0).to_gallon())).abs() < 1e-9)
+            }
+            _ => panic!("expected avgas in gallons"),
+        }
+    }
+
+    #[test]
+    fn uplift_to_target_and_full() {
+        let uplift = FuelUplift::new(
+            "Main".to_string(),
+            LeverArm::Meter(0.325),
+            FuelType::Avgas,
+            Volume::Liter(40.0),
+            Volume::Gallon(0.0),
+        );
+        assert_eq!(None, uplift.uplift_to_full());
+        assert_eq!(
+            Volume::Gallon(0.0),
+            uplift.required_uplift(Volume::Liter(30.0))
+        );
+
+        let uplift = uplift.with_capacity(Volume::Liter(110.0));
+        match uplift.uplift_to_full() {
+            Some(Volume::Gallon(g)) => assert!((g - Volume::Liter(70.0).to_gallon()).abs() < 1e-9),
+            _ => panic!("expected gallons"),
+        }
+        match uplift.required_uplift(Volume::Gallon(20.0)) {
+            Volume::Gallon(g) => {
+                assert!((g - (20.0 - Volume::Liter(40.0).to_gallon())).abs() < 1e-9)
+            }
+            _ => panic!("expected gallons"),
+        }
+    }
+
+    #[test]
+    fn uplift_rounded_to_pump_increments() {
+        let uplift = FuelUplift::new(
+            "Main".to_string(),
+            LeverArm::Meter(0.325),
+            FuelType::Avgas,
+            Volume::Liter(40.0),
+            Volume::Liter(23.37),
+        );
+
+        let liters = uplift.clone().rounded_to(&Volume::Liter(1.0));
+        assert_eq!(&Volume::Liter(24.0), liters.uplift());
+        assert!((liters.moment().mass().kilo() - 64.0 * 0.72).abs() < 1e-9);
+        assert!((liters.moment().total().kgm() - 64.0 * 0.72 * 0.325).abs() < 1e-9);
+        assert_eq!(
+            &Volume::Gallon(6.5),
+            uplift.clone().rounded_to(&Volume::Gallon(0.5)).uplift()
+        );
+
+        // A full tank is not overfilled
+        let full = uplift.with_capacity(Volume::Liter(63.5));
+        assert_eq!(
+            &Volume::Liter(23.0),
+            full.rounded_to(&Volume::Liter(1.0)).uplift()
+        );
+
+        // Exact multiples stay, also after converting units
+        let two_gallons = Volume::Liter(Volume::Gallon(2.0).to_liter());
+        assert_eq!(
+            Volume::Gallon(2.0),
+            two_gallons.rounded_up_to(&Volume::Gallon(0.5))
+        );
+        assert_eq!(
+            Volume::Liter(23.0),
+            Volume::Liter(23.37).rounded_down_to(&Volume::Liter(1.0))
+        );
+        assert_eq!(
+            Volume::Liter(23.37),
+            Volume::Liter(23.37).rounded_up_to(&Volume::Liter(0.0))
+        );
+    }
+
+    #[test]
+    fn fuel_quantity_as_volume() {
+        let three_quarters = FuelQuantity::Fraction(0.75, Volume::Gallon(24.0));
+        assert_eq!(
+            Volume::Gallon(18.0),
+            three_quarters.volume(&FuelType::Avgas)
+        );
+
+        let totalizer = FuelQuantity::Mass(Mass::Kilo(36.0));
+        assert!((totalizer.volume(&FuelType::Avgas).to_liter() - 50.0).abs() < 1e-9);
+        assert!((totalizer.mass(&FuelType::Mogas).kilo() - 36.0).abs() < 1e-9);
+        assert_eq!(
+            Mass::Avgas(Volume::Liter(20.0)),
+            FuelQuantity::Volume(Volume::Liter(20.0)).mass(&FuelType::Avgas)
+        );
+    }
+
+    #[test]
+    fn uplift_from_gauge_and_totalizer() {
+        // Half of an 80 L tank on the gauge and 18 kg from the totalizer, 25 L
+        let uplift = FuelUplift::from_quantities(
+            "Main".to_string(),
+            LeverArm::Meter(0.325),
+            FuelType::Avgas,
+            &FuelQuantity::Fraction(0.5, Volume::Liter(80.0)),
+            &FuelQuantity::Mass(Mass::Kilo(18.0)),
+        );
+        assert_eq!(Some(&Volume::Liter(80.0)), uplift.capacity());
+        assert_eq!(&Volume::Liter(40.0), uplift.remaining());
+        assert!((uplift.uplift().to_liter() - 25.0).abs() < 1e-9);
+
+        let mut plane = empty_airplane();
+        Loading::new().with_fuel(uplift).apply(&mut plane);
+        let expected = 517.0 * 0.4294 + 65.0 * 0.72 * 0.325;
+        assert!((plane.total_mass_moment().kgm() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fuel_moment_is_last() {
+        let moments = loading().moments();
+
+        assert_eq!(3, moments.len());
+        assert_eq!("Pilot", moments[0].name());
+        assert!(moments.last().unwrap().mass().is_fuel());
+    }
+
+    #[test]
+    fn manifest_summary() {
+        let manifest = Manifest::new(empty_airplane(), loading());
+        let summary = manifest.summary();
+
+        assert_eq!(
+            517.0 + 80.0 + 5.0 + 62.0 * 0.72,
+            summary.takeoff_mass().kilo()
+        );
+        assert!(
+            (517.0 + 80.0 + 5.0 + 45.0 * 0.72 - summary.landing_mass().unwrap().kilo()).abs()
+                < 1e-9
+        );
+        assert!(summary.within_limits());
+        assert!(manifest.to_string().contains("J. Doe"));
+    }
+
+    #[test]
+    fn manifest_without_mass() {
+        let plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![],
+            empty_airplane().limits().clone(),
+            Volume::Liter(17.0),
+        );
+        let manifest = Manifest::new(plane, Loading::new()).to_string();
+
+        assert!(manifest.contains(" kg m, CG -\n"));
+        assert!(!manifest.contains("NaN"));
+    }
+
+    #[test]
+    fn manifest_lists_baggage_items() {
+        let bag = BaggageItem::itemized(
+            "Baggage".to_string(),
+            LeverArm::Meter(1.3),
+            vec![
+                StationItem::new("Tow bar".to_string(), Mass::Kilo(3.5)),
+                StationItem::new("Cockpit cover".to_string(), Mass::Kilo(1.5)),
+            ],
+        );
+        assert_eq!(&Mass::Kilo(5.0), bag.mass());
+        assert_eq!(2, bag.items().len());
+
+        let manifest =
+            Manifest::new(empty_airplane(), Loading::new().with_baggage(bag)).to_string();
+        assert!(manifest.contains("  Baggage                                   5.00 kg\n"));
+        assert!(manifest.contains("    Tow bar                                 3.50 kg\n"));
+        assert!(manifest.contains("    Cockpit cover                           1.50 kg\n"));
+    }
+
+    #[test]
+    fn manifest_lists_notes() {
+        let mut plane = empty_airplane();
+        assert!(!Manifest::new(plane.clone(), loading())
+            .to_string()
+            .contains("Notes"));
+
+        plane.add_moment(
+            Moment::new("Seat".to_string(), LeverArm::Meter(1.0), Mass::Kilo(8.0))
+                .with_note("Child seat installed".to_string()),
+        );
+        assert!(Manifest::new(plane, loading())
+            .to_string()
+            .contains("Child seat installed"));
+    }
+
+    #[test]
+    fn manifest_registration() {
+        let manifest = Manifest::new(empty_airplane(), loading());
+        assert!(manifest.to_string().starts_with("Loading manifest PH-DHA"));
+        assert_eq!("PHDHA-manifest.txt", manifest.file_name("txt"));
+    }
+
+    #[test]
+    fn manifest_with_flight_profile() {
+        let profile = FlightProfile::new(Volume::Liter(2.0)).with_cruise(60.0, Volume::Liter(18.0));
+        let manifest = Manifest::new(empty_airplane(), loading()).with_flight_profile(profile);
+
+        assert_eq!(
+            &Volume::Liter(20.0),
+            manifest.airplane().fuel_consumption_trip()
+        );
+        assert_eq!(
+            manifest.airplane().total_mass_landing().ok().as_ref(),
+            manifest.summary().landing_mass()
+        );
+        let report = manifest.to_string();
+        assert!(report.contains("Trip fuel"));
+        assert!(report.contains("Cruise"));
+        assert!(report.contains("20.00L"));
+    }
+
+    #[test]
+    fn templates_by_name_in_order() {
+        let mut templates = LoadingTemplates::new();
+        templates.add(LoadingTemplate::new("Solo".to_string(), loading()));
+        templates.add(LoadingTemplate::new("Empty".to_string(), Loading::new()));
+
+        let mut dual = loading();
+        dual.add_occupant(Occupant::new(
+            "R. Roe".to_string(),
+            "Instructor".to_string(),
+            LeverArm::Meter(0.515),
+            Mass::Kilo(85.0),
+        ));
+        templates.add(LoadingTemplate::new("Solo".to_string(), dual));
+
+        let names: Vec<&str> = templates.iter().map(LoadingTemplate::name).collect();
+        assert_eq!(vec!["Solo", "Empty"], names);
+
+        let manifest = templates.manifest("Solo", empty_airplane()).unwrap();
+        assert_eq!(2, manifest.loading().occupants().len());
+        assert!(templates.manifest("Dual", empty_airplane()).is_none());
+
+        assert!(templates.remove("Empty").is_some());
+        assert_eq!(1, templates.iter().count());
+    }
+
+    #[test]
+    fn scenarios_in_order() {
+        let mut heavy = loading();
+        heavy.add_occupant(Occupant::new(
+            "R. Roe".to_string(),
+            "Passenger".to_string(),
+            LeverArm::Meter(0.515),
+            Mass::Kilo(110.0),
+        ));
+
+        let summaries = evaluate_scenarios(&empty_airplane(), &[loading(), heavy]);
+
+        assert_eq!(2, summaries.len());
+        assert!(summaries[0].within_limits());
+        assert!(summaries[0].forward_cg_margin().meter() > 0.0);
+        assert!(!summaries[1].within_limits());
+        assert!(
+            (summaries[1].mtow_margin().kilo() - (750.0 - 517.0 - 195.0 - 62.0 * 0.72)).abs()
+                < 1e-9
+        );
+    }
+
+    #[test]
+    fn compute_without_changes() {
+        let profile = empty_airplane().with_observer(|_| panic!("the profile was changed"));
+        let pilot = |mass: f64| {
+            Occupant::new(
+                "J. Doe".to_string(),
+                "Pilot".to_string(),
+                LeverArm::Meter(0.515),
+                Mass::Kilo(mass),
+            )
+        };
+
+        let light = loading();
+        let heavy = light.clone().with_seat("Pilot", pilot(95.0));
+        assert_eq!(3, heavy.moments().len());
+        assert!(
+            (compute(&profile, &heavy).takeoff_mass().kilo()
+                - compute(&profile, &light).takeoff_mass().kilo()
+                - 15.0)
+                .abs()
+                < 1e-9
+        );
+
+        let mut plane = empty_airplane();
+        light.apply(&mut plane);
+        assert_eq!(plane.summary(), compute(&profile, &light));
+        assert_eq!(1, profile.len());
+        assert_eq!(
+            4,
+            Loading::new()
+                .with_seat("Pilot", pilot(80.0))
+                .with_seat("Passenger", pilot(80.0))
+                .with_baggage(light.baggage()[0].clone())
+                .with_tank("Main", light.fuel()[0].clone())
+                .moments()
+                .len()
+        );
+    }
+
+    #[test]
+    fn defaults_unless_overridden() {
+        let headset = StationDefault::new(
+            "Headset bag".to_string(),
+            LeverArm::Meter(1.3),
+            Mass::Kilo(2.0),
+        );
+        let student = StationDefault::new(
+            "Passenger".to_string(),
+            LeverArm::Meter(0.515),
+            Mass::Kilo(77.0),
+        );
+        let loading = loading().with_default(headset).with_default(student);
+
+        let names: Vec<String> = loading
+            .moments()
+            .iter()
+            .map(|m| m.name().to_string())
+            .collect();
+        assert_eq!(vec!["Pilot", "Headset bag", "Passenger", "Main"], names);
+        assert_eq!(&Mass::Kilo(5.0), loading.moments()[1].mass());
+        assert_eq!(1, loading.applied_defaults().count());
+
+        let manifest = Manifest::new(empty_airplane(), loading).to_string();
+        assert!(manifest.contains("Defaults\n  Passenger"));
+    }
+}
@@ -0,0 +1,280 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use crate::decimal::Rounding;
+
+/// A column of the weight and balance table, the imperial ones are only in dual unit tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Header {
+    Name,
+    LeverArm,
+    LeverArmInch,
+    Mass,
+    MassPound,
+    MassMoment,
+    MassMomentPoundInch,
+}
+
+/// Column labels and volume unit suffixes used in the table output.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Labels {
+    name: String,
+    lever_arm: String,
+    mass: String,
+    mass_moment: String,
+    total: String,
+    liter: String,
+    gallon: String,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    headers: BTreeMap<Header, String>,
+    #[cfg_attr(feature = "serde", serde(default = "default_takeoff"))]
+    takeoff: String,
+    #[cfg_attr(feature = "serde", serde(default = "default_landing"))]
+    landing: String,
+    #[cfg_attr(feature = "serde", serde(default = "default_center_of_gravity"))]
+    center_of_gravity: String,
+}
+
+fn default_takeoff() -> String {
+    "Takeoff".to_string()
+}
+
+fn default_landing() -> String {
+    "Landing".to_string()
+}
+
+fn default_center_of_gravity() -> String {
+    "CG".to_string()
+}
+
+impl Labels {
+    pub fn new(
+        name: String,
+        lever_arm: String,
+        mass: String,
+        mass_moment: String,
+        total: String,
+    ) -> Labels {
+        Labels {
+            name,
+            lever_arm,
+            mass,
+            mass_moment,
+            total,
+            liter: "L".to_string(),
+            gallon: "gal".to_string(),
+            headers: BTreeMap::new(),
+            takeoff: default_takeoff(),
+            landing: default_landing(),
+            center_of_gravity: default_center_of_gravity(),
+        }
+    }
+
+    pub fn with_volume_units(mut self, liter: String, gallon: String) -> Labels {
+        self.liter = liter;
+        self.gallon = gallon;
+        self
+    }
+
+    /// The labels of the take-off and landing columns of the totals, `Takeoff` and `Landing` by
+    /// default.
+    pub fn with_conditions(mut self, takeoff: String, landing: String) -> Labels {
+        self.takeoff = takeoff;
+        self.landing = landing;
+        self
+    }
+
+    pub fn with_center_of_gravity(mut self, center_of_gravity: String) -> Labels {
+        self.center_of_gravity = center_of_gravity;
+        self
+    }
+
+    /// Replaces the label and unit in the header of a column, e.g. `"Arm [in]"` for
+    /// [`Header::LeverArm`] of an airplane documented in inches.
+    pub fn with_header(mut self, header: Header, text: String) -> Labels {
+        self.headers.insert(header, text);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn lever_arm(&self) -> &str {
+        &self.lever_arm
+    }
+
+    pub fn mass(&self) -> &str {
+        &self.mass
+    }
+
+    pub fn mass_moment(&self) -> &str {
+        &self.mass_moment
+    }
+
+    pub fn total(&self) -> &str {
+        &self.total
+    }
+
+    pub fn liter(&self) -> &str {
+        &self.liter
+    }
+
+    pub fn gallon(&self) -> &str {
+        &self.gallon
+    }
+
+    pub fn takeoff(&self) -> &str {
+        &self.takeoff
+    }
+
+    pub fn landing(&self) -> &str {
+        &self.landing
+    }
+
+    pub fn center_of_gravity(&self) -> &str {
+        &self.center_of_gravity
+    }
+
+    /// The header of a column, the label with its unit as in `Lever Arm [m]` unless replaced.
+    pub fn header(&self, header: Header) -> String {
+        if let Some(text) = self.headers.get(&header) {
+            return text.clone();
+        }
+        match header {
+            Header::Name => self.name.clone(),
+            Header::LeverArm => format!("{} [m]", self.lever_arm),
+            Header::LeverArmInch => format!("{} [in]", self.lever_arm),
+            Header::Mass => format!("{} [kg]", self.mass),
+            Header::MassPound => format!("{} [lb]", self.mass),
+            Header::MassMoment => format!("{} [kg m]", self.mass_moment),
+            Header::MassMomentPoundInch => format!("{} [lb in]", self.mass_moment),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Locale {
+    decimal_separator: char,
+    labels: Labels,
+    #[cfg_attr(feature = "serde", serde(default))]
+    rounding: Rounding,
+}
+
+impl Locale {
+    pub fn new(decimal_separator: char, labels: Labels) -> Locale {
+        Locale {
+            decimal_separator,
+            labels,
+            rounding: Rounding::default(),
+        }
+    }
+
+    pub fn english() -> Locale {
+        Locale::new(
+            '.',
+            Labels::new(
+                "Name".to_string(),
+                "Lever Arm".to_string(),
+                "Mass".to_string(),
+                "Mass Moment".to_string(),
+                "Total".to_string(),
+            ),
+        )
+    }
+
+    pub fn dutch() -> Locale {
+        Locale::new(
+            ',',
+            Labels::new(
+                "Naam".to_string(),
+                "Arm".to_string(),
+                "Massa".to_string(),
+                "Moment".to_string(),
+                "Totaal".to_string(),
+            )
+            .with_volume_units("l".to_string(), "gal".to_string())
+            .with_conditions("Start".to_string(), "Landing".to_string())
+            .with_center_of_gravity("Zwaartepunt".to_string()),
+        )
+    }
+
+    pub fn with_decimal_separator(mut self, decimal_separator: char) -> Locale {
+        self.decimal_separator = decimal_separator;
+        self
+    }
+
+    /// How the numbers in the tables are rounded, ties away from zero by default.
+    pub fn with_rounding(mut self, rounding: Rounding) -> Locale {
+        self.rounding = rounding;
+        self
+    }
+
+    pub fn decimal_separator(&self) -> char {
+        self.decimal_separator
+    }
+
+    pub fn labels(&self) -> &Labels {
+        &self.labels
+    }
+
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    pub fn number(&self, value: f64, decimals: usize) -> String {
+        let value = self.rounding.round(value, decimals as u32);
+        let formatted = format!("{:.*}", decimals, value);
+        if self.decimal_separator == '.' {
+            formatted
+        } else {
+            formatted.replace('.', &self.decimal_separator.to_string())
+        }
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Locale {
+        Locale::english()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn number_uses_decimal_separator() {
+        assert_eq!("0.4294", Locale::english().number(0.42944, 4));
+        assert_eq!("-12,50", Locale::dutch().number(-12.5, 2));
+        assert_eq!("2.68", Locale::english().number(2.675, 2));
+        assert_eq!(
+            "0.12",
+            Locale::english()
+                .with_rounding(Rounding::TiesEven)
+                .number(0.125, 2)
+        );
+    }
+
+    #[test]
+    fn headers() {
+        let labels = Locale::dutch().labels().clone();
+        assert_eq!("Naam", labels.header(Header::Name));
+        assert_eq!("Moment [lb in]", labels.header(Header::MassMomentPoundInch));
+
+        let labels = labels.with_header(Header::LeverArm, "Arm [in]".to_string());
+        assert_eq!("Arm [in]", labels.header(Header::LeverArm));
+        assert_eq!("Massa [kg]", labels.header(Header::Mass));
+    }
+}
@@ -0,0 +1,615 @@
+//! Take-off and landing performance from the distance tables in the POH.
+//!
+//! A [`PerformanceTable`] holds the distances of a POH chart at a grid of pressure altitudes,
+//! temperatures and masses and interpolates linearly between them. Values outside the table are
+//! an error, POH charts must not be extrapolated.
+//!
+//! The tables are entered with the pressure altitude, which follows from the elevation and QNH
+//! with [`pressure_altitude_ft`]. The atmosphere helpers use the ICAO standard atmosphere up to
+//! the tropopause.
+
+use std::fmt;
+
+use crate::error::WeightBalanceError;
+use crate::weight_and_balance::{Airplane, Mass};
+use crate::wind::Wind;
+
+const ISA_SEA_LEVEL_PRESSURE_HPA: f64 = 1013.25;
+const ISA_SEA_LEVEL_TEMPERATURE_K: f64 = 288.15;
+const ISA_LAPSE_RATE_K_FT: f64 = 0.0019812;
+const ISA_TROPOPAUSE_FT: f64 = 36089.24;
+const KELVIN: f64 = 273.15;
+const APPROACH_SPEED_FACTOR: f64 = 1.3;
+const FEET_PER_MINUTE_IN_KNOT: f64 = 6076.12 / 60.0;
+// Exponent of the barometric formula, g / (R * L)
+const BAROMETRIC_EXPONENT: f64 = 5.255876;
+
+/// The altitude on the altimeter set to 1013.25 hPa.
+pub fn pressure_altitude_ft(elevation_ft: f64, qnh_hpa: f64) -> f64 {
+    let t0 = ISA_SEA_LEVEL_TEMPERATURE_K / ISA_LAPSE_RATE_K_FT;
+    elevation_ft
+        + t0 * (1.0 - (qnh_hpa / ISA_SEA_LEVEL_PRESSURE_HPA).powf(1.0 / BAROMETRIC_EXPONENT))
+}
+
+/// The ISA temperature in °C, constant above the tropopause.
+pub fn isa_temperature_c(pressure_altitude_ft: f64) -> f64 {
+    ISA_SEA_LEVEL_TEMPERATURE_K
+        - KELVIN
+        - ISA_LAPSE_RATE_K_FT * pressure_altitude_ft.min(ISA_TROPOPAUSE_FT)
+}
+
+/// The altitude in the standard atmosphere with the same air density as the given pressure
+/// altitude and outside air temperature.
+pub fn density_altitude_ft(pressure_altitude_ft: f64, temperature_c: f64) -> f64 {
+    let t0 = ISA_SEA_LEVEL_TEMPERATURE_K / ISA_LAPSE_RATE_K_FT;
+    let pressure_ratio = (1.0 - pressure_altitude_ft / t0).powf(BAROMETRIC_EXPONENT);
+    let temperature_ratio = (temperature_c + KELVIN) / ISA_SEA_LEVEL_TEMPERATURE_K;
+    let density_ratio = pressure_ratio / temperature_ratio;
+    t0 * (1.0 - density_ratio.powf(1.0 / (BAROMETRIC_EXPONENT - 1.0)))
+}
+
+// The air density relative to sea level in the standard atmosphere
+fn density_ratio(density_altitude_ft: f64) -> f64 {
+    let t0 = ISA_SEA_LEVEL_TEMPERATURE_K / ISA_LAPSE_RATE_K_FT;
+    (1.0 - density_altitude_ft / t0).powf(BAROMETRIC_EXPONENT - 1.0)
+}
+
+/// The stall speed at `actual_mass` from the POH stall speed at `reference_mass`, usually the
+/// maximum take-off mass. The speed is in the unit of `reference_vs`.
+pub fn stall_speed(reference_vs: f64, reference_mass: &Mass, actual_mass: &Mass) -> f64 {
+    reference_vs * (actual_mass.kilo() / reference_mass.kilo()).sqrt()
+}
+
+/// The final approach speed, 1.3 times the stall speed in the landing configuration.
+pub fn approach_speed(stall_speed: f64) -> f64 {
+    APPROACH_SPEED_FACTOR * stall_speed
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PerformanceError {
+    /// The grid sizes don't match the number of distances, or a grid is not ascending.
+    InvalidTable(String),
+    /// The named value is outside the range of the table.
+    OutsideTable(&'static str, f64),
+    WeightBalance(WeightBalanceError),
+}
+
+impl fmt::Display for PerformanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PerformanceError::InvalidTable(reason) => write!(f, "invalid table: {}", reason),
+            PerformanceError::OutsideTable(name, value) => {
+                write!(f, "{} {} is outside the table", name, value)
+            }
+            PerformanceError::WeightBalance(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for PerformanceError {}
+
+impl From<WeightBalanceError> for PerformanceError {
+    fn from(e: WeightBalanceError) -> PerformanceError {
+        PerformanceError::WeightBalance(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PerformanceTable {
+    pressure_altitudes_ft: Vec<f64>,
+    temperatures_c: Vec<f64>,
+    masses_kg: Vec<f64>,
+    distances_m: Vec<f64>,
+}
+
+// The grid cell of `value` and the position within it
+fn locate(grid: &[f64], value: f64, name: &'static str) -> Result<(usize, f64), PerformanceError> {
+    if grid.len() == 1 {
+        return if value == grid[0] {
+            Ok((0, 0.0))
+        } else {
+            Err(PerformanceError::OutsideTable(name, value))
+        };
+    }
+
+    let i = grid
+        .windows(2)
+        .position(|w| w[0] <= value && value <= w[1])
+        .ok_or(PerformanceError::OutsideTable(name, value))?;
+    Ok((i, (value - grid[i]) / (grid[i + 1] - grid[i])))
+}
+
+impl PerformanceTable {
+    /// The distances in meters are ordered by mass, then pressure altitude, then temperature:
+    /// first all temperatures at the lowest altitude and mass. Every grid is ascending.
+    pub fn new(
+        pressure_altitudes_ft: Vec<f64>,
+        temperatures_c: Vec<f64>,
+        masses_kg: Vec<f64>,
+        distances_m: Vec<f64>,
+    ) -> Result<PerformanceTable, PerformanceError> {
+        for (name, grid) in [
+            ("pressure altitudes", &pressure_altitudes_ft),
+            ("temperatures", &temperatures_c),
+            ("masses", &masses_kg),
+        ] {
+            if grid.is_empty() || grid.windows(2).any(|w| w[0] >= w[1]) {
+                return Err(PerformanceError::InvalidTable(format!(
+                    "{} must be ascending",
+                    name
+                )));
+            }
+        }
+
+        let expected = pressure_altitudes_ft.len() * temperatures_c.len() * masses_kg.len();
+        if distances_m.len() != expected {
+            return Err(PerformanceError::InvalidTable(format!(
+                "expected {} distances, got {}",
+                expected,
+                distances_m.len()
+            )));
+        }
+
+        Ok(PerformanceTable {
+            pressure_altitudes_ft,
+            temperatures_c,
+            masses_kg,
+            distances_m,
+        })
+    }
+
+    pub fn pressure_altitudes_ft(&self) -> &Vec<f64> {
+        &self.pressure_altitudes_ft
+    }
+
+    pub fn temperatures_c(&self) -> &Vec<f64> {
+        &self.temperatures_c
+    }
+
+    pub fn masses_kg(&self) -> &Vec<f64> {
+        &self.masses_kg
+    }
+
+    fn at(&self, mass: usize, altitude: usize, temperature: usize) -> f64 {
+        let index = (mass * self.pressure_altitudes_ft.len() + altitude)
+            * self.temperatures_c.len()
+            + temperature;
+        self.distances_m[index]
+    }
+
+    /// The distance in meters, interpolated between the surrounding grid points.
+    pub fn distance(
+        &self,
+        pressure_altitude_ft: f64,
+        temperature_c: f64,
+        mass: &Mass,
+    ) -> Result<f64, PerformanceError> {
+        let (a, fa) = locate(
+            &self.pressure_altitudes_ft,
+            pressure_altitude_ft,
+            "pressure altitude",
+        )?;
+        let (t, ft) = locate(&self.temperatures_c, temperature_c, "temperature")?;
+        let (m, fm) = locate(&self.masses_kg, mass.kilo(), "mass")?;
+
+        // Trilinear interpolation, a grid of one value has no upper neighbour
+        let upper = |i: usize, f: f64| if f > 0.0 { i + 1 } else { i };
+        let mut distance = 0.0;
+        for (mi, wm) in [(m, 1.0 - fm), (upper(m, fm), fm)] {
+            for (ai, wa) in [(a, 1.0 - fa), (upper(a, fa), fa)] {
+                for (ti, wt) in [(t, 1.0 - ft), (upper(t, ft), ft)] {
+                    let weight = wm * wa * wt;
+                    if weight > 0.0 {
+                        distance += weight * self.at(mi, ai, ti);
+                    }
+                }
+            }
+        }
+        Ok(distance)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum RunwayCondition {
+    PavedDry,
+    PavedWet,
+    GrassDry,
+    GrassWet,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Conditions {
+    pressure_altitude_ft: f64,
+    temperature_c: f64,
+    headwind_kt: f64,
+    runway: RunwayCondition,
+}
+
+impl Conditions {
+    /// A negative headwind is a tailwind.
+    pub fn new(
+        pressure_altitude_ft: f64,
+        temperature_c: f64,
+        headwind_kt: f64,
+        runway: RunwayCondition,
+    ) -> Conditions {
+        Conditions {
+            pressure_altitude_ft,
+            temperature_c,
+            headwind_kt,
+            runway,
+        }
+    }
+
+    /// The headwind component of the wind on a runway with the given heading.
+    pub fn with_wind(mut self, wind: &Wind, runway_heading_deg: f64) -> Conditions {
+        self.headwind_kt = wind.components(runway_heading_deg).headwind_kt();
+        self
+    }
+
+    pub fn pressure_altitude_ft(&self) -> f64 {
+        self.pressure_altitude_ft
+    }
+
+    pub fn temperature_c(&self) -> f64 {
+        self.temperature_c
+    }
+
+    pub fn headwind_kt(&self) -> f64 {
+        self.headwind_kt
+    }
+
+    pub fn runway(&self) -> RunwayCondition {
+        self.runway
+    }
+
+    pub fn density_altitude_ft(&self) -> f64 {
+        density_altitude_ft(self.pressure_altitude_ft, self.temperature_c)
+    }
+}
+
+/// A take-off or landing distance table with the POH corrections for wind and runway surface.
+/// The defaults are the common single engine piston corrections, use the factors from the POH
+/// when it gives them.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DistancePerformance {
+    table: PerformanceTable,
+    headwind_kt_per_10_percent: f64,
+    tailwind_kt_per_10_percent: f64,
+    paved_wet_factor: f64,
+    grass_dry_factor: f64,
+    grass_wet_factor: f64,
+}
+
+impl DistancePerformance {
+    /// 10% less per 9 kt headwind, 10% more per 2 kt tailwind, 20% more on dry grass and 30%
+    /// more on wet grass.
+    pub fn takeoff(table: PerformanceTable) -> DistancePerformance {
+        DistancePerformance {
+            table,
+            headwind_kt_per_10_percent: 9.0,
+            tailwind_kt_per_10_percent: 2.0,
+            paved_wet_factor: 1.0,
+            grass_dry_factor: 1.2,
+            grass_wet_factor: 1.3,
+        }
+    }
+
+    /// 10% less per 9 kt headwind, 10% more per 2 kt tailwind, 15% more on a wet paved or dry
+    /// grass runway and 35% more on wet grass.
+    pub fn landing(table: PerformanceTable) -> DistancePerformance {
+        DistancePerformance {
+            table,
+            headwind_kt_per_10_percent: 9.0,
+            tailwind_kt_per_10_percent: 2.0,
+            paved_wet_factor: 1.15,
+            grass_dry_factor: 1.15,
+            grass_wet_factor: 1.35,
+        }
+    }
+
+    /// Knots of headwind and tailwind that change the distance by 10%.
+    pub fn with_wind_corrections(
+        mut self,
+        headwind_kt: f64,
+        tailwind_kt: f64,
+    ) -> DistancePerformance {
+        self.headwind_kt_per_10_percent = headwind_kt;
+        self.tailwind_kt_per_10_percent = tailwind_kt;
+        self
+    }
+
+    /// Distance factors relative to a dry paved runway.
+    pub fn with_runway_factors(
+        mut self,
+        paved_wet: f64,
+        grass_dry: f64,
+        grass_wet: f64,
+    ) -> DistancePerformance {
+        self.paved_wet_factor = paved_wet;
+        self.grass_dry_factor = grass_dry;
+        self.grass_wet_factor = grass_wet;
+        self
+    }
+
+    pub fn table(&self) -> &PerformanceTable {
+        &self.table
+    }
+
+    /// The distance in meters at the given mass, corrected for wind and runway.
+    pub fn distance(&self, mass: &Mass, conditions: &Conditions) -> Result<f64, PerformanceError> {
+        let distance = self.table.distance(
+            conditions.pressure_altitude_ft,
+            conditions.temperature_c,
+            mass,
+        )?;
+
+        let wind = if conditions.headwind_kt >= 0.0 {
+            1.0 - 0.1 * conditions.headwind_kt / self.headwind_kt_per_10_percent
+        } else {
+            1.0 - 0.1 * conditions.headwind_kt / self.tailwind_kt_per_10_percent
+        };
+        let runway = match conditions.runway {
+            RunwayCondition::PavedDry => 1.0,
+            RunwayCondition::PavedWet => self.paved_wet_factor,
+            RunwayCondition::GrassDry => self.grass_dry_factor,
+            RunwayCondition::GrassWet => self.grass_wet_factor,
+        };
+
+        Ok(distance * wind.max(0.0) * runway)
+    }
+}
+
+/// Climb performance from the POH rate of climb at a reference mass at sea level. The rate
+/// falls linearly with density altitude to zero at the absolute ceiling and with the ratio of the
+/// reference mass to the actual mass.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClimbPerformance {
+    reference_rate_fpm: f64,
+    reference_mass: Mass,
+    absolute_ceiling_ft: f64,
+    climb_speed_kias: f64,
+}
+
+impl ClimbPerformance {
+    pub fn new(
+        reference_rate_fpm: f64,
+        reference_mass: Mass,
+        absolute_ceiling_ft: f64,
+        climb_speed_kias: f64,
+    ) -> ClimbPerformance {
+        ClimbPerformance {
+            reference_rate_fpm,
+            reference_mass,
+            absolute_ceiling_ft,
+            climb_speed_kias,
+        }
+    }
+
+    pub fn reference_rate_fpm(&self) -> f64 {
+        self.reference_rate_fpm
+    }
+
+    pub fn reference_mass(&self) -> &Mass {
+        &self.reference_mass
+    }
+
+    pub fn absolute_ceiling_ft(&self) -> f64 {
+        self.absolute_ceiling_ft
+    }
+
+    pub fn climb_speed_kias(&self) -> f64 {
+        self.climb_speed_kias
+    }
+
+    /// The rate of climb in feet per minute, zero at and above the absolute ceiling.
+    pub fn rate_of_climb_fpm(&self, mass: &Mass, density_altitude_ft: f64) -> f64 {
+        let altitude = 1.0 - density_altitude_ft / self.absolute_ceiling_ft;
+        let mass = self.reference_mass.kilo() / mass.kilo();
+        (self.reference_rate_fpm * altitude * mass).max(0.0)
+    }
+
+    /// The climb gradient in percent at the true airspeed of the climb speed.
+    pub fn gradient_percent(&self, mass: &Mass, density_altitude_ft: f64) -> f64 {
+        let true_airspeed_kt = self.climb_speed_kias / density_ratio(density_altitude_ft).sqrt();
+        100.0 * self.rate_of_climb_fpm(mass, density_altitude_ft)
+            / (true_airspeed_kt * FEET_PER_MINUTE_IN_KNOT)
+    }
+}
+
+impl Airplane {
+    /// The airplane with the MTOW of the hot-and-high entries of its limits at the density
+    /// altitude of `conditions`, see [`Airplane::at_density_altitude`].
+    pub fn at_conditions(&self, conditions: &Conditions) -> Airplane {
+        self.at_density_altitude(conditions.density_altitude_ft())
+    }
+
+    /// The take-off distance in meters at the take-off mass.
+    pub fn takeoff_distance(
+        &self,
+        performance: &DistancePerformance,
+        conditions: &Conditions,
+    ) -> Result<f64, PerformanceError> {
+        performance.distance(&self.total_mass(), conditions)
+    }
+
+    /// The landing distance in meters at the landing mass, the fuel on board minus the trip fuel.
+    pub fn landing_distance(
+        &self,
+        performance: &DistancePerformance,
+        conditions: &Conditions,
+    ) -> Result<f64, PerformanceError> {
+        performance.distance(&self.total_mass_landing()?, conditions)
+    }
+
+    /// The rate of climb in feet per minute at the take-off mass.
+    pub fn rate_of_climb_fpm(&self, climb: &ClimbPerformance, density_altitude_ft: f64) -> f64 {
+        climb.rate_of_climb_fpm(&self.total_mass(), density_altitude_ft)
+    }
+
+    /// The climb gradient in percent at the take-off mass.
+    pub fn climb_gradient_percent(
+        &self,
+        climb: &ClimbPerformance,
+        density_altitude_ft: f64,
+    ) -> f64 {
+        climb.gradient_percent(&self.total_mass(), density_altitude_ft)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{CenterOfGravity, LeverArm, Limits, Moment, Volume};
+
+    // Landing distance over 50 ft, two altitudes, two temperatures and two masses
+    fn table() -> PerformanceTable {
+        PerformanceTable::new(
+            vec![0.0, 2000.0],
+            vec![0.0, 20.0],
+            vec![600.0, 750.0],
+            vec![
+                400.0, 420.0, 430.0, 450.0, // 600 kg
+                480.0, 500.0, 510.0, 540.0, // 750 kg
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn interpolates_the_table() {
+        let table = table();
+
+        assert_eq!(400.0, table.distance(0.0, 0.0, &Mass::Kilo(600.0)).unwrap());
+        assert_eq!(
+            540.0,
+            table.distance(2000.0, 20.0, &Mass::Kilo(750.0)).unwrap()
+        );
+        assert!((table.distance(1000.0, 10.0, &Mass::Kilo(675.0)).unwrap() - 466.25).abs() < 1e-9);
+        assert_eq!(
+            Err(PerformanceError::OutsideTable("temperature", 30.0)),
+            table.distance(0.0, 30.0, &Mass::Kilo(600.0))
+        );
+        assert!(PerformanceTable::new(vec![0.0], vec![0.0], vec![600.0], vec![]).is_err());
+    }
+
+    #[test]
+    fn standard_atmosphere() {
+        assert!((pressure_altitude_ft(0.0, 1013.25)).abs() < 1e-9);
+        // About 27 ft per hPa near sea level
+        assert!((pressure_altitude_ft(100.0, 1003.25) - 374.2).abs() < 0.1);
+        assert!((isa_temperature_c(5000.0) - 5.094).abs() < 1e-9);
+        assert!((isa_temperature_c(40000.0) + 56.5).abs() < 1e-3);
+
+        assert!((density_altitude_ft(5000.0, isa_temperature_c(5000.0)) - 5000.0).abs() < 1e-6);
+        // A little less than the 120 ft per °C above ISA rule of thumb
+        let hot = density_altitude_ft(5000.0, isa_temperature_c(5000.0) + 20.0);
+        assert!((hot - 7272.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn stall_speed_with_mass() {
+        let mtow = Mass::Kilo(750.0);
+        assert_eq!(44.0, stall_speed(44.0, &mtow, &mtow));
+        // The stall speed scales with the square root of the mass
+        assert!(
+            (stall_speed(44.0, &mtow, &Mass::Kilo(562.5)) - 44.0 * 0.75_f64.sqrt()).abs() < 1e-9
+        );
+        assert!((approach_speed(40.0) - 52.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn climb_with_mass_and_altitude() {
+        let climb = ClimbPerformance::new(700.0, Mass::Kilo(750.0), 14000.0, 65.0);
+
+        assert_eq!(700.0, climb.rate_of_climb_fpm(&Mass::Kilo(750.0), 0.0));
+        assert!((climb.rate_of_climb_fpm(&Mass::Kilo(600.0), 7000.0) - 437.5).abs() < 1e-9);
+        assert_eq!(0.0, climb.rate_of_climb_fpm(&Mass::Kilo(750.0), 15000.0));
+
+        // 700 ft/min at 65 kt is about 10.6%, the true airspeed is higher at altitude
+        assert!((climb.gradient_percent(&Mass::Kilo(750.0), 0.0) - 10.63).abs() < 0.01);
+        let high = climb.gradient_percent(&Mass::Kilo(750.0), 7000.0);
+        assert!(high < 0.5 * 10.63);
+    }
+
+    #[test]
+    fn distances_at_takeoff_and_landing_mass() {
+        let plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![
+                Moment::new(
+                    "Empty".to_string(),
+                    LeverArm::Meter(0.4294),
+                    Mass::Kilo(600.0),
+                ),
+                Moment::new(
+                    "Fuel".to_string(),
+                    LeverArm::Meter(0.325),
+                    Mass::Avgas(Volume::Liter(50.0)),
+                ),
+            ],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(50.0),
+        );
+        let performance = DistancePerformance::landing(table());
+
+        let calm = Conditions::new(0.0, 0.0, 0.0, RunwayCondition::PavedDry);
+        assert_eq!(400.0, plane.landing_distance(&performance, &calm).unwrap());
+
+        let headwind = Conditions::new(0.0, 0.0, 9.0, RunwayCondition::GrassWet);
+        assert!(
+            (plane.landing_distance(&performance, &headwind).unwrap() - 400.0 * 0.9 * 1.35).abs()
+                < 1e-9
+        );
+
+        let crosswind = Conditions::new(0.0, 0.0, 0.0, RunwayCondition::PavedDry)
+            .with_wind(&Wind::new(330.0, 18.0), 270.0);
+        assert!((plane.landing_distance(&performance, &crosswind).unwrap() - 360.0).abs() < 1e-9);
+
+        let tailwind = Conditions::new(0.0, 0.0, -2.0, RunwayCondition::PavedDry);
+        assert!((plane.landing_distance(&performance, &tailwind).unwrap() - 440.0).abs() < 1e-9);
+
+        // Take-off at 636 kg with all fuel on board
+        let takeoff = DistancePerformance::takeoff(table());
+        let grass = Conditions::new(0.0, 0.0, 0.0, RunwayCondition::GrassDry);
+        assert!((plane.takeoff_distance(&takeoff, &grass).unwrap() - 419.2 * 1.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hot_and_high_mtow() {
+        let plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            )
+            .with_altitude_mtow(5000.0, Mass::Kilo(720.0)),
+            Volume::Liter(17.0),
+        );
+
+        let mtow = |temperature_c: f64| {
+            let conditions = Conditions::new(4000.0, temperature_c, 0.0, RunwayCondition::PavedDry);
+            plane.at_conditions(&conditions).limits().mtow().kilo()
+        };
+        assert_eq!(750.0, mtow(isa_temperature_c(4000.0)));
+        assert_eq!(720.0, mtow(30.0));
+    }
+}
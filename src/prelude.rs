@@ -0,0 +1,17 @@
+//! The commonly used types, `use airplane::prelude::*;` to get started.
+
+pub use crate::error::WeightBalanceError;
+pub use crate::loading::{
+    BaggageItem, FuelQuantity, FuelUplift, Loading, Manifest, Occupant, StationDefault, StationItem,
+};
+pub use crate::registration::Registration;
+pub use crate::types::{FuelType, VolumeType};
+#[cfg(feature = "visualizer")]
+pub use crate::visualizer::{
+    weight_and_balance_chart, weight_and_balance_table, weight_and_balance_table_pages, ChartUnits,
+    TableUnits, Visualization, WeightBalanceChartVisualization, WeightBalanceTableVisualization,
+};
+pub use crate::weight_and_balance::{
+    Airplane, CenterOfGravity, LeverArm, LimitKind, Limits, Mass, MassMoment, Moment, Volume,
+    WeightBalanceSummary,
+};
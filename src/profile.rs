@@ -0,0 +1,916 @@
+//! Aircraft profiles: the empty aircraft with its stations, fuel tanks, limits and trip fuel in a
+//! form that can be kept in version-controlled files.
+//!
+//! With the `toml` feature a profile is read and written with [`Airplane::from_toml`] and
+//! [`Airplane::to_toml`]. Arms are in meters aft of datum, masses in kilograms and fuel volumes
+//! either `liter` or `gallon`:
+//!
+//! ```toml
+//! schema_version = 2
+//! callsign = "PHDHA"
+//! trip_fuel = { liter = 17.0 }
+//!
+//! [limits]
+//! minimum_weight_kg = 558.0
+//! mtow_kg = 750.0
+//! forward_cg_m = 0.427
+//! rearward_cg_m = 0.523
+//!
+//! [[stations]]
+//! name = "Empty aircraft"
+//! arm_m = 0.4294
+//! mass_kg = 517.0
+//!
+//! [[stations]]
+//! name = "Pilot"
+//! arm_m = 0.515
+//! mass_kg = 80.0
+//!
+//! [[tanks]]
+//! name = "Fuel"
+//! arm_m = 0.325
+//! fuel = "avgas"
+//! volume = { liter = 55.0 }
+//! usable_capacity = { liter = 109.0 }
+//! total_capacity = { liter = 113.0 }
+//! ```
+//!
+//! Stricter limits of a club or operator are added as `[[tagged_limits]]` tables with the same
+//! fields and a `kind` of `"policy"`, `"operational"` or `"structural"`, leaving `[limits]` the
+//! certified envelope. A hot-and-high placard is a `[[limits.altitude_mtow]]` table with the
+//! `density_altitude_ft` from which the `mtow_kg` applies.
+//!
+//! Stations are loaded in order, followed by the tanks. The capacities of a tank are optional,
+//! the total capacity defaults to the usable capacity. A tank without a fuel moment has its
+//! capacities in a `[[capacities]]` table.
+//!
+//! The order the fuel burns in is a `fuel_burn` of `"last"`, `"proportional"` or
+//! `{ sequence = ["Aux", "Main"] }`, and the `diversion_fuel` and `landing_fuel` are volumes like
+//! the trip fuel. The `[warning_thresholds]` and the `[[limit_overrides]]` of relaxed policy
+//! limits are written when they are set.
+//!
+//! Every written document carries a `schema_version`. Documents from older crate versions are
+//! upgraded with [`migrate_toml`] and [`migrate_json`] when they are read; documents without a
+//! version are version 1.
+//!
+//! With the `json` feature the complete state, an [`Airplane`], a [`Loading`] or a [`Manifest`]
+//! with its results, is exchanged as JSON, as are saved [`LoadingTemplates`] and the
+//! [`WeightBalanceSummary`] of a calculation and its [`FinalizedCalculation`]. Unlike the TOML
+//! profile this keeps every unit variant as it was entered, e.g. `{"mogas": {"gallon": 12.0}}` or
+//! `{"millimeter": 427.0}`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "json")]
+use crate::finalized::FinalizedCalculation;
+#[cfg(feature = "json")]
+use crate::loading::{Loading, LoadingTemplates, Manifest};
+use crate::registration::Registration;
+use crate::types::FuelType;
+#[cfg(feature = "json")]
+use crate::weight_and_balance::WeightBalanceSummary;
+use crate::weight_and_balance::{
+    Airplane, CenterOfGravity, FuelBurn, LeverArm, LimitKind, Limits, Mass, Moment, Tank, Volume,
+    WarningThresholds,
+};
+
+pub const SCHEMA_VERSION: u32 = 2;
+
+const UNVERSIONED: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub schema_version: u32,
+    pub callsign: Registration,
+    pub trip_fuel: Volume,
+    pub limits: LimitsProfile,
+    #[serde(default)]
+    pub stations: Vec<StationProfile>,
+    #[serde(default)]
+    pub tanks: Vec<TankProfile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tagged_limits: Vec<LimitsProfile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capacities: Vec<CapacityProfile>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub fuel_burn: FuelBurn,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diversion_fuel: Option<Volume>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub landing_fuel: Option<Volume>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub warning_thresholds: WarningThresholds,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub limit_overrides: Vec<LimitOverrideProfile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitsProfile {
+    pub minimum_weight_kg: f64,
+    pub mtow_kg: f64,
+    pub forward_cg_m: f64,
+    pub rearward_cg_m: f64,
+    #[serde(default, skip_serializing_if = "is_structural")]
+    pub kind: LimitKind,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub altitude_mtow: Vec<AltitudeMtowProfile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AltitudeMtowProfile {
+    pub density_altitude_ft: f64,
+    pub mtow_kg: f64,
+}
+
+fn is_structural(kind: &LimitKind) -> bool {
+    *kind == LimitKind::Structural
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+impl From<&Limits> for LimitsProfile {
+    fn from(limits: &Limits) -> LimitsProfile {
+        LimitsProfile {
+            minimum_weight_kg: limits.minimum_weight().kilo(),
+            mtow_kg: limits.mtow().kilo(),
+            forward_cg_m: limits.forward_cg_limit().meter(),
+            rearward_cg_m: limits.rearward_cg_limit().meter(),
+            kind: limits.kind(),
+            altitude_mtow: limits
+                .altitude_mtows()
+                .iter()
+                .map(|(altitude, mtow)| AltitudeMtowProfile {
+                    density_altitude_ft: *altitude,
+                    mtow_kg: mtow.kilo(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<&LimitsProfile> for Limits {
+    type Error = ProfileError;
+
+    fn try_from(limits: &LimitsProfile) -> Result<Limits, ProfileError> {
+        if limits.minimum_weight_kg > limits.mtow_kg {
+            return Err(ProfileError::InvalidLimits(
+                "minimum weight exceeds MTOW".to_string(),
+            ));
+        }
+        if limits.forward_cg_m > limits.rearward_cg_m {
+            return Err(ProfileError::InvalidLimits(
+                "forward CG limit is aft of the rearward CG limit".to_string(),
+            ));
+        }
+
+        Ok(limits.altitude_mtow.iter().fold(
+            Limits::new(
+                Mass::Kilo(limits.minimum_weight_kg),
+                Mass::Kilo(limits.mtow_kg),
+                CenterOfGravity::Meter(limits.forward_cg_m),
+                CenterOfGravity::Meter(limits.rearward_cg_m),
+            )
+            .with_kind(limits.kind),
+            |l, entry| l.with_altitude_mtow(entry.density_altitude_ft, Mass::Kilo(entry.mtow_kg)),
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StationProfile {
+    pub name: String,
+    pub arm_m: f64,
+    pub mass_kg: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TankProfile {
+    pub name: String,
+    pub arm_m: f64,
+    pub fuel: FuelType,
+    pub volume: Volume,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usable_capacity: Option<Volume>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_capacity: Option<Volume>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// The capacities of a tank without a fuel moment in the profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapacityProfile {
+    pub name: String,
+    pub usable_capacity: Volume,
+    pub total_capacity: Volume,
+}
+
+/// Tagged limits relaxed for a flight, the tagged limits of the profile are the relaxed ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LimitOverrideProfile {
+    pub index: usize,
+    pub original: LimitsProfile,
+    pub relaxed: LimitsProfile,
+    pub justification: String,
+}
+
+#[derive(Debug)]
+pub enum ProfileError {
+    /// The limits contradict each other, e.g. an aft CG limit forward of the forward limit.
+    InvalidLimits(String),
+    /// A tank holds more fuel than its usable capacity.
+    InvalidTank(String),
+    /// The document is written by a newer crate version or its version is not a number.
+    UnsupportedVersion(String),
+    #[cfg(feature = "toml")]
+    TomlDeserialize(toml::de::Error),
+    #[cfg(feature = "toml")]
+    TomlSerialize(toml::ser::Error),
+    #[cfg(feature = "json")]
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::InvalidLimits(reason) => write!(f, "invalid limits: {}", reason),
+            ProfileError::InvalidTank(reason) => write!(f, "invalid tank: {}", reason),
+            ProfileError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported schema version {}, expected at most {}",
+                version, SCHEMA_VERSION
+            ),
+            #[cfg(feature = "toml")]
+            ProfileError::TomlDeserialize(e) => write!(f, "cannot read TOML profile: {}", e),
+            #[cfg(feature = "toml")]
+            ProfileError::TomlSerialize(e) => write!(f, "cannot write TOML profile: {}", e),
+            #[cfg(feature = "json")]
+            ProfileError::Json(e) => write!(f, "invalid JSON: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+fn annotated(moment: Moment, note: Option<String>, metadata: BTreeMap<String, String>) -> Moment {
+    let moment = match note {
+        Some(note) => moment.with_note(note),
+        None => moment,
+    };
+    metadata.into_iter().fold(moment, |moment, (key, value)| {
+        moment.with_metadata(key, value)
+    })
+}
+
+impl From<&Airplane> for Profile {
+    fn from(plane: &Airplane) -> Profile {
+        let mut stations = vec![];
+        let mut tanks = vec![];
+
+        for m in plane.iter() {
+            match m.mass() {
+                Mass::Kilo(kg) => stations.push(StationProfile {
+                    name: m.name().to_string(),
+                    arm_m: m.lever_arm().meter(),
+                    mass_kg: *kg,
+                    note: m.note().map(str::to_string),
+                    metadata: m.metadata().clone(),
+                }),
+                Mass::Avgas(v) => tanks.push(TankProfile {
+                    name: m.name().to_string(),
+                    arm_m: m.lever_arm().meter(),
+                    fuel: FuelType::Avgas,
+                    volume: v.clone(),
+                    usable_capacity: plane.tank(m.name()).map(|t| t.usable().clone()),
+                    total_capacity: plane.tank(m.name()).map(|t| t.total().clone()),
+                    note: m.note().map(str::to_string),
+                    metadata: m.metadata().clone(),
+                }),
+                Mass::Mogas(v) => tanks.push(TankProfile {
+                    name: m.name().to_string(),
+                    arm_m: m.lever_arm().meter(),
+                    fuel: FuelType::Mogas,
+                    volume: v.clone(),
+                    usable_capacity: plane.tank(m.name()).map(|t| t.usable().clone()),
+                    total_capacity: plane.tank(m.name()).map(|t| t.total().clone()),
+                    note: m.note().map(str::to_string),
+                    metadata: m.metadata().clone(),
+                }),
+            }
+        }
+
+        // The capacities of the tanks with a fuel moment are written with the moment
+        let capacities = plane
+            .tanks()
+            .iter()
+            .filter(|t| {
+                !plane
+                    .iter()
+                    .any(|m| m.name() == t.name() && m.mass().is_fuel())
+            })
+            .map(|t| CapacityProfile {
+                name: t.name().to_string(),
+                usable_capacity: t.usable().clone(),
+                total_capacity: t.total().clone(),
+            })
+            .collect();
+
+        Profile {
+            schema_version: SCHEMA_VERSION,
+            callsign: plane.registration().clone(),
+            trip_fuel: plane.fuel_consumption_trip().clone(),
+            limits: plane.limits().into(),
+            stations,
+            tanks,
+            tagged_limits: plane.tagged_limits().iter().map(Into::into).collect(),
+            capacities,
+            fuel_burn: plane.fuel_burn().clone(),
+            diversion_fuel: plane.fuel_consumption_diversion().cloned(),
+            landing_fuel: plane.landing_fuel().cloned(),
+            warning_thresholds: plane.warning_thresholds().clone(),
+            limit_overrides: plane
+                .limit_overrides()
+                .iter()
+                .map(|o| LimitOverrideProfile {
+                    index: o.index(),
+                    original: o.original().into(),
+                    relaxed: o.relaxed().into(),
+                    justification: o.justification().to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<Profile> for Airplane {
+    type Error = ProfileError;
+
+    fn try_from(profile: Profile) -> Result<Airplane, ProfileError> {
+        let limits = Limits::try_from(&profile.limits)?;
+        let mut tagged_limits = profile
+            .tagged_limits
+            .iter()
+            .map(Limits::try_from)
+            .collect::<Result<Vec<Limits>, ProfileError>>()?;
+        let overrides = profile
+            .limit_overrides
+            .iter()
+            .map(|o| {
+                Ok((
+                    o.index,
+                    Limits::try_from(&o.original)?,
+                    Limits::try_from(&o.relaxed)?,
+                    o.justification.clone(),
+                ))
+            })
+            .collect::<Result<Vec<_>, ProfileError>>()?;
+        // The limits are relaxed again from their originals, the last override first
+        for (index, original, _, _) in overrides.iter().rev() {
+            if let Some(limits) = tagged_limits.get_mut(*index) {
+                *limits = original.clone();
+            }
+        }
+
+        let stations = profile.stations.into_iter().map(|s| {
+            let moment = Moment::new(s.name, LeverArm::Meter(s.arm_m), Mass::Kilo(s.mass_kg));
+            annotated(moment, s.note, s.metadata)
+        });
+        let capacities: Vec<Tank> = profile
+            .tanks
+            .iter()
+            .filter_map(|t| {
+                let usable = t.usable_capacity.clone()?;
+                let total = t.total_capacity.clone().unwrap_or(usable.clone());
+                Some(Tank::new(t.name.clone(), usable, total))
+            })
+            .chain(
+                profile
+                    .capacities
+                    .into_iter()
+                    .map(|c| Tank::new(c.name, c.usable_capacity, c.total_capacity)),
+            )
+            .collect();
+        let tanks = profile.tanks.into_iter().map(|t| {
+            let mass = match t.fuel {
+                FuelType::Avgas => Mass::Avgas(t.volume),
+                FuelType::Mogas => Mass::Mogas(t.volume),
+            };
+            annotated(
+                Moment::new(t.name, LeverArm::Meter(t.arm_m), mass),
+                t.note,
+                t.metadata,
+            )
+        });
+
+        let mut plane = Airplane::new(
+            profile.callsign,
+            stations.chain(tanks).collect(),
+            limits,
+            profile.trip_fuel,
+        )
+        .with_fuel_burn(profile.fuel_burn)
+        .with_warning_thresholds(profile.warning_thresholds);
+        if let Some(diversion) = profile.diversion_fuel {
+            plane = plane.with_fuel_consumption_diversion(diversion);
+        }
+        if let Some(landing) = profile.landing_fuel {
+            plane = plane.with_landing_fuel(landing);
+        }
+        for tank in capacities {
+            plane.add_tank(tank);
+        }
+        for limits in tagged_limits {
+            plane.add_limits(limits);
+        }
+        for (index, _, relaxed, justification) in overrides {
+            plane
+                .override_limits(index, relaxed, justification)
+                .map_err(|e| ProfileError::InvalidLimits(e.to_string()))?;
+        }
+        plane
+            .check_tank_capacity()
+            .map_err(|e| ProfileError::InvalidTank(e.to_string()))?;
+
+        Ok(plane)
+    }
+}
+
+fn document_version(version: Option<Option<i64>>) -> Result<u32, ProfileError> {
+    match version {
+        None => Ok(UNVERSIONED),
+        Some(Some(v)) if v >= UNVERSIONED as i64 && v <= SCHEMA_VERSION as i64 => Ok(v as u32),
+        Some(v) => Err(ProfileError::UnsupportedVersion(
+            v.map_or("?".to_string(), |v| v.to_string()),
+        )),
+    }
+}
+
+/// Applies the migration steps from `version` up to [`SCHEMA_VERSION`], `steps[i]` upgrades
+/// version `i + 1` to `i + 2`.
+fn migrate<D>(document: &mut D, version: u32, steps: &[fn(&mut D)]) {
+    for step in &steps[version as usize - 1..] {
+        step(document);
+    }
+}
+
+// Version 1 documents were written before the version was embedded, the layout is unchanged.
+#[cfg(feature = "toml")]
+const TOML_MIGRATIONS: [fn(&mut toml::Table); 1] = [|_| {}];
+
+#[cfg(feature = "json")]
+const JSON_MIGRATIONS: [fn(&mut serde_json::Map<String, serde_json::Value>); 1] = [|_| {}];
+
+/// Upgrades a TOML profile in place to the current [`SCHEMA_VERSION`].
+#[cfg(feature = "toml")]
+pub fn migrate_toml(profile: &mut toml::Table) -> Result<(), ProfileError> {
+    let version = document_version(profile.get("schema_version").map(|v| v.as_integer()))?;
+
+    migrate(profile, version, &TOML_MIGRATIONS);
+    profile.insert(
+        "schema_version".to_string(),
+        toml::Value::Integer(SCHEMA_VERSION as i64),
+    );
+    Ok(())
+}
+
+/// Upgrades a JSON document in place to the current [`SCHEMA_VERSION`].
+#[cfg(feature = "json")]
+pub fn migrate_json(document: &mut serde_json::Value) -> Result<(), ProfileError> {
+    let document = document
+        .as_object_mut()
+        .ok_or(ProfileError::UnsupportedVersion("?".to_string()))?;
+    let version = document_version(document.get("schema_version").map(|v| v.as_i64()))?;
+
+    migrate(document, version, &JSON_MIGRATIONS);
+    document.insert(
+        "schema_version".to_string(),
+        serde_json::Value::from(SCHEMA_VERSION),
+    );
+    Ok(())
+}
+
+#[cfg(feature = "toml")]
+impl Airplane {
+    pub fn from_toml(profile: &str) -> Result<Airplane, ProfileError> {
+        let mut table: toml::Table =
+            toml::from_str(profile).map_err(ProfileError::TomlDeserialize)?;
+        migrate_toml(&mut table)?;
+
+        let profile: Profile = table.try_into().map_err(ProfileError::TomlDeserialize)?;
+        profile.try_into()
+    }
+
+    pub fn to_toml(&self) -> Result<String, ProfileError> {
+        toml::to_string(&Profile::from(self)).map_err(ProfileError::TomlSerialize)
+    }
+}
+
+#[cfg(feature = "json")]
+#[derive(Serialize)]
+struct Versioned<'a, T> {
+    schema_version: u32,
+    #[serde(flatten)]
+    document: &'a T,
+}
+
+#[cfg(feature = "json")]
+fn to_json<T: Serialize>(document: &T) -> Result<String, ProfileError> {
+    serde_json::to_string(&Versioned {
+        schema_version: SCHEMA_VERSION,
+        document,
+    })
+    .map_err(ProfileError::Json)
+}
+
+#[cfg(feature = "json")]
+fn from_json<T: serde::de::DeserializeOwned>(json: &str) -> Result<T, ProfileError> {
+    let mut document: serde_json::Value = serde_json::from_str(json).map_err(ProfileError::Json)?;
+    migrate_json(&mut document)?;
+
+    serde_json::from_value(document).map_err(ProfileError::Json)
+}
+
+#[cfg(feature = "json")]
+impl Airplane {
+    pub fn from_json(json: &str) -> Result<Airplane, ProfileError> {
+        from_json(json)
+    }
+
+    pub fn to_json(&self) -> Result<String, ProfileError> {
+        to_json(self)
+    }
+}
+
+#[cfg(feature = "json")]
+impl Loading {
+    pub fn from_json(json: &str) -> Result<Loading, ProfileError> {
+        from_json(json)
+    }
+
+    pub fn to_json(&self) -> Result<String, ProfileError> {
+        to_json(self)
+    }
+}
+
+#[cfg(feature = "json")]
+impl Manifest {
+    pub fn from_json(json: &str) -> Result<Manifest, ProfileError> {
+        from_json(json)
+    }
+
+    pub fn to_json(&self) -> Result<String, ProfileError> {
+        to_json(self)
+    }
+}
+
+#[cfg(feature = "json")]
+impl LoadingTemplates {
+    pub fn from_json(json: &str) -> Result<LoadingTemplates, ProfileError> {
+        from_json(json)
+    }
+
+    pub fn to_json(&self) -> Result<String, ProfileError> {
+        to_json(self)
+    }
+}
+
+#[cfg(feature = "json")]
+impl WeightBalanceSummary {
+    pub fn from_json(json: &str) -> Result<WeightBalanceSummary, ProfileError> {
+        from_json(json)
+    }
+
+    pub fn to_json(&self) -> Result<String, ProfileError> {
+        to_json(self)
+    }
+}
+
+#[cfg(feature = "json")]
+impl FinalizedCalculation {
+    pub fn from_json(json: &str) -> Result<FinalizedCalculation, ProfileError> {
+        from_json(json)
+    }
+
+    /// The same calculation always gives the same JSON, to archive or to hash.
+    pub fn to_canonical_json(&self) -> Result<String, ProfileError> {
+        to_json(self)
+    }
+}
+
+#[cfg(all(test, feature = "toml"))]
+mod test {
+    use super::*;
+
+    const PROFILE: &str = r#"
+callsign = "PHDHA"
+trip_fuel = { liter = 17.0 }
+
+[limits]
+minimum_weight_kg = 558.0
+mtow_kg = 750.0
+forward_cg_m = 0.427
+rearward_cg_m = 0.523
+
+[[stations]]
+name = "Empty aircraft"
+arm_m = 0.4294
+mass_kg = 517.0
+
+[[stations]]
+name = "Pilot"
+arm_m = 0.515
+mass_kg = 80.0
+
+[[tanks]]
+name = "Fuel"
+arm_m = 0.325
+fuel = "avgas"
+volume = { liter = 55.0 }
+"#;
+
+    #[test]
+    fn read_toml_profile() {
+        let plane = Airplane::from_toml(PROFILE).unwrap();
+
+        assert_eq!("PHDHA", plane.registration().compact());
+        assert_eq!(3, plane.len());
+        assert_eq!(517.0 + 80.0 + 55.0 * 0.72, plane.total_mass().kilo());
+        assert_eq!(17.0, plane.fuel_consumption_trip().to_liter());
+        assert!(plane.within_limits());
+    }
+
+    #[test]
+    fn toml_round_trip() {
+        let plane = Airplane::from_toml(PROFILE).unwrap();
+        let again = Airplane::from_toml(&plane.to_toml().unwrap()).unwrap();
+
+        assert_eq!(plane.total_mass().kilo(), again.total_mass().kilo());
+        assert_eq!(
+            plane.total_mass_moment().kgm(),
+            again.total_mass_moment().kgm()
+        );
+        assert!(again.iter().last().unwrap().mass().is_fuel());
+    }
+
+    #[test]
+    fn invalid_toml_profile() {
+        assert!(matches!(
+            Airplane::from_toml("callsign = 1"),
+            Err(ProfileError::TomlDeserialize(_))
+        ));
+        assert!(matches!(
+            Airplane::from_toml(&PROFILE.replace("\"PHDHA\"", "\"PH-\"")),
+            Err(ProfileError::TomlDeserialize(_))
+        ));
+        assert!(matches!(
+            Airplane::from_toml(&PROFILE.replace("mtow_kg = 750.0", "mtow_kg = 500.0")),
+            Err(ProfileError::InvalidLimits(_))
+        ));
+    }
+
+    #[test]
+    fn notes_and_metadata() {
+        let profile = PROFILE.replace(
+            "name = \"Pilot\"",
+            "name = \"Pilot\"\nnote = \"Child seat installed\"\nmetadata = { seat = \"2\" }",
+        );
+        let plane = Airplane::from_toml(&profile).unwrap();
+        let pilot = plane.iter().nth(1).unwrap();
+        assert_eq!(Some("Child seat installed"), pilot.note());
+        assert_eq!(Some(&"2".to_string()), pilot.metadata().get("seat"));
+
+        let again = Airplane::from_toml(&plane.to_toml().unwrap()).unwrap();
+        assert_eq!(plane.iter().nth(1), again.iter().nth(1));
+        assert!(!Airplane::from_toml(PROFILE)
+            .unwrap()
+            .to_toml()
+            .unwrap()
+            .contains("note"));
+    }
+
+    #[test]
+    fn tank_capacity() {
+        let profile = PROFILE.replace(
+            "volume = { liter = 55.0 }",
+            "volume = { liter = 55.0 }\nusable_capacity = { liter = 109.0 }",
+        );
+        let plane = Airplane::from_toml(&profile).unwrap();
+        let tank = plane.tank("Fuel").unwrap();
+        assert_eq!(109.0, tank.total().to_liter());
+
+        let again = Airplane::from_toml(&plane.to_toml().unwrap()).unwrap();
+        assert_eq!(plane.tanks(), again.tanks());
+
+        assert!(matches!(
+            Airplane::from_toml(&profile.replace("liter = 55.0", "liter = 120.0")),
+            Err(ProfileError::InvalidTank(_))
+        ));
+    }
+
+    #[test]
+    fn every_field_round_trips() {
+        let mut plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![
+                Moment::new(
+                    "Empty".to_string(),
+                    LeverArm::Meter(0.4294),
+                    Mass::Kilo(517.0),
+                ),
+                Moment::new(
+                    "Main".to_string(),
+                    LeverArm::Meter(0.325),
+                    Mass::Avgas(Volume::Liter(55.0)),
+                ),
+                Moment::new(
+                    "Aux".to_string(),
+                    LeverArm::Meter(1.1),
+                    Mass::Mogas(Volume::Gallon(5.0)),
+                ),
+            ],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Meter(0.427),
+                CenterOfGravity::Meter(0.523),
+            )
+            .with_altitude_mtow(5000.0, Mass::Kilo(720.0)),
+            Volume::Liter(17.0),
+        )
+        .with_fuel_burn(FuelBurn::Sequence(vec![
+            "Aux".to_string(),
+            "Main".to_string(),
+        ]))
+        .with_fuel_consumption_diversion(Volume::Liter(8.0))
+        .with_landing_fuel(Volume::Liter(30.0))
+        .with_warning_thresholds(
+            WarningThresholds::new()
+                .with_mtow_fraction(0.05)
+                .with_reserve(45.0, Volume::Liter(25.0)),
+        );
+        plane.add_tank(Tank::new(
+            "Main".to_string(),
+            Volume::Liter(109.0),
+            Volume::Liter(113.0),
+        ));
+        plane.add_tank(Tank::new(
+            "Aux".to_string(),
+            Volume::Gallon(10.0),
+            Volume::Gallon(10.5),
+        ));
+        plane.add_tank(Tank::new(
+            "Ferry".to_string(),
+            Volume::Liter(50.0),
+            Volume::Liter(52.0),
+        ));
+        plane.add_limits(
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(700.0),
+                CenterOfGravity::Meter(0.44),
+                CenterOfGravity::Meter(0.51),
+            )
+            .with_kind(LimitKind::Policy),
+        );
+        plane
+            .override_limits(
+                0,
+                Limits::new(
+                    Mass::Kilo(558.0),
+                    Mass::Kilo(720.0),
+                    CenterOfGravity::Meter(0.43),
+                    CenterOfGravity::Meter(0.52),
+                ),
+                "Ferry flight".to_string(),
+            )
+            .unwrap();
+
+        let toml = plane.to_toml().unwrap();
+        assert!(toml.contains("[[capacities]]"));
+        assert_eq!(plane, Airplane::from_toml(&toml).unwrap());
+    }
+
+    #[test]
+    fn policy_limits() {
+        let profile = PROFILE.to_string()
+            + r#"
+[[tagged_limits]]
+kind = "policy"
+minimum_weight_kg = 558.0
+mtow_kg = 700.0
+forward_cg_m = 0.44
+rearward_cg_m = 0.51
+"#;
+        let plane = Airplane::from_toml(&profile).unwrap();
+        assert_eq!(LimitKind::Structural, plane.limits().kind());
+        assert_eq!(LimitKind::Policy, plane.tagged_limits()[0].kind());
+        assert!(plane.within_limits());
+        assert_eq!(Some(false), plane.summary().within_policy_limits());
+
+        let again = Airplane::from_toml(&plane.to_toml().unwrap()).unwrap();
+        assert_eq!(plane.tagged_limits(), again.tagged_limits());
+        assert!(matches!(
+            Airplane::from_toml(&profile.replace("mtow_kg = 700.0", "mtow_kg = 500.0")),
+            Err(ProfileError::InvalidLimits(_))
+        ));
+    }
+
+    #[test]
+    fn altitude_mtow() {
+        let profile = PROFILE.to_string()
+            + r#"
+[[limits.altitude_mtow]]
+density_altitude_ft = 5000.0
+mtow_kg = 720.0
+"#;
+        let plane = Airplane::from_toml(&profile).unwrap();
+        assert_eq!(
+            &vec![(5000.0, Mass::Kilo(720.0))],
+            plane.limits().altitude_mtows()
+        );
+
+        let again = Airplane::from_toml(&plane.to_toml().unwrap()).unwrap();
+        assert_eq!(plane.limits(), again.limits());
+    }
+
+    #[test]
+    fn schema_version() {
+        let plane = Airplane::from_toml(PROFILE).unwrap();
+        assert!(plane.to_toml().unwrap().contains("schema_version = 2"));
+
+        let mut table: toml::Table = toml::from_str(PROFILE).unwrap();
+        migrate_toml(&mut table).unwrap();
+        assert_eq!(Some(2), table["schema_version"].as_integer());
+
+        assert!(matches!(
+            Airplane::from_toml(&format!("schema_version = 3\n{}", PROFILE)),
+            Err(ProfileError::UnsupportedVersion(v)) if v == "3"
+        ));
+        assert!(matches!(
+            Airplane::from_toml(&format!("schema_version = \"two\"\n{}", PROFILE)),
+            Err(ProfileError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn loading_templates_json() {
+        use crate::loading::LoadingTemplate;
+
+        let mut templates = LoadingTemplates::new();
+        templates.add(LoadingTemplate::new(
+            "Instructor + student, half fuel".to_string(),
+            Loading::new(),
+        ));
+
+        let json = templates.to_json().unwrap();
+        assert_eq!(templates, LoadingTemplates::from_json(&json).unwrap());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_keeps_unit_variants() {
+        use crate::loading::FuelUplift;
+
+        let plane = Airplane::from_toml(PROFILE).unwrap();
+        let mut loading = Loading::new();
+        loading.add_fuel(FuelUplift::new(
+            "Aux".to_string(),
+            LeverArm::Meter(0.6),
+            FuelType::Mogas,
+            Volume::Gallon(2.0),
+            Volume::Gallon(3.0),
+        ));
+        let manifest = Manifest::new(plane, loading);
+
+        let json = manifest.to_json().unwrap();
+        assert!(json.starts_with(r#"{"schema_version":2,"#));
+        assert!(json.contains(r#"{"mogas":{"gallon":5.0}}"#));
+
+        let again = Manifest::from_json(&json).unwrap();
+        assert_eq!(
+            manifest.summary().takeoff_mass().kilo(),
+            again.summary().takeoff_mass().kilo()
+        );
+        assert_eq!(
+            manifest.airplane().total_mass_moment().kgm(),
+            again.airplane().total_mass_moment().kgm()
+        );
+        assert!(Airplane::from_json("{}").is_err());
+
+        // Documents from before the version was embedded
+        let unversioned = json.replacen(r#""schema_version":2,"#, "", 1);
+        assert!(Manifest::from_json(&unversioned).is_ok());
+    }
+}
@@ -0,0 +1,181 @@
+//! Aircraft registrations as in `PH-DHA` or `N123AB`.
+//!
+//! A registration is the nationality prefix and the mark. With a hyphen the prefix is everything
+//! before it, without one the longest known prefix is taken, so ambiguous registrations such as
+//! Canadian `C-` and Portuguese `CS-` marks should be written with the hyphen. US registrations
+//! are written without a hyphen, all others with one. An [`Airplane`] carries its registration,
+//! which is how its charts and reports are captioned and, without the hyphen, how their files are
+//! named.
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+#[cfg(doc)]
+use crate::weight_and_balance::Airplane;
+
+// Nationality prefixes that are recognised without a hyphen
+const PREFIXES: [&str; 48] = [
+    "N", "C", "D", "F", "G", "I", "B", "M", "PH", "OO", "OY", "OH", "OE", "OK", "OM", "HB", "SE",
+    "LN", "LX", "EI", "EC", "CS", "SP", "SX", "HA", "YR", "LY", "YL", "ES", "TF", "9H", "5B", "TC",
+    "VH", "ZK", "ZS", "JA", "HL", "PP", "PR", "PT", "LV", "CC", "XA", "XB", "VT", "RA", "UR",
+];
+
+const MAX_MARK_LEN: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistrationError {
+    Empty,
+    /// The registration without a hyphen does not start with a known prefix.
+    UnknownPrefix(String),
+    /// The mark is empty, too long or has characters other than letters and digits.
+    InvalidMark(String),
+}
+
+impl fmt::Display for RegistrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistrationError::Empty => write!(f, "registration is empty"),
+            RegistrationError::UnknownPrefix(r) => {
+                write!(f, "registration {} has no known nationality prefix", r)
+            }
+            RegistrationError::InvalidMark(r) => {
+                write!(f, "registration {} has an invalid mark", r)
+            }
+        }
+    }
+}
+
+impl core::error::Error for RegistrationError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct Registration {
+    // The prefix and the mark without a hyphen
+    compact: String,
+    prefix_len: usize,
+}
+
+impl Registration {
+    /// Normalizes to upper case, surrounding whitespace is ignored.
+    pub fn parse(registration: &str) -> Result<Registration, RegistrationError> {
+        let upper = registration.trim().to_ascii_uppercase();
+        if upper.is_empty() {
+            return Err(RegistrationError::Empty);
+        }
+
+        let (prefix, mark) = match upper.split_once('-') {
+            Some((prefix, mark)) => (prefix, mark),
+            None => {
+                let prefix = PREFIXES
+                    .iter()
+                    .filter(|p| upper.starts_with(*p) && upper.len() > p.len())
+                    .max_by_key(|p| p.len())
+                    .ok_or_else(|| RegistrationError::UnknownPrefix(upper.clone()))?;
+                upper.split_at(prefix.len())
+            }
+        };
+
+        let valid = |part: &str, max: usize| {
+            !part.is_empty() && part.len() <= max && part.chars().all(|c| c.is_ascii_alphanumeric())
+        };
+        if !valid(prefix, 2) {
+            return Err(RegistrationError::UnknownPrefix(upper.clone()));
+        }
+        if !valid(mark, MAX_MARK_LEN) {
+            return Err(RegistrationError::InvalidMark(upper.clone()));
+        }
+
+        Ok(Registration {
+            compact: alloc::format!("{}{}", prefix, mark),
+            prefix_len: prefix.len(),
+        })
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.compact[..self.prefix_len]
+    }
+
+    pub fn mark(&self) -> &str {
+        &self.compact[self.prefix_len..]
+    }
+
+    /// `PH-DHA`, `N-123AB`.
+    pub fn hyphenated(&self) -> String {
+        alloc::format!("{}-{}", self.prefix(), self.mark())
+    }
+
+    /// `PHDHA`, `N123AB`, also used in file names.
+    pub fn compact(&self) -> &str {
+        &self.compact
+    }
+}
+
+/// As it is written in captions and reports, see the module documentation.
+impl fmt::Display for Registration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.prefix() {
+            "N" => write!(f, "{}", self.compact()),
+            _ => write!(f, "{}", self.hyphenated()),
+        }
+    }
+}
+
+impl TryFrom<String> for Registration {
+    type Error = RegistrationError;
+
+    fn try_from(registration: String) -> Result<Registration, RegistrationError> {
+        Registration::parse(&registration)
+    }
+}
+
+impl From<Registration> for String {
+    fn from(registration: Registration) -> String {
+        registration.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_and_format() {
+        let dutch = Registration::parse("ph-dha").unwrap();
+        assert_eq!(dutch, Registration::parse(" PHDHA").unwrap());
+        assert_eq!("PH-DHA", dutch.to_string());
+        assert_eq!("PHDHA", dutch.compact());
+
+        let us = Registration::parse("N123AB").unwrap();
+        assert_eq!(("N", "123AB"), (us.prefix(), us.mark()));
+        assert_eq!("N123AB", us.to_string());
+        assert_eq!("N-123AB", us.hyphenated());
+
+        assert_eq!("CS", Registration::parse("CSABC").unwrap().prefix());
+        assert_eq!("C", Registration::parse("C-FABC").unwrap().prefix());
+    }
+
+    #[test]
+    fn invalid_registrations() {
+        assert_eq!(Err(RegistrationError::Empty), Registration::parse(" "));
+        assert_eq!(
+            Err(RegistrationError::UnknownPrefix("QQABC".to_string())),
+            Registration::parse("QQABC")
+        );
+        assert!(matches!(
+            Registration::parse("PH-"),
+            Err(RegistrationError::InvalidMark(_))
+        ));
+        assert!(matches!(
+            Registration::parse("PH-D HA"),
+            Err(RegistrationError::InvalidMark(_))
+        ));
+        assert!(matches!(
+            Registration::parse("PH-ABCDEFG"),
+            Err(RegistrationError::InvalidMark(_))
+        ));
+    }
+}
@@ -0,0 +1,309 @@
+//! Typical figures for common trainers, meant as a quick start for apps.
+//!
+//! Empty mass and CG differ for every individual aircraft and the envelopes are simplified to
+//! their rectangular outline. Always replace the values with the ones from the aircraft's own
+//! weighing report and POH before using a template for flight planning.
+
+use alloc::string::ToString;
+use alloc::vec;
+
+use crate::registration::Registration;
+use crate::types::FuelType;
+use crate::weight_and_balance::{
+    Airplane, CenterOfGravity, LeverArm, Limits, Mass, Moment, Tank, Volume,
+};
+
+pub const NOTICE: &str =
+    "Typical values only: verify against the POH and weighing report of your own aircraft.";
+
+pub struct Station {
+    name: &'static str,
+    lever_arm: f64,
+}
+
+impl Station {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn lever_arm(&self) -> LeverArm {
+        LeverArm::Meter(self.lever_arm)
+    }
+}
+
+pub struct AircraftType {
+    id: &'static str,
+    name: &'static str,
+    empty_mass: f64,
+    empty_arm: f64,
+    minimum_weight: f64,
+    mtow: f64,
+    forward_cg_limit: f64,
+    rearward_cg_limit: f64,
+    seats: &'static [Station],
+    baggage: &'static [Station],
+    fuel_arm: f64,
+    fuel: FuelType,
+    usable_fuel: f64,
+}
+
+impl AircraftType {
+    pub fn id(&self) -> &'static str {
+        self.id
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn notice(&self) -> &'static str {
+        NOTICE
+    }
+
+    pub fn seats(&self) -> &'static [Station] {
+        self.seats
+    }
+
+    pub fn baggage(&self) -> &'static [Station] {
+        self.baggage
+    }
+
+    pub fn fuel_arm(&self) -> LeverArm {
+        LeverArm::Meter(self.fuel_arm)
+    }
+
+    pub fn fuel(&self) -> FuelType {
+        self.fuel.clone()
+    }
+
+    pub fn usable_fuel(&self) -> Volume {
+        Volume::Liter(self.usable_fuel)
+    }
+
+    /// The empty aircraft with the typical empty mass, ready to receive a
+    /// [`Loading`](crate::loading::Loading) built from the seat, baggage and fuel arms. The fuel
+    /// tank is named "Fuel".
+    pub fn airplane(&self, registration: Registration) -> Airplane {
+        let mut plane = Airplane::new(
+            registration,
+            vec![Moment::new(
+                "Empty aircraft".to_string(),
+                LeverArm::Meter(self.empty_arm),
+                Mass::Kilo(self.empty_mass),
+            )],
+            Limits::new(
+                Mass::Kilo(self.minimum_weight),
+                Mass::Kilo(self.mtow),
+                CenterOfGravity::Meter(self.forward_cg_limit),
+                CenterOfGravity::Meter(self.rearward_cg_limit),
+            ),
+            Volume::Liter(0.0),
+        );
+        plane.add_tank(Tank::new(
+            "Fuel".to_string(),
+            self.usable_fuel(),
+            self.usable_fuel(),
+        ));
+        plane
+    }
+}
+
+static TYPES: [AircraftType; 5] = [
+    AircraftType {
+        id: "aquila-a210",
+        name: "Aquila A210",
+        empty_mass: 517.0,
+        empty_arm: 0.4294,
+        minimum_weight: 558.0,
+        mtow: 750.0,
+        forward_cg_limit: 0.427,
+        rearward_cg_limit: 0.523,
+        seats: &[
+            Station {
+                name: "Pilot",
+                lever_arm: 0.515,
+            },
+            Station {
+                name: "Passenger",
+                lever_arm: 0.515,
+            },
+        ],
+        baggage: &[Station {
+            name: "Baggage",
+            lever_arm: 1.3,
+        }],
+        fuel_arm: 0.325,
+        fuel: FuelType::Avgas,
+        usable_fuel: 109.0,
+    },
+    AircraftType {
+        id: "c150",
+        name: "Cessna 150M",
+        empty_mass: 504.0,
+        empty_arm: 0.84,
+        minimum_weight: 600.0,
+        mtow: 726.0,
+        forward_cg_limit: 0.80,
+        rearward_cg_limit: 0.953,
+        seats: &[
+            Station {
+                name: "Pilot",
+                lever_arm: 0.99,
+            },
+            Station {
+                name: "Passenger",
+                lever_arm: 0.99,
+            },
+        ],
+        baggage: &[Station {
+            name: "Baggage",
+            lever_arm: 1.63,
+        }],
+        fuel_arm: 1.07,
+        fuel: FuelType::Avgas,
+        usable_fuel: 85.0,
+    },
+    AircraftType {
+        id: "c172",
+        name: "Cessna 172S",
+        empty_mass: 762.0,
+        empty_arm: 1.01,
+        minimum_weight: 800.0,
+        mtow: 1157.0,
+        forward_cg_limit: 0.889,
+        rearward_cg_limit: 1.201,
+        seats: &[
+            Station {
+                name: "Pilot",
+                lever_arm: 0.94,
+            },
+            Station {
+                name: "Front passenger",
+                lever_arm: 0.94,
+            },
+            Station {
+                name: "Rear passengers",
+                lever_arm: 1.85,
+            },
+        ],
+        baggage: &[
+            Station {
+                name: "Baggage A",
+                lever_arm: 2.41,
+            },
+            Station {
+                name: "Baggage B",
+                lever_arm: 3.12,
+            },
+        ],
+        fuel_arm: 1.22,
+        fuel: FuelType::Avgas,
+        usable_fuel: 201.0,
+    },
+    AircraftType {
+        id: "da40",
+        name: "Diamond DA40 180",
+        empty_mass: 795.0,
+        empty_arm: 2.44,
+        minimum_weight: 850.0,
+        mtow: 1150.0,
+        forward_cg_limit: 2.40,
+        rearward_cg_limit: 2.59,
+        seats: &[
+            Station {
+                name: "Pilot",
+                lever_arm: 2.30,
+            },
+            Station {
+                name: "Front passenger",
+                lever_arm: 2.30,
+            },
+            Station {
+                name: "Rear passengers",
+                lever_arm: 3.25,
+            },
+        ],
+        baggage: &[Station {
+            name: "Baggage",
+            lever_arm: 3.65,
+        }],
+        fuel_arm: 2.63,
+        fuel: FuelType::Avgas,
+        usable_fuel: 148.0,
+    },
+    AircraftType {
+        id: "pa28",
+        name: "Piper PA-28-161 Warrior",
+        empty_mass: 680.0,
+        empty_arm: 2.20,
+        minimum_weight: 750.0,
+        mtow: 1107.0,
+        forward_cg_limit: 2.108,
+        rearward_cg_limit: 2.362,
+        seats: &[
+            Station {
+                name: "Pilot",
+                lever_arm: 2.04,
+            },
+            Station {
+                name: "Front passenger",
+                lever_arm: 2.04,
+            },
+            Station {
+                name: "Rear passengers",
+                lever_arm: 3.00,
+            },
+        ],
+        baggage: &[Station {
+            name: "Baggage",
+            lever_arm: 3.63,
+        }],
+        fuel_arm: 2.41,
+        fuel: FuelType::Avgas,
+        usable_fuel: 182.0,
+    },
+];
+
+pub fn aircraft_types() -> &'static [AircraftType] {
+    &TYPES
+}
+
+pub fn aircraft_type(id: &str) -> Option<&'static AircraftType> {
+    TYPES.iter().find(|t| t.id.eq_ignore_ascii_case(id))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::loading::{FuelUplift, Loading, Manifest, Occupant};
+
+    #[test]
+    fn lookup_by_identifier() {
+        assert_eq!("Cessna 172S", aircraft_type("C172").unwrap().name());
+        assert!(aircraft_type("b747").is_none());
+    }
+
+    #[test]
+    fn pilot_and_half_fuel_within_limits() {
+        for t in aircraft_types() {
+            let mut loading = Loading::new();
+            loading.add_occupant(Occupant::new(
+                "Pilot".to_string(),
+                t.seats()[0].name().to_string(),
+                t.seats()[0].lever_arm(),
+                Mass::Kilo(80.0),
+            ));
+            loading.add_fuel(FuelUplift::new(
+                "Fuel".to_string(),
+                t.fuel_arm(),
+                t.fuel(),
+                Volume::Liter(0.0),
+                Volume::Liter(t.usable_fuel().to_liter() / 2.0),
+            ));
+
+            let manifest =
+                Manifest::new(t.airplane(Registration::parse("PH-XXX").unwrap()), loading);
+            assert!(manifest.summary().within_limits(), "{}", t.id());
+        }
+    }
+}
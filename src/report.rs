@@ -0,0 +1,227 @@
+//! Names and metadata for the charts, tables and documents made for a flight, so archived weight
+//! and balance documents can be traced back to the airplane, pilot and calculation.
+//!
+//! The file name is `<registration>_<date>_<kind>.<extension>` with the compact registration,
+//! e.g. `PHDHA_2026-10-14_chart.svg`. The same metadata is embedded in an SVG as a
+//! `<metadata>` element with [`ReportMetadata::embed_in_svg`], or written into the document
+//! information of a PDF from [`ReportMetadata::entries`].
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::registration::Registration;
+use crate::weight_and_balance::Airplane;
+
+// The namespace of the metadata element in SVGs
+const NAMESPACE: &str = "https://github.com/michaelvlaar/airplane-rs";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReportMetadata {
+    registration: Registration,
+    date: String,
+    pilot: Option<String>,
+    crate_version: String,
+    profile_hash: Option<String>,
+}
+
+pub(crate) fn escaped(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl ReportMetadata {
+    /// `date` is the date of the flight as it should appear in file names, e.g. `2026-10-14`.
+    /// With the `integrity` feature the profile hash is the hash of `plane`.
+    pub fn new(plane: &Airplane, date: String) -> ReportMetadata {
+        #[cfg(feature = "integrity")]
+        let profile_hash = Some(plane.profile_hash().to_string());
+        #[cfg(not(feature = "integrity"))]
+        let profile_hash = None;
+
+        ReportMetadata {
+            registration: plane.registration().clone(),
+            date,
+            pilot: None,
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            profile_hash,
+        }
+    }
+
+    pub fn with_pilot(mut self, pilot: String) -> ReportMetadata {
+        self.pilot = Some(pilot);
+        self
+    }
+
+    /// The hash of the aircraft profile, e.g. of the empty aircraft instead of the loaded one.
+    pub fn with_profile_hash(mut self, profile_hash: String) -> ReportMetadata {
+        self.profile_hash = Some(profile_hash);
+        self
+    }
+
+    pub fn registration(&self) -> &Registration {
+        &self.registration
+    }
+
+    pub fn date(&self) -> &str {
+        &self.date
+    }
+
+    pub fn pilot(&self) -> Option<&str> {
+        self.pilot.as_deref()
+    }
+
+    pub fn crate_version(&self) -> &str {
+        &self.crate_version
+    }
+
+    pub fn profile_hash(&self) -> Option<&str> {
+        self.profile_hash.as_deref()
+    }
+
+    /// The file name for a document of `kind`, e.g. `"chart"` or `"manifest"`. Characters that
+    /// are not allowed in file names on common systems are replaced by `_`.
+    pub fn file_name(&self, kind: &str, extension: &str) -> String {
+        let name = alloc::format!(
+            "{}_{}_{}.{}",
+            self.registration.compact(),
+            self.date,
+            kind,
+            extension
+        );
+        name.chars()
+            .map(|c| match c {
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | ' ' => '_',
+                c => c,
+            })
+            .collect()
+    }
+
+    /// The metadata as keys and values in a fixed order, without the ones that are not set.
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        let mut entries = alloc::vec![
+            ("registration", self.registration.to_string()),
+            ("date", self.date.clone()),
+        ];
+        if let Some(pilot) = &self.pilot {
+            entries.push(("pilot", pilot.clone()));
+        }
+        entries.push(("crate-version", self.crate_version.clone()));
+        if let Some(hash) = &self.profile_hash {
+            entries.push(("profile-hash", hash.clone()));
+        }
+        entries
+    }
+
+    /// The `<metadata>` element with every entry as an attribute.
+    pub fn svg_metadata(&self) -> String {
+        let attributes: String = self
+            .entries()
+            .iter()
+            .map(|(key, value)| alloc::format!(" {}=\"{}\"", key, escaped(value)))
+            .collect();
+        alloc::format!(
+            "<metadata><report xmlns=\"{}\"{}/></metadata>",
+            NAMESPACE,
+            attributes
+        )
+    }
+
+    /// `svg` with [`ReportMetadata::svg_metadata`] as the first child of the `<svg>` element,
+    /// unchanged when there is no `<svg>` element.
+    pub fn embed_in_svg(&self, svg: &str) -> String {
+        let Some(start) = svg.find("<svg") else {
+            return svg.to_string();
+        };
+        let Some(end) = svg[start..].find('>').map(|end| start + end + 1) else {
+            return svg.to_string();
+        };
+
+        let mut embedded = String::with_capacity(svg.len() + 256);
+        embedded.push_str(&svg[..end]);
+        embedded.push('\n');
+        embedded.push_str(&self.svg_metadata());
+        embedded.push_str(&svg[end..]);
+        embedded
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::weight_and_balance::{CenterOfGravity, Limits, Mass, Volume};
+
+    fn metadata(registration: &str) -> ReportMetadata {
+        let plane = Airplane::new(
+            Registration::parse(registration).unwrap(),
+            alloc::vec![],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(17.0),
+        );
+        ReportMetadata::new(&plane, "2026-10-14".to_string())
+    }
+
+    #[test]
+    fn file_names() {
+        let metadata = metadata("ph-dha");
+        assert_eq!("PH-DHA", metadata.registration().to_string());
+        assert_eq!(
+            "PHDHA_2026-10-14_chart.svg",
+            metadata.file_name("chart", "svg")
+        );
+        assert_eq!(
+            "N123AB_2026-10-14_manifest_draft.txt",
+            self::metadata("N-123AB").file_name("manifest draft", "txt")
+        );
+    }
+
+    #[test]
+    fn metadata_in_svg() {
+        let metadata = metadata("PHDHA")
+            .with_pilot("J. \"Jo\" Doe".to_string())
+            .with_profile_hash("ab12".to_string());
+        let keys: Vec<&str> = metadata.entries().iter().map(|(key, _)| *key).collect();
+        assert_eq!(
+            alloc::vec![
+                "registration",
+                "date",
+                "pilot",
+                "crate-version",
+                "profile-hash"
+            ],
+            keys
+        );
+
+        let svg = metadata.embed_in_svg("<?xml?>\n<svg width=\"10\"><rect/></svg>");
+        assert!(svg.starts_with("<?xml?>\n<svg width=\"10\">\n<metadata><report"));
+        assert!(svg.contains("pilot=\"J. &quot;Jo&quot; Doe\""));
+        assert!(svg.contains(&alloc::format!(
+            "crate-version=\"{}\"",
+            env!("CARGO_PKG_VERSION")
+        )));
+        assert!(svg.ends_with("/></metadata><rect/></svg>"));
+        assert_eq!("<p/>", metadata.embed_in_svg("<p/>"));
+    }
+
+    #[cfg(feature = "integrity")]
+    #[test]
+    fn profile_hash_of_the_airplane() {
+        let metadata = metadata("PHDHA");
+        assert_eq!(Some(64), metadata.profile_hash().map(str::len));
+    }
+}
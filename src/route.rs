@@ -0,0 +1,357 @@
+//! Trip fuel per leg of a route, for a weight and balance calculation at the start of every leg.
+//!
+//! A route is read from the route, track or waypoints of a GPX file, or from a simple flight
+//! plan with a leg name and distance in nautical miles per line:
+//!
+//! ```text
+//! # EHRD - EHTE - EHRD
+//! EHRD-EHTE,72.5
+//! EHTE-EHRD,72.5
+//! ```
+
+use std::fmt;
+
+use crate::weight_and_balance::{Airplane, Mass, Volume};
+use crate::xml;
+
+const EARTH_RADIUS_NM: f64 = 3440.065;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteError {
+    Xml(String),
+    /// The line number (1-based) and the value that is not a number.
+    InvalidNumber(usize, String),
+    /// A route needs at least one leg, a GPX file at least two points.
+    NoLegs,
+    /// The last moment of the airplane is not the fuel on board.
+    NoFuel,
+    /// The fuel on board runs out on the leg with this name.
+    InsufficientFuel(String),
+    /// The ground speed in knots is not above zero.
+    InvalidGroundSpeed,
+}
+
+impl fmt::Display for RouteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteError::Xml(e) => write!(f, "invalid GPX: {}", e),
+            RouteError::InvalidNumber(line, value) => {
+                write!(f, "line {}: {} is not a number", line, value)
+            }
+            RouteError::NoLegs => write!(f, "route has no legs"),
+            RouteError::NoFuel => write!(f, "last moment is not the fuel on board"),
+            RouteError::InsufficientFuel(leg) => write!(f, "fuel runs out on leg {}", leg),
+            RouteError::InvalidGroundSpeed => write!(f, "ground speed is not above zero"),
+        }
+    }
+}
+
+impl std::error::Error for RouteError {}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Leg {
+    name: String,
+    distance_nm: f64,
+}
+
+impl Leg {
+    pub fn new(name: String, distance_nm: f64) -> Leg {
+        Leg { name, distance_nm }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn distance_nm(&self) -> f64 {
+        self.distance_nm
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Route {
+    legs: Vec<Leg>,
+}
+
+fn distance_nm((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (lon2 - lon1).to_radians();
+
+    let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_NM * a.sqrt().asin()
+}
+
+impl Route {
+    pub fn new() -> Route {
+        Route::default()
+    }
+
+    pub fn add_leg(&mut self, leg: Leg) {
+        self.legs.push(leg);
+    }
+
+    pub fn legs(&self) -> &Vec<Leg> {
+        &self.legs
+    }
+
+    pub fn distance_nm(&self) -> f64 {
+        self.legs.iter().map(|l| l.distance_nm).sum()
+    }
+
+    /// One leg between every two consecutive points. Route points are used when present, then
+    /// track points and then waypoints.
+    pub fn from_gpx(gpx: &str) -> Result<Route, RouteError> {
+        let elements = xml::elements(gpx).map_err(RouteError::Xml)?;
+
+        let points = ["rtept", "trkpt", "wpt"]
+            .iter()
+            .map(|kind| {
+                elements
+                    .iter()
+                    .filter(|e| e.name == *kind)
+                    .map(|e| {
+                        let coordinate = |name: &str| {
+                            e.attribute(name)
+                                .and_then(|v| v.trim().parse::<f64>().ok())
+                                .ok_or_else(|| {
+                                    RouteError::Xml(format!("<{}> without valid {}", kind, name))
+                                })
+                        };
+                        Ok((coordinate("lat")?, coordinate("lon")?))
+                    })
+                    .collect::<Result<Vec<_>, RouteError>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .find(|points| !points.is_empty())
+            .unwrap_or_default();
+
+        if points.len() < 2 {
+            return Err(RouteError::NoLegs);
+        }
+
+        let mut route = Route::new();
+        for (i, pair) in points.windows(2).enumerate() {
+            route.add_leg(Leg::new(
+                format!("Leg {}", i + 1),
+                distance_nm(pair[0], pair[1]),
+            ));
+        }
+        Ok(route)
+    }
+
+    pub fn from_plan(plan: &str) -> Result<Route, RouteError> {
+        let mut route = Route::new();
+
+        for (i, line) in plan.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (name, distance) = line.rsplit_once(',').unwrap_or(("", line));
+            let distance = distance.trim();
+            let distance_nm = distance
+                .parse::<f64>()
+                .map_err(|_| RouteError::InvalidNumber(i + 1, distance.to_string()))?;
+
+            route.add_leg(Leg::new(name.trim().to_string(), distance_nm));
+        }
+
+        if route.legs.is_empty() {
+            return Err(RouteError::NoLegs);
+        }
+        Ok(route)
+    }
+
+    /// Trip fuel of every leg at a constant ground speed in knots and burn rate per hour, in the
+    /// unit of the burn rate.
+    pub fn trip_fuel(
+        &self,
+        ground_speed_kt: f64,
+        burn_rate: &Volume,
+    ) -> Result<Vec<Volume>, RouteError> {
+        if ground_speed_kt.is_nan() || ground_speed_kt <= 0.0 {
+            return Err(RouteError::InvalidGroundSpeed);
+        }
+
+        Ok(self
+            .legs
+            .iter()
+            .map(|l| {
+                let hours = l.distance_nm / ground_speed_kt;
+                match burn_rate {
+                    Volume::Liter(l) => Volume::Liter(l * hours),
+                    Volume::Gallon(g) => Volume::Gallon(g * hours),
+                }
+            })
+            .collect())
+    }
+
+    /// The airplane at the start of every leg: the fuel of the previous legs burned and the trip
+    /// fuel of the leg itself, so the summary of each gives its take-off and landing figures.
+    /// Everything else of `plane` is kept, the fuel stays in the unit it was entered in.
+    pub fn airplanes(
+        &self,
+        plane: &Airplane,
+        ground_speed_kt: f64,
+        burn_rate: &Volume,
+    ) -> Result<Vec<Airplane>, RouteError> {
+        let fuel = plane.iter().last().ok_or(RouteError::NoFuel)?;
+        let volume = fuel.mass().volume().ok_or(RouteError::NoFuel)?;
+        let mut on_board = volume.to_liter();
+
+        self.legs
+            .iter()
+            .zip(self.trip_fuel(ground_speed_kt, burn_rate)?)
+            .map(|(leg, trip)| {
+                // The unit of the fuel on board, its liters otherwise
+                let in_unit = match volume {
+                    Volume::Liter(_) => Volume::Liter(on_board),
+                    Volume::Gallon(_) => Volume::Gallon(Volume::Liter(on_board).to_gallon()),
+                };
+                let mass = match fuel.mass() {
+                    Mass::Mogas(_) => Mass::Mogas(in_unit),
+                    _ => Mass::Avgas(in_unit),
+                };
+
+                on_board -= trip.to_liter();
+                if on_board < 0.0 {
+                    return Err(RouteError::InsufficientFuel(leg.name.clone()));
+                }
+                Ok(plane
+                    .with_fuel_on_board(mass)
+                    .with_fuel_consumption_trip(trip))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{CenterOfGravity, LeverArm, Limits, Moment, Tank};
+
+    fn airplane(fuel: Mass) -> Airplane {
+        Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![
+                Moment::new(
+                    "Empty".to_string(),
+                    LeverArm::Meter(0.4294),
+                    Mass::Kilo(517.0),
+                ),
+                Moment::new("Fuel".to_string(), LeverArm::Meter(0.325), fuel),
+            ],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(0.0),
+        )
+    }
+
+    #[test]
+    fn gpx_legs() {
+        let route = Route::from_gpx(
+            r#"<?xml version="1.0"?>
+<gpx version="1.1" creator="test">
+  <wpt lat="0" lon="0"><name>Ignored</name></wpt>
+  <rte>
+    <rtept lat="52.0" lon="4.0"><name>A</name></rtept>
+    <rtept lat="53.0" lon="4.0"><name>B</name></rtept>
+    <rtept lat="53.0" lon="5.0"/>
+  </rte>
+</gpx>"#,
+        )
+        .unwrap();
+
+        assert_eq!(2, route.legs().len());
+        // A degree of latitude is 60 NM
+        assert!((route.legs()[0].distance_nm() - 60.04).abs() < 0.01);
+        assert!((route.legs()[1].distance_nm() - 36.1).abs() < 0.1);
+        assert!(Route::from_gpx("<gpx><wpt lat=\"1\" lon=\"1\"/></gpx>").is_err());
+    }
+
+    #[test]
+    fn fuel_per_leg() {
+        let route = Route::from_plan("# round trip\nEHRD-EHTE, 60\nEHTE-EHRD,90\n").unwrap();
+        assert_eq!("EHTE-EHRD", route.legs()[1].name());
+        assert_eq!(
+            Err(RouteError::InvalidNumber(1, "far".to_string())),
+            Route::from_plan("EHRD-EHTE,far").map(|_| ())
+        );
+
+        let fuel = route.trip_fuel(100.0, &Volume::Liter(20.0)).unwrap();
+        assert!((fuel[0].to_liter() - 12.0).abs() < 1e-9);
+        assert!((fuel[1].to_liter() - 18.0).abs() < 1e-9);
+
+        let plane = airplane(Mass::Avgas(Volume::Liter(60.0)));
+
+        let legs = route
+            .airplanes(&plane, 100.0, &Volume::Liter(20.0))
+            .unwrap();
+        assert_eq!(2, legs.len());
+        assert!((legs[1].total_mass().kilo() - (517.0 + 48.0 * 0.72)).abs() < 1e-9);
+        assert!(
+            (legs[1].total_mass_landing().unwrap().kilo() - (517.0 + 30.0 * 0.72)).abs() < 1e-9
+        );
+    }
+
+    #[test]
+    fn legs_keep_the_airplane() {
+        let route = Route::from_plan("EHRD-EHTE,60\nEHTE-EHRD,90\n").unwrap();
+        let mut plane =
+            airplane(Mass::Mogas(Volume::Gallon(20.0))).with_landing_fuel(Volume::Liter(30.0));
+        plane.add_tank(Tank::new(
+            "Fuel".to_string(),
+            Volume::Gallon(24.0),
+            Volume::Gallon(26.0),
+        ));
+
+        let legs = route
+            .airplanes(&plane, 100.0, &Volume::Gallon(5.0))
+            .unwrap();
+        assert_eq!(plane.tanks(), legs[1].tanks());
+        assert_eq!(Some(&Volume::Liter(30.0)), legs[1].landing_fuel());
+        assert_eq!(&Volume::Gallon(3.0), legs[0].fuel_consumption_trip());
+        match legs[1].iter().last().unwrap().mass() {
+            Mass::Mogas(Volume::Gallon(g)) => assert!((g - 17.0).abs() < 1e-9),
+            other => panic!("expected mogas in gallons, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn route_errors() {
+        let route = Route::from_plan("EHRD-EHTE,60\nEHTE-EHRD,90\n").unwrap();
+        let plane = airplane(Mass::Avgas(Volume::Liter(60.0)));
+
+        // 30 L for the first leg leaves 30 L for the 45 L of the second
+        assert_eq!(
+            Err(RouteError::InsufficientFuel("EHTE-EHRD".to_string())),
+            route
+                .airplanes(&plane, 100.0, &Volume::Liter(50.0))
+                .map(|_| ())
+        );
+        assert!(route.airplanes(&plane, 100.0, &Volume::Liter(40.0)).is_ok());
+
+        for speed in [0.0, -90.0, f64::NAN] {
+            assert_eq!(
+                Err(RouteError::InvalidGroundSpeed),
+                route.trip_fuel(speed, &Volume::Liter(20.0))
+            );
+        }
+        assert_eq!(
+            Err(RouteError::InvalidGroundSpeed),
+            route
+                .airplanes(&plane, 0.0, &Volume::Liter(20.0))
+                .map(|_| ())
+        );
+    }
+}
@@ -0,0 +1,324 @@
+//! Seat assignments that move the CG where it is wanted, for four-seaters flown near a CG limit.
+//!
+//! Every assignment of the occupants to the seats is tried, so this is meant for the handful of
+//! seats of a light aircraft. Only assignments with the take-off and landing within the limits
+//! are suggested.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::loading::Occupant;
+use crate::weight_and_balance::{Airplane, CenterOfGravity, LeverArm, Mass};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum SeatingGoal {
+    /// The take-off and landing CG closest to the middle of the CG limits.
+    #[default]
+    Center,
+    /// The most distance between the forward limit and the take-off and landing CG.
+    ForwardMargin,
+    /// The most distance between the rearward limit and the take-off and landing CG.
+    RearwardMargin,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeatAssignment {
+    occupants: Vec<Occupant>,
+    center_of_gravity: CenterOfGravity,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    landing_center_of_gravity: Option<CenterOfGravity>,
+}
+
+impl SeatAssignment {
+    /// The occupants in the order they were given, each in their seat.
+    pub fn occupants(&self) -> &Vec<Occupant> {
+        &self.occupants
+    }
+
+    pub fn center_of_gravity(&self) -> &CenterOfGravity {
+        &self.center_of_gravity
+    }
+
+    /// `None` when the airplane has no landing figures.
+    pub fn landing_center_of_gravity(&self) -> Option<&CenterOfGravity> {
+        self.landing_center_of_gravity.as_ref()
+    }
+}
+
+struct Search<'a> {
+    masses: Vec<f64>,
+    arms: Vec<f64>,
+    // Lower is better, `None` outside the limits, of the moment of the occupants
+    score: &'a dyn Fn(f64) -> Option<f64>,
+    best: Option<(f64, Vec<usize>)>,
+}
+
+impl Search<'_> {
+    // Seats the occupant `next` and everyone after them in every free seat
+    fn seat(&mut self, next: usize, moment: f64, seats: &mut Vec<usize>) {
+        if next == self.masses.len() {
+            if let Some(score) = (self.score)(moment) {
+                if self.best.as_ref().is_none_or(|(best, _)| score < *best) {
+                    self.best = Some((score, seats.clone()));
+                }
+            }
+            return;
+        }
+
+        for seat in 0..self.arms.len() {
+            if seats.contains(&seat) {
+                continue;
+            }
+            seats.push(seat);
+            let moment = moment + self.masses[next] * self.arms[seat];
+            self.seat(next + 1, moment, seats);
+            seats.pop();
+        }
+    }
+}
+
+/// The assignment of `people`, names and masses, to `seats`, names and arms, that best meets
+/// `goal` for `plane` with everything but the occupants on board. `None` with more people than
+/// seats, nobody on board or when no assignment keeps the take-off and landing within the limits.
+pub fn assign_seats(
+    plane: &Airplane,
+    people: &[(String, Mass)],
+    seats: &[(String, LeverArm)],
+    goal: SeatingGoal,
+) -> Option<SeatAssignment> {
+    let masses: Vec<f64> = people.iter().map(|(_, mass)| mass.kilo()).collect();
+    let occupants_mass = masses.iter().sum::<f64>();
+    let takeoff = (
+        plane.total_mass().kilo() + occupants_mass,
+        plane.total_mass_moment().kgm(),
+    );
+    if people.len() > seats.len() || takeoff.0 <= 0.0 {
+        return None;
+    }
+    let landing = match (
+        plane.total_mass_landing(),
+        plane.total_mass_moment_landing(),
+    ) {
+        (Ok(mass), Ok(moment)) => Some((mass.kilo() + occupants_mass, moment.kgm())),
+        _ => None,
+    };
+
+    // The take-off and landing CG with the occupants' moment
+    let centers_of_gravity = move |moment: f64| {
+        let cg = |(mass, base): (f64, f64)| CenterOfGravity::Meter((base + moment) / mass);
+        (cg(takeoff), landing.map(cg))
+    };
+    let limits = plane.limits();
+    let (forward, rearward) = (
+        limits.forward_cg_limit().meter(),
+        limits.rearward_cg_limit().meter(),
+    );
+    let score = |moment: f64| {
+        let (takeoff_cg, landing_cg) = centers_of_gravity(moment);
+        let mut figures = alloc::vec![(takeoff.0, takeoff_cg)];
+        if let (Some(landing), Some(cg)) = (landing, landing_cg) {
+            figures.push((landing.0, cg));
+        }
+        if !figures
+            .iter()
+            .all(|(mass, cg)| limits.contains(&Mass::Kilo(*mass), cg))
+        {
+            return None;
+        }
+
+        let worst = |distance: &dyn Fn(f64) -> f64| {
+            figures
+                .iter()
+                .map(|(_, cg)| distance(cg.meter()))
+                .fold(f64::NEG_INFINITY, f64::max)
+        };
+        Some(match goal {
+            SeatingGoal::Center => worst(&|cg| (cg - (forward + rearward) / 2.0).abs()),
+            SeatingGoal::ForwardMargin => worst(&|cg| forward - cg),
+            SeatingGoal::RearwardMargin => worst(&|cg| cg - rearward),
+        })
+    };
+
+    let mut search = Search {
+        masses,
+        arms: seats.iter().map(|(_, arm)| arm.meter()).collect(),
+        score: &score,
+        best: None,
+    };
+    search.seat(0, 0.0, &mut Vec::new());
+
+    let (_, assignment) = search.best?;
+    let moment: f64 = assignment
+        .iter()
+        .zip(&search.masses)
+        .map(|(seat, mass)| mass * search.arms[*seat])
+        .sum();
+    let (center_of_gravity, landing_center_of_gravity) = centers_of_gravity(moment);
+    let occupants = people
+        .iter()
+        .zip(assignment)
+        .map(|((name, mass), seat)| {
+            let (seat, arm) = &seats[seat];
+            Occupant::new(name.clone(), seat.clone(), arm.clone(), mass.clone())
+        })
+        .collect();
+
+    Some(SeatAssignment {
+        occupants,
+        center_of_gravity,
+        landing_center_of_gravity,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{Limits, Moment, Volume};
+    use alloc::string::ToString;
+
+    fn four_seater() -> Airplane {
+        with_cg_limits(2.1, 2.4)
+    }
+
+    fn with_cg_limits(forward: f64, rearward: f64) -> Airplane {
+        Airplane::new(
+            Registration::parse("PHABC").unwrap(),
+            alloc::vec![Moment::new(
+                "Empty".to_string(),
+                LeverArm::Meter(2.2),
+                Mass::Kilo(700.0),
+            )],
+            Limits::new(
+                Mass::Kilo(800.0),
+                Mass::Kilo(1100.0),
+                CenterOfGravity::Meter(forward),
+                CenterOfGravity::Meter(rearward),
+            ),
+            Volume::Liter(30.0),
+        )
+    }
+
+    fn people() -> Vec<(String, Mass)> {
+        alloc::vec![
+            ("Light".to_string(), Mass::Kilo(60.0)),
+            ("Heavy".to_string(), Mass::Kilo(110.0)),
+            ("Medium".to_string(), Mass::Kilo(80.0)),
+        ]
+    }
+
+    fn rear_seated(assignment: &SeatAssignment) -> Vec<&str> {
+        assignment
+            .occupants()
+            .iter()
+            .filter(|o| o.seat().starts_with("Rear"))
+            .map(|o| o.name())
+            .collect()
+    }
+
+    fn seats() -> Vec<(String, LeverArm)> {
+        alloc::vec![
+            ("Front left".to_string(), LeverArm::Meter(2.0)),
+            ("Front right".to_string(), LeverArm::Meter(2.0)),
+            ("Rear left".to_string(), LeverArm::Meter(3.0)),
+            ("Rear right".to_string(), LeverArm::Meter(3.0)),
+        ]
+    }
+
+    #[test]
+    fn heaviest_in_front_for_rearward_margin() {
+        let people = people();
+
+        let forward = assign_seats(
+            &four_seater(),
+            &people,
+            &seats(),
+            SeatingGoal::RearwardMargin,
+        )
+        .unwrap();
+        let seat_of = |assignment: &SeatAssignment, name: &str| {
+            assignment
+                .occupants()
+                .iter()
+                .find(|o| o.name() == name)
+                .map(|o| o.seat().to_string())
+                .unwrap()
+        };
+        assert!(seat_of(&forward, "Heavy").starts_with("Front"));
+        assert!(seat_of(&forward, "Medium").starts_with("Front"));
+        assert_eq!("Rear left", seat_of(&forward, "Light"));
+
+        let aft = assign_seats(
+            &four_seater(),
+            &people,
+            &seats(),
+            SeatingGoal::ForwardMargin,
+        )
+        .unwrap();
+        assert!(aft.center_of_gravity().meter() > forward.center_of_gravity().meter());
+
+        let center = assign_seats(&four_seater(), &people, &seats(), SeatingGoal::Center).unwrap();
+        let cg = center.center_of_gravity().meter();
+        assert!(cg >= forward.center_of_gravity().meter() && cg <= aft.center_of_gravity().meter());
+    }
+
+    #[test]
+    fn margins_only_within_limits() {
+        // With the light occupant alone in the back the CG of 2.2105 m is in front of the limit
+        let plane = with_cg_limits(2.215, 2.3);
+        let forward =
+            assign_seats(&plane, &people(), &seats(), SeatingGoal::RearwardMargin).unwrap();
+        assert_eq!(vec!["Medium"], rear_seated(&forward));
+        assert!((forward.center_of_gravity().meter() - 2120.0 / 950.0).abs() < 1e-9);
+
+        // The heavy occupant in the back would be behind the rearward limit
+        let aft = assign_seats(&plane, &people(), &seats(), SeatingGoal::ForwardMargin).unwrap();
+        let mut rear = rear_seated(&aft);
+        rear.sort();
+        assert_eq!(vec!["Light", "Medium"], rear);
+
+        // Burning the fuel behind the seats moves the CG forward, the light occupant alone in
+        // the back is within the limits at take-off but not at landing
+        let mut fueled = with_cg_limits(2.215, 2.4).with_fuel_consumption_trip(Volume::Liter(50.0));
+        fueled.add_moment(Moment::new(
+            "Fuel".to_string(),
+            LeverArm::Meter(3.0),
+            Mass::Avgas(Volume::Liter(50.0)),
+        ));
+        let forward =
+            assign_seats(&fueled, &people(), &seats(), SeatingGoal::RearwardMargin).unwrap();
+        assert_eq!(vec!["Medium"], rear_seated(&forward));
+        let landing = forward.landing_center_of_gravity().unwrap().meter();
+        assert!((landing - 2120.0 / 950.0).abs() < 1e-9);
+
+        assert_eq!(
+            None,
+            assign_seats(
+                &with_cg_limits(2.1, 2.2),
+                &people(),
+                &seats(),
+                SeatingGoal::Center
+            )
+        );
+    }
+
+    #[test]
+    fn more_people_than_seats() {
+        let people: Vec<(String, Mass)> = (0..5)
+            .map(|i| (alloc::format!("P{}", i), Mass::Kilo(70.0)))
+            .collect();
+        assert_eq!(
+            None,
+            assign_seats(&four_seater(), &people, &seats(), SeatingGoal::Center)
+        );
+    }
+}
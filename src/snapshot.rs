@@ -0,0 +1,186 @@
+//! Snapshot comparison of generated SVGs, for regression tests of apps that render charts and
+//! tables without comparing bytes.
+//!
+//! Both SVGs are normalized to one node per line: tags with their attributes sorted by name,
+//! whitespace in the values collapsed and the decimal numbers in them rounded, and the text
+//! between the tags trimmed. Comments, the XML declaration and whitespace between
+//! tags are left out, and an empty tag equals a start tag directly followed by its end.
+
+use std::fmt;
+
+use crate::xml::{self, Node};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SvgDifference {
+    /// An SVG is not well-formed enough to compare.
+    Invalid(String),
+    /// The first differing node of the normalized SVGs, counted from zero, `None` past the end
+    /// of one of them.
+    Node {
+        index: usize,
+        expected: Option<String>,
+        actual: Option<String>,
+    },
+}
+
+impl fmt::Display for SvgDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let or_end = |node: &Option<String>| node.clone().unwrap_or_else(|| "the end".to_string());
+        match self {
+            SvgDifference::Invalid(error) => write!(f, "invalid SVG: {}", error),
+            SvgDifference::Node {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "node {} differs, expected {} but got {}",
+                index,
+                or_end(expected),
+                or_end(actual)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SvgDifference {}
+
+// The decimal numbers in `value` rounded to `decimals` without trailing zeros, with runs of
+// whitespace collapsed to a space. Integers are kept as they are, so colors such as `#00FF00`
+// are not taken for numbers.
+fn rounded(value: &str, decimals: usize) -> String {
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut rounded = String::with_capacity(value.len());
+    let mut rest = value.as_str();
+
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+        // A minus directly before the digits belongs to the number
+        let (before, number_start) = match rest[..start].strip_suffix('-') {
+            Some(before) => (before, start - 1),
+            None => (&rest[..start], start),
+        };
+        rounded.push_str(before);
+        rest = &rest[number_start..];
+        let end = rest[1..]
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .map_or(rest.len(), |end| end + 1);
+        let (number, after) = rest.split_at(end);
+
+        match number.parse::<f64>() {
+            Ok(value) if number.contains('.') => {
+                let fixed = format!("{:.*}", decimals, value);
+                let fixed = match fixed.contains('.') {
+                    true => fixed.trim_end_matches('0').trim_end_matches('.'),
+                    false => &fixed,
+                };
+                rounded.push_str(if fixed == "-0" { "0" } else { fixed });
+            }
+            _ => rounded.push_str(number),
+        }
+        rest = after;
+    }
+
+    rounded.push_str(rest);
+    rounded
+}
+
+/// The SVG as one line per node, with the numbers in attribute values rounded to `decimals`.
+pub fn normalized_svg(svg: &str, decimals: usize) -> Result<String, SvgDifference> {
+    let nodes = xml::nodes(svg).map_err(SvgDifference::Invalid)?;
+    let mut normalized = String::new();
+
+    for node in nodes {
+        match node {
+            Node::Start(element) => {
+                let mut attributes = element.attributes;
+                attributes.sort();
+                normalized.push('<');
+                normalized.push_str(&element.name);
+                for (name, value) in attributes {
+                    normalized.push_str(&format!(" {}=\"{}\"", name, rounded(&value, decimals)));
+                }
+                normalized.push('>');
+            }
+            Node::End(name) => normalized.push_str(&format!("</{}>", name)),
+            Node::Text(text) => normalized.push_str(&text.replace('\n', " ")),
+        }
+        normalized.push('\n');
+    }
+
+    Ok(normalized)
+}
+
+/// Compares the normalized SVGs, with the first difference as the error so a failing test shows
+/// where the SVGs part.
+pub fn compare_svgs(expected: &str, actual: &str, decimals: usize) -> Result<(), SvgDifference> {
+    let expected = normalized_svg(expected, decimals)?;
+    let actual = normalized_svg(actual, decimals)?;
+    let (mut expected, mut actual) = (expected.lines(), actual.lines());
+
+    for index in 0.. {
+        match (expected.next(), actual.next()) {
+            (None, None) => break,
+            (e, a) if e == a => continue,
+            (e, a) => {
+                return Err(SvgDifference::Node {
+                    index,
+                    expected: e.map(str::to_string),
+                    actual: a.map(str::to_string),
+                })
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ignores_attribute_order_and_precision() {
+        let expected = r#"<svg width="100" height="50"><rect x="1.004" y="-0.001"/>
+<text x="10">Fuel</text></svg>"#;
+        let actual = r#"<?xml version="1.0"?>
+<svg height="50" width="100">
+  <rect y="0" x="1"></rect>
+  <text x="10.00">Fuel</text>
+</svg>"#;
+
+        assert_eq!(Ok(()), compare_svgs(expected, actual, 2));
+        assert_eq!(
+            Ok("<rect x=\"1.004\" y=\"-0.001\">\n</rect>\n".to_string()),
+            normalized_svg(r#"<rect y="-0.001" x="1.004"/>"#, 3)
+        );
+        assert_eq!("M1 2.5 L-3 4", rounded("M1.001  2.5\nL-3.0 4", 2));
+        assert_eq!("#00FF00", rounded("#00FF00", 2));
+    }
+
+    #[test]
+    fn reports_the_first_difference() {
+        let difference = compare_svgs(
+            "<svg><text>Fuel</text></svg>",
+            "<svg><text>Pilot</text></svg>",
+            2,
+        );
+        assert_eq!(
+            Err(SvgDifference::Node {
+                index: 2,
+                expected: Some("Fuel".to_string()),
+                actual: Some("Pilot".to_string()),
+            }),
+            difference
+        );
+
+        let shorter = compare_svgs("<svg><g/></svg>", "<svg></svg>", 2).unwrap_err();
+        assert_eq!(
+            "node 1 differs, expected <g> but got </svg>",
+            shorter.to_string()
+        );
+        assert!(matches!(
+            compare_svgs("<svg", "<svg/>", 2),
+            Err(SvgDifference::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,22 @@
+//! The supported API of the crate.
+//!
+//! Everything re-exported here keeps its path, name and signature within a major version (and
+//! within a minor version before 1.0), even when the modules behind it are reorganised. New
+//! methods, trait implementations and error variants may be added; [`WeightBalanceError`] is
+//! `#[non_exhaustive]` for that reason. Items only reachable through the other modules, such as
+//! the table string helpers or the migration functions, can still change between releases.
+
+pub use crate::error::WeightBalanceError;
+pub use crate::loading::{BaggageItem, FuelUplift, Loading, Manifest, Occupant};
+pub use crate::locale::{Labels, Locale};
+pub use crate::registration::{Registration, RegistrationError};
+pub use crate::types::{FuelType, VolumeType};
+#[cfg(feature = "visualizer")]
+pub use crate::visualizer::{
+    weight_and_balance_chart, weight_and_balance_table, weight_and_balance_table_pages, TableUnits,
+    Visualization, WeightBalanceChartVisualization, WeightBalanceTableVisualization,
+};
+pub use crate::weight_and_balance::{
+    Airplane, CenterOfGravity, LeverArm, LimitKind, Limits, Mass, MassMoment, Moment, Volume,
+    WeightBalanceSummary,
+};
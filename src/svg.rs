@@ -0,0 +1,444 @@
+//! A drawing backend writing the SVG of the charts and tables, the elements as the SVG backend of
+//! plotters writes them.
+//!
+//! The `<svg>` element has the role, title and description of the document for assistive
+//! technologies. Groups of elements and the values of table cells are passed to the backend in
+//! drawing order as [`Markup`], so they are written around the elements drawn after them instead
+//! of being patched into the SVG afterwards. Deterministic output has at most two decimals in its
+//! numbers, so it does not depend on the floating point formatting of the platform.
+
+use std::{fmt, io};
+
+use plotters::element::PointCollection;
+use plotters_backend::text_anchor::{HPos, VPos};
+use plotters_backend::{
+    BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingBackend, DrawingErrorKind,
+    FontStyle, FontTransform,
+};
+
+use crate::report::escaped;
+use crate::visualizer::{millimeters, TextDirection};
+
+// The font size of the SVG is the size of the text style divided by this
+const FONT_SCALE: f64 = 1.24;
+
+// What the `<svg>` element says about the document, the metadata to embed and how the elements
+// are written
+pub(crate) struct SvgOptions<'o> {
+    pub(crate) role: &'static str,
+    pub(crate) title: &'o str,
+    pub(crate) description: &'o str,
+    pub(crate) metadata: Option<&'o str>,
+    // The size in millimeters at this DPI instead of in pixels, the view box stays in pixels
+    pub(crate) dpi: Option<f64>,
+    pub(crate) deterministic: bool,
+    pub(crate) text_direction: TextDirection,
+}
+
+// A group of the elements drawn after it until the next group, or the value of the text drawn
+// next, which is wrapped in a group with the value as its title
+pub(crate) enum Markup<'m> {
+    Group { role: &'static str, label: &'m str },
+    Cell { value: f64, unit: &'static str },
+}
+
+// Writes the SVG to `out` as it is drawn, starting with the `<svg>` element when it is prepared
+pub(crate) struct SvgBackend<'a> {
+    out: &'a mut dyn fmt::Write,
+    header: Option<String>,
+    size: (u32, u32),
+    deterministic: bool,
+    text_direction: TextDirection,
+    group_open: bool,
+    cell: Option<(f64, &'static str)>,
+    presented: bool,
+}
+
+impl<'a> SvgBackend<'a> {
+    pub(crate) fn new(
+        out: &'a mut dyn fmt::Write,
+        size: (u32, u32),
+        options: &SvgOptions<'_>,
+    ) -> SvgBackend<'a> {
+        let (width, height) = match options.dpi {
+            Some(dpi) => (
+                format!("{:.2}mm", millimeters(size.0, dpi)),
+                format!("{:.2}mm", millimeters(size.1, dpi)),
+            ),
+            None => (size.0.to_string(), size.1.to_string()),
+        };
+        let mut header = format!(
+            "<svg width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" \
+             xmlns=\"http://www.w3.org/2000/svg\" role=\"{}\">\n",
+            width, height, size.0, size.1, options.role
+        );
+        header.push_str(&format!("<title>{}</title>\n", escaped(options.title)));
+        header.push_str(&format!("<desc>{}</desc>\n", escaped(options.description)));
+        if let Some(metadata) = options.metadata {
+            header.push_str(metadata);
+            header.push('\n');
+        }
+
+        SvgBackend {
+            out,
+            header: Some(header),
+            size,
+            deterministic: options.deterministic,
+            text_direction: options.text_direction,
+            group_open: false,
+            cell: None,
+            presented: false,
+        }
+    }
+
+    pub(crate) fn markup(
+        &mut self,
+        markup: &Markup<'_>,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        match markup {
+            Markup::Group { role, label } => {
+                self.close_group()?;
+                self.write(&format!(
+                    "<g role=\"{}\" aria-label=\"{}\">\n",
+                    role,
+                    escaped(label)
+                ))?;
+                self.group_open = true;
+            }
+            Markup::Cell { value, unit } => self.cell = Some((*value, unit)),
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, svg: &str) -> Result<(), DrawingErrorKind<io::Error>> {
+        self.out
+            .write_str(svg)
+            .map_err(|e| DrawingErrorKind::DrawingError(io::Error::other(e)))
+    }
+
+    fn close_group(&mut self) -> Result<(), DrawingErrorKind<io::Error>> {
+        if self.group_open {
+            self.write("</g>\n")?;
+            self.group_open = false;
+        }
+        Ok(())
+    }
+
+    // `value` as plotters writes it, rounded to two decimals when deterministic
+    fn number(&self, value: f64) -> String {
+        let number = value.to_string();
+        match number.split_once('.') {
+            Some((_, decimals)) if self.deterministic && decimals.len() > 2 => {
+                let rounded = format!("{:.2}", value);
+                rounded
+                    .trim_end_matches('0')
+                    .trim_end_matches('.')
+                    .to_string()
+            }
+            _ => number,
+        }
+    }
+
+    fn color(color: BackendColor) -> String {
+        let (r, g, b) = color.rgb;
+        format!("#{:02X}{:02X}{:02X}", r, g, b)
+    }
+
+    // An element without content, its attribute values escaped
+    fn empty_tag(
+        &mut self,
+        name: &str,
+        attributes: &[(&str, &str)],
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        self.write(&format!("<{}{}/>\n", name, Self::attributes(attributes)))
+    }
+
+    fn attributes(attributes: &[(&str, &str)]) -> String {
+        attributes
+            .iter()
+            .map(|(key, value)| format!(" {}=\"{}\"", key, escaped(value)))
+            .collect()
+    }
+
+    fn points<I: IntoIterator<Item = BackendCoord>>(path: I) -> String {
+        path.into_iter()
+            .map(|(x, y)| format!("{},{} ", x, y))
+            .collect()
+    }
+}
+
+// Whether the first letter of `text` is of a script written right to left
+pub(crate) fn starts_rtl(text: &str) -> bool {
+    text.chars().find(|c| c.is_alphabetic()).is_some_and(|c| {
+        matches!(
+            c as u32,
+            0x0590..=0x08FF
+                | 0xFB1D..=0xFDFF
+                | 0xFE70..=0xFEFF
+                | 0x10800..=0x10FFF
+                | 0x1E800..=0x1EFFF
+        )
+    })
+}
+
+impl DrawingBackend for SvgBackend<'_> {
+    type ErrorType = io::Error;
+
+    fn get_size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<io::Error>> {
+        match self.header.take() {
+            Some(header) => self.write(&header),
+            None => Ok(()),
+        }
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<io::Error>> {
+        if !self.presented {
+            self.ensure_prepared()?;
+            self.close_group()?;
+            self.write("</svg>\n")?;
+            self.presented = true;
+        }
+        Ok(())
+    }
+
+    fn draw_pixel(
+        &mut self,
+        point: BackendCoord,
+        color: BackendColor,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        if color.alpha == 0.0 {
+            return Ok(());
+        }
+        let opacity = self.number(color.alpha);
+        self.empty_tag(
+            "rect",
+            &[
+                ("x", &point.0.to_string()),
+                ("y", &point.1.to_string()),
+                ("width", "1"),
+                ("height", "1"),
+                ("stroke", "none"),
+                ("opacity", &opacity),
+                ("fill", &Self::color(color)),
+            ],
+        )
+    }
+
+    fn draw_line<S: BackendStyle>(
+        &mut self,
+        from: BackendCoord,
+        to: BackendCoord,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        let color = style.color();
+        if color.alpha == 0.0 {
+            return Ok(());
+        }
+        let opacity = self.number(color.alpha);
+        self.empty_tag(
+            "line",
+            &[
+                ("opacity", &opacity),
+                ("stroke", &Self::color(color)),
+                ("stroke-width", &style.stroke_width().to_string()),
+                ("x1", &from.0.to_string()),
+                ("y1", &from.1.to_string()),
+                ("x2", &to.0.to_string()),
+                ("y2", &to.1.to_string()),
+            ],
+        )
+    }
+
+    fn draw_rect<S: BackendStyle>(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        let color = style.color();
+        if color.alpha == 0.0 {
+            return Ok(());
+        }
+        let (fill, stroke) = match fill {
+            true => (Self::color(color), "none".to_string()),
+            false => ("none".to_string(), Self::color(color)),
+        };
+        let opacity = self.number(color.alpha);
+        self.empty_tag(
+            "rect",
+            &[
+                ("x", &upper_left.0.to_string()),
+                ("y", &upper_left.1.to_string()),
+                ("width", &(bottom_right.0 - upper_left.0).to_string()),
+                ("height", &(bottom_right.1 - upper_left.1).to_string()),
+                ("opacity", &opacity),
+                ("fill", &fill),
+                ("stroke", &stroke),
+            ],
+        )
+    }
+
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        path: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        let color = style.color();
+        if color.alpha == 0.0 {
+            return Ok(());
+        }
+        let opacity = self.number(color.alpha);
+        self.empty_tag(
+            "polyline",
+            &[
+                ("fill", "none"),
+                ("opacity", &opacity),
+                ("stroke", &Self::color(color)),
+                ("stroke-width", &style.stroke_width().to_string()),
+                ("points", &Self::points(path)),
+            ],
+        )
+    }
+
+    fn draw_circle<S: BackendStyle>(
+        &mut self,
+        center: BackendCoord,
+        radius: u32,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        let color = style.color();
+        if color.alpha == 0.0 {
+            return Ok(());
+        }
+        let (stroke, fill) = match fill {
+            true => ("none".to_string(), Self::color(color)),
+            false => (Self::color(color), "none".to_string()),
+        };
+        let opacity = self.number(color.alpha);
+        self.empty_tag(
+            "circle",
+            &[
+                ("cx", &center.0.to_string()),
+                ("cy", &center.1.to_string()),
+                ("r", &radius.to_string()),
+                ("opacity", &opacity),
+                ("fill", &fill),
+                ("stroke", &stroke),
+                ("stroke-width", &style.stroke_width().to_string()),
+            ],
+        )
+    }
+
+    fn fill_polygon<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        vert: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        let color = style.color();
+        if color.alpha == 0.0 {
+            return Ok(());
+        }
+        let opacity = self.number(color.alpha);
+        self.empty_tag(
+            "polygon",
+            &[
+                ("opacity", &opacity),
+                ("fill", &Self::color(color)),
+                ("points", &Self::points(vert)),
+            ],
+        )
+    }
+
+    // A right to left text has its anchor swapped, so it still starts at the same side of its
+    // position
+    fn draw_text<S: BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &S,
+        pos: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        let color = style.color();
+        if color.alpha == 0.0 {
+            return Ok(());
+        }
+        let right_to_left = match self.text_direction {
+            TextDirection::Ltr => false,
+            TextDirection::Rtl => true,
+            TextDirection::Auto => starts_rtl(text),
+        };
+        let anchor = match (style.anchor().h_pos, right_to_left) {
+            (HPos::Left, false) | (HPos::Right, true) => "start",
+            (HPos::Right, false) | (HPos::Left, true) => "end",
+            (HPos::Center, _) => "middle",
+        };
+        let dy = match style.anchor().v_pos {
+            VPos::Top => "0.76em",
+            VPos::Center => "0.5ex",
+            VPos::Bottom => "-0.5ex",
+        };
+
+        let (x, y) = (pos.0.to_string(), pos.1.to_string());
+        let mut attributes = vec![];
+        if right_to_left {
+            attributes.push(("direction", "rtl".to_string()));
+        }
+        attributes.extend([
+            ("x", x.clone()),
+            ("y", y.clone()),
+            ("dy", dy.to_string()),
+            ("text-anchor", anchor.to_string()),
+            ("font-family", style.family().as_str().to_string()),
+            ("font-size", self.number(style.size() / FONT_SCALE)),
+            ("opacity", self.number(color.alpha)),
+            ("fill", Self::color(color)),
+        ]);
+        match style.style() {
+            FontStyle::Normal => {}
+            FontStyle::Bold => attributes.push(("font-weight", "bold".to_string())),
+            other => attributes.push(("font-style", other.as_str().to_string())),
+        }
+        let degrees = match style.transform() {
+            FontTransform::Rotate90 => Some(90),
+            FontTransform::Rotate180 => Some(180),
+            FontTransform::Rotate270 => Some(270),
+            FontTransform::None => None,
+        };
+        if let Some(degrees) = degrees {
+            attributes.push(("transform", format!("rotate({}, {}, {})", degrees, x, y)));
+        }
+
+        let attributes: Vec<(&str, &str)> = attributes
+            .iter()
+            .map(|(key, value)| (*key, value.as_str()))
+            .collect();
+        let mut svg = format!(
+            "<text{}>\n{}\n</text>\n",
+            Self::attributes(&attributes),
+            escaped(text)
+        );
+        if let Some((value, unit)) = self.cell.take() {
+            let value = self.number(value);
+            svg = format!(
+                "<g data-value=\"{}\" data-unit=\"{}\"><title>{} {}</title>\n{}</g>\n",
+                value, unit, value, unit, svg
+            );
+        }
+        self.write(&svg)
+    }
+}
+
+// The markup draws nothing itself, it has no points
+impl<'b> PointCollection<'b, BackendCoord> for &'b Markup<'_> {
+    type Point = &'b BackendCoord;
+    type IntoIter = core::iter::Empty<&'b BackendCoord>;
+
+    fn point_iter(self) -> Self::IntoIter {
+        core::iter::empty()
+    }
+}
@@ -0,0 +1,151 @@
+//! Statistics over the summaries of past flights, to see whether an airplane is habitually flown
+//! close to its maximum take-off mass.
+//!
+//! The MTOW of a flight is its take-off mass plus the MTOW margin of its summary, so a history
+//! may cover flights under different limits.
+
+use crate::weight_and_balance::{Mass, WeightBalanceSummary};
+
+/// How close to the MTOW a take-off counts as heavy unless given otherwise.
+pub const HEAVY_MARGIN: Mass = Mass::Kilo(10.0);
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrendStatistics {
+    flights: usize,
+    average_takeoff_mass: Mass,
+    average_landing_mass: Option<Mass>,
+    heavy_flights: usize,
+    above_mtow_flights: usize,
+}
+
+impl TrendStatistics {
+    /// Statistics with take-offs within [`HEAVY_MARGIN`] of the MTOW counted as heavy, `None`
+    /// without flights.
+    pub fn new(history: &[WeightBalanceSummary]) -> Option<TrendStatistics> {
+        TrendStatistics::with_heavy_margin(history, &HEAVY_MARGIN)
+    }
+
+    pub fn with_heavy_margin(
+        history: &[WeightBalanceSummary],
+        heavy_margin: &Mass,
+    ) -> Option<TrendStatistics> {
+        if history.is_empty() {
+            return None;
+        }
+
+        let flights = history.len();
+        let takeoff: f64 = history.iter().map(|s| s.takeoff_mass().kilo()).sum();
+        let landings: alloc::vec::Vec<f64> = history
+            .iter()
+            .filter_map(|s| s.landing_mass().map(Mass::kilo))
+            .collect();
+        let average_landing_mass = match landings.is_empty() {
+            true => None,
+            false => Some(Mass::Kilo(
+                landings.iter().sum::<f64>() / landings.len() as f64,
+            )),
+        };
+
+        Some(TrendStatistics {
+            flights,
+            average_takeoff_mass: Mass::Kilo(takeoff / flights as f64),
+            average_landing_mass,
+            heavy_flights: history
+                .iter()
+                .filter(|s| s.mtow_margin().kilo() <= heavy_margin.kilo())
+                .count(),
+            above_mtow_flights: history
+                .iter()
+                .filter(|s| s.mtow_margin().kilo() < 0.0)
+                .count(),
+        })
+    }
+
+    pub fn flights(&self) -> usize {
+        self.flights
+    }
+
+    pub fn average_takeoff_mass(&self) -> &Mass {
+        &self.average_takeoff_mass
+    }
+
+    /// Over the flights with a landing mass, `None` when there are none.
+    pub fn average_landing_mass(&self) -> Option<&Mass> {
+        self.average_landing_mass.as_ref()
+    }
+
+    /// The flights that took off within the heavy margin of the MTOW or above it.
+    pub fn heavy_flights(&self) -> usize {
+        self.heavy_flights
+    }
+
+    /// The heavy flights as a percentage of all flights.
+    pub fn heavy_percentage(&self) -> f64 {
+        self.heavy_flights as f64 / self.flights as f64 * 100.0
+    }
+
+    pub fn above_mtow_flights(&self) -> usize {
+        self.above_mtow_flights
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{Airplane, CenterOfGravity, LeverArm, Limits, Moment, Volume};
+    use alloc::string::ToString;
+
+    fn summary(pilot: f64) -> WeightBalanceSummary {
+        Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            alloc::vec![
+                Moment::new(
+                    "Empty".to_string(),
+                    LeverArm::Meter(0.4294),
+                    Mass::Kilo(517.0),
+                ),
+                Moment::new(
+                    "Pilot".to_string(),
+                    LeverArm::Meter(0.515),
+                    Mass::Kilo(pilot)
+                ),
+                Moment::new(
+                    "Fuel".to_string(),
+                    LeverArm::Meter(0.325),
+                    Mass::Avgas(Volume::Liter(100.0)),
+                ),
+            ],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(50.0),
+        )
+        .summary()
+    }
+
+    #[test]
+    fn statistics_over_flights() {
+        assert_eq!(None, TrendStatistics::new(&[]));
+
+        // 589 kg empty with fuel, 36 kg of fuel burned
+        let history = [summary(80.0), summary(155.0), summary(170.0)];
+        let statistics = TrendStatistics::new(&history).unwrap();
+        assert_eq!(3, statistics.flights());
+        assert!((statistics.average_takeoff_mass().kilo() - (589.0 + 135.0)).abs() < 1e-9);
+        assert!(
+            (statistics.average_landing_mass().unwrap().kilo() - (589.0 + 135.0 - 36.0)).abs()
+                < 1e-9
+        );
+        assert_eq!(2, statistics.heavy_flights());
+        assert_eq!(1, statistics.above_mtow_flights());
+        assert!((statistics.heavy_percentage() - 200.0 / 3.0).abs() < 1e-9);
+
+        let statistics = TrendStatistics::with_heavy_margin(&history, &Mass::Kilo(0.0)).unwrap();
+        assert_eq!(1, statistics.heavy_flights());
+    }
+}
@@ -0,0 +1,200 @@
+//! Trip fuel from the phases of a flight instead of a single figure.
+//!
+//! Start and taxi burn a fixed amount, the climb, cruise and descent burn at their own rate per
+//! hour for a number of minutes. The trip fuel is the sum of all phases, so the landing figures of
+//! an [`Airplane`] with it reflect the whole flight.
+
+use alloc::vec::Vec;
+
+use crate::weight_and_balance::{Airplane, Volume};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Phase {
+    StartTaxi,
+    Climb,
+    Cruise,
+    Descent,
+}
+
+/// The fuel burned in one phase of the flight.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhaseFuel {
+    phase: Phase,
+    minutes: f64,
+    fuel: Volume,
+}
+
+impl PhaseFuel {
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// Zero for start and taxi, which burn a fixed amount.
+    pub fn minutes(&self) -> f64 {
+        self.minutes
+    }
+
+    pub fn fuel(&self) -> &Volume {
+        &self.fuel
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Rate {
+    minutes: f64,
+    burn_rate: Volume,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlightProfile {
+    start_taxi: Volume,
+    #[cfg_attr(feature = "serde", serde(default))]
+    climb: Option<Rate>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    cruise: Option<Rate>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    descent: Option<Rate>,
+}
+
+impl FlightProfile {
+    /// The trip fuel is in the unit of `start_taxi`.
+    pub fn new(start_taxi: Volume) -> FlightProfile {
+        FlightProfile {
+            start_taxi,
+            climb: None,
+            cruise: None,
+            descent: None,
+        }
+    }
+
+    /// `minutes` of climb at `burn_rate` per hour.
+    pub fn with_climb(mut self, minutes: f64, burn_rate: Volume) -> FlightProfile {
+        self.climb = Some(Rate { minutes, burn_rate });
+        self
+    }
+
+    pub fn with_cruise(mut self, minutes: f64, burn_rate: Volume) -> FlightProfile {
+        self.cruise = Some(Rate { minutes, burn_rate });
+        self
+    }
+
+    pub fn with_descent(mut self, minutes: f64, burn_rate: Volume) -> FlightProfile {
+        self.descent = Some(Rate { minutes, burn_rate });
+        self
+    }
+
+    /// The fuel of every phase in the order they are flown, each in the unit of its burn rate.
+    /// Phases that are not set are left out.
+    pub fn breakdown(&self) -> Vec<PhaseFuel> {
+        let mut phases = alloc::vec![PhaseFuel {
+            phase: Phase::StartTaxi,
+            minutes: 0.0,
+            fuel: self.start_taxi.clone(),
+        }];
+
+        for (phase, rate) in [
+            (Phase::Climb, &self.climb),
+            (Phase::Cruise, &self.cruise),
+            (Phase::Descent, &self.descent),
+        ] {
+            let Some(rate) = rate else {
+                continue;
+            };
+            let hours = rate.minutes / 60.0;
+            let fuel = match rate.burn_rate {
+                Volume::Liter(l) => Volume::Liter(l * hours),
+                Volume::Gallon(g) => Volume::Gallon(g * hours),
+            };
+            phases.push(PhaseFuel {
+                phase,
+                minutes: rate.minutes,
+                fuel,
+            });
+        }
+        phases
+    }
+
+    pub fn trip_fuel(&self) -> Volume {
+        let liter: f64 = self.breakdown().iter().map(|p| p.fuel.to_liter()).sum();
+        match self.start_taxi {
+            Volume::Liter(_) => Volume::Liter(liter),
+            Volume::Gallon(_) => Volume::Gallon(Volume::Liter(liter).to_gallon()),
+        }
+    }
+
+    /// `plane` with the trip fuel of this profile.
+    pub fn apply(&self, plane: Airplane) -> Airplane {
+        plane.with_fuel_consumption_trip(self.trip_fuel())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{CenterOfGravity, LeverArm, Limits, Mass, Moment};
+    use alloc::string::ToString;
+
+    #[test]
+    fn trip_fuel_from_phases() {
+        let profile = FlightProfile::new(Volume::Liter(2.0))
+            .with_climb(10.0, Volume::Liter(30.0))
+            .with_cruise(90.0, Volume::Liter(20.0))
+            .with_descent(15.0, Volume::Gallon(4.0));
+
+        let breakdown = profile.breakdown();
+        assert_eq!(
+            alloc::vec![
+                Phase::StartTaxi,
+                Phase::Climb,
+                Phase::Cruise,
+                Phase::Descent
+            ],
+            breakdown
+                .iter()
+                .map(PhaseFuel::phase)
+                .collect::<Vec<Phase>>()
+        );
+        assert!((breakdown[1].fuel().to_liter() - 5.0).abs() < 1e-9);
+        assert_eq!(&Volume::Gallon(1.0), breakdown[3].fuel());
+
+        let trip = 2.0 + 5.0 + 30.0 + Volume::Gallon(1.0).to_liter();
+        assert!((profile.trip_fuel().to_liter() - trip).abs() < 1e-9);
+        assert_eq!(1, FlightProfile::new(Volume::Liter(2.0)).breakdown().len());
+
+        let plane = profile.apply(Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            alloc::vec![
+                Moment::new(
+                    "Empty".to_string(),
+                    LeverArm::Meter(0.4294),
+                    Mass::Kilo(517.0),
+                ),
+                Moment::new(
+                    "Fuel".to_string(),
+                    LeverArm::Meter(0.325),
+                    Mass::Avgas(Volume::Liter(60.0)),
+                ),
+            ],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(0.0),
+        ));
+        assert!(
+            (plane.total_mass_landing().unwrap().kilo() - (517.0 + (60.0 - trip) * 0.72)).abs()
+                < 1e-9
+        );
+    }
+}
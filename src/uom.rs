@@ -0,0 +1,81 @@
+//! Conversions to and from [`uom`](::uom) quantities.
+//!
+//! Fuel masses convert with the density of their fuel type, quantities coming from `uom` become
+//! kilograms, liters and meters.
+
+use ::uom::si::f64 as si;
+use ::uom::si::{length::meter, mass::kilogram, volume::liter};
+
+use crate::weight_and_balance::{CenterOfGravity, LeverArm, Mass, Volume};
+
+impl From<Mass> for si::Mass {
+    fn from(mass: Mass) -> si::Mass {
+        si::Mass::new::<kilogram>(mass.kilo())
+    }
+}
+
+impl From<si::Mass> for Mass {
+    fn from(mass: si::Mass) -> Mass {
+        Mass::Kilo(mass.get::<kilogram>())
+    }
+}
+
+impl From<Volume> for si::Volume {
+    fn from(volume: Volume) -> si::Volume {
+        si::Volume::new::<liter>(volume.to_liter())
+    }
+}
+
+impl From<si::Volume> for Volume {
+    fn from(volume: si::Volume) -> Volume {
+        Volume::Liter(volume.get::<liter>())
+    }
+}
+
+impl From<LeverArm> for si::Length {
+    fn from(lever_arm: LeverArm) -> si::Length {
+        si::Length::new::<meter>(lever_arm.meter())
+    }
+}
+
+impl From<si::Length> for LeverArm {
+    fn from(length: si::Length) -> LeverArm {
+        LeverArm::Meter(length.get::<meter>())
+    }
+}
+
+impl From<CenterOfGravity> for si::Length {
+    fn from(center_of_gravity: CenterOfGravity) -> si::Length {
+        si::Length::new::<meter>(center_of_gravity.meter())
+    }
+}
+
+impl From<si::Length> for CenterOfGravity {
+    fn from(length: si::Length) -> CenterOfGravity {
+        CenterOfGravity::Meter(length.get::<meter>())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::uom::si::{length::inch, mass::pound, volume::gallon};
+
+    #[test]
+    fn conversions() {
+        let fuel: si::Mass = Mass::Avgas(Volume::Liter(100.0)).into();
+        assert!((fuel.get::<kilogram>() - 72.0).abs() < 1e-9);
+
+        let pilot = Mass::from(si::Mass::new::<pound>(176.37));
+        assert!((pilot.kilo() - 80.0).abs() < 0.01);
+
+        let tank: si::Volume = Volume::Gallon(10.0).into();
+        assert!((tank.get::<gallon>() - 10.0).abs() < 1e-4);
+
+        let arm = LeverArm::from(si::Length::new::<inch>(20.28));
+        assert!((arm.meter() - 0.5151).abs() < 0.001);
+
+        let cg: si::Length = CenterOfGravity::Millimeter(427.0).into();
+        assert!((cg.get::<meter>() - 0.427).abs() < 1e-9);
+    }
+}
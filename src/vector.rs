@@ -0,0 +1,481 @@
+//! A drawing backend writing PDF or encapsulated PostScript, so the chart and the table are
+//! vector documents instead of rasterized SVGs.
+//!
+//! Text is set in the standard Helvetica and Courier fonts every PDF reader and PostScript printer
+//! has, so no fonts are embedded, and is laid out with the fixed text metrics of the deterministic
+//! SVG output. A pixel is a point unless the size is given at a DPI.
+//!
+//! Both formats share the content stream of PDF, the prolog of the EPS defines its operators as
+//! PostScript procedures. PostScript has no transparency, so colors with an alpha are mixed with
+//! the white background instead.
+
+use std::{fmt, io};
+
+use plotters_backend::text_anchor::{HPos, VPos};
+use plotters_backend::{
+    BackendColor, BackendCoord, BackendStyle, BackendTextStyle, DrawingBackend, DrawingErrorKind,
+    FontFamily, FontStyle, FontTransform,
+};
+
+// The width of a character relative to the font size, exact for Courier and the average for
+// Helvetica
+const COURIER_WIDTH_EM: f64 = 0.6;
+const HELVETICA_WIDTH_EM: f64 = 0.5;
+
+// The font size of the SVG output is the size of the text style divided by this
+const FONT_SCALE: f64 = 1.24;
+
+const POINTS_IN_INCH: f64 = 72.0;
+
+// The distance of the control points of a quarter circle as a Bézier curve to its ends
+const KAPPA: f64 = 0.552_284_75;
+
+// The font resources by name
+const FONTS: [(&str, &str); 4] = [
+    ("F1", "Helvetica"),
+    ("F2", "Helvetica-Bold"),
+    ("F3", "Courier"),
+    ("F4", "Courier-Bold"),
+];
+
+// The operators of the content stream as PostScript procedures, in a dictionary of their own so
+// they do not leak into the document the EPS is placed in
+const EPS_PROLOG: &str = "/airplane 32 dict def
+airplane begin
+/q {gsave} bind def
+/Q {grestore} bind def
+/cm {6 array astore concat} bind def
+/rg {setrgbcolor} bind def
+/RG {setrgbcolor} bind def
+/w {setlinewidth} bind def
+/m {moveto} bind def
+/l {lineto} bind def
+/c {curveto} bind def
+/h {closepath} bind def
+/re {4 2 roll moveto 1 index 0 rlineto 0 exch rlineto neg 0 rlineto closepath} bind def
+/S {stroke} bind def
+/f {fill} bind def
+/BT {} def
+/ET {} def
+/Tf {selectfont} bind def
+/Tm {6 array astore concat} bind def
+/Td {moveto} bind def
+/Tj {show} bind def
+/latin1 {findfont dup length dict begin {1 index /FID ne {def} {pop pop} ifelse} forall
+/Encoding ISOLatin1Encoding def currentdict end definefont pop} bind def
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VectorFormat {
+    Pdf,
+    Eps,
+}
+
+// Draws the content stream of a page to `buf`, written to `out` in the complete document when
+// presented
+pub(crate) struct VectorBackend<'a> {
+    out: &'a mut dyn fmt::Write,
+    buf: String,
+    dimensions: (u32, u32),
+    scale: f64,
+    title: String,
+    format: VectorFormat,
+    presented: bool,
+}
+
+impl<'a> VectorBackend<'a> {
+    pub(crate) fn new(
+        out: &'a mut dyn fmt::Write,
+        dimensions: (u32, u32),
+        dpi: Option<f64>,
+        title: String,
+        format: VectorFormat,
+    ) -> VectorBackend<'a> {
+        let scale = dpi.map_or(1.0, |dpi| POINTS_IN_INCH / dpi);
+        // The origin of PDF and PostScript is at the bottom left, plotters draws from the top left
+        let buf = format!(
+            "{:.4} 0 0 {:.4} 0 {:.2} cm\n",
+            scale,
+            -scale,
+            dimensions.1 as f64 * scale
+        );
+
+        VectorBackend {
+            out,
+            buf,
+            dimensions,
+            scale,
+            title,
+            format,
+            presented: false,
+        }
+    }
+
+    // Saves the graphics state and sets the color and opacity with the fill (`rg`) or stroke
+    // (`RG`) operator, `false` when the color is transparent and nothing is drawn
+    fn paint(&mut self, color: BackendColor, operator: &str) -> bool {
+        if color.alpha == 0.0 {
+            return false;
+        }
+        let alpha = match self.format {
+            VectorFormat::Pdf => 1.0,
+            VectorFormat::Eps => color.alpha,
+        };
+        let channel = |c: u8| (c as f64 * alpha + 255.0 * (1.0 - alpha)) / 255.0;
+        let (r, g, b) = color.rgb;
+        self.buf.push_str(&format!(
+            "q {:.3} {:.3} {:.3} {}\n",
+            channel(r),
+            channel(g),
+            channel(b),
+            operator
+        ));
+        if color.alpha < 1.0 && self.format == VectorFormat::Pdf {
+            self.buf
+                .push_str(&format!("/{} gs\n", opacity_name(color.alpha)));
+        }
+        true
+    }
+
+    fn stroke<S: BackendStyle>(&mut self, style: &S, path: &str) {
+        if self.paint(style.color(), "RG") {
+            self.buf
+                .push_str(&format!("{} w\n{}S Q\n", style.stroke_width(), path));
+        }
+    }
+
+    fn fill<S: BackendStyle>(&mut self, style: &S, path: &str) {
+        if self.paint(style.color(), "rg") {
+            self.buf.push_str(&format!("{}f Q\n", path));
+        }
+    }
+}
+
+// The graphics state with `alpha` as the opacity, e.g. `A020` for 0.2
+fn opacity_name(alpha: f64) -> String {
+    format!("A{:03}", (alpha.clamp(0.0, 1.0) * 100.0).round() as u32)
+}
+
+// The points as a path, closed when `close` is set
+fn path(points: impl IntoIterator<Item = BackendCoord>, close: bool) -> String {
+    let mut path = String::new();
+    for (i, (x, y)) in points.into_iter().enumerate() {
+        let operator = if i == 0 { "m" } else { "l" };
+        path.push_str(&format!("{} {} {}\n", x, y, operator));
+    }
+    if close && !path.is_empty() {
+        path.push_str("h\n");
+    }
+    path
+}
+
+fn rectangle(upper_left: BackendCoord, bottom_right: BackendCoord) -> String {
+    format!(
+        "{} {} {} {} re\n",
+        upper_left.0,
+        upper_left.1,
+        bottom_right.0 - upper_left.0,
+        bottom_right.1 - upper_left.1
+    )
+}
+
+fn circle(center: BackendCoord, radius: u32) -> String {
+    let (x, y, r) = (center.0 as f64, center.1 as f64, radius as f64);
+    let k = KAPPA * r;
+    let mut path = format!("{:.2} {:.2} m\n", x + r, y);
+    for [c1, c2, end] in [
+        [(x + r, y + k), (x + k, y + r), (x, y + r)],
+        [(x - k, y + r), (x - r, y + k), (x - r, y)],
+        [(x - r, y - k), (x - k, y - r), (x, y - r)],
+        [(x + k, y - r), (x + r, y - k), (x + r, y)],
+    ] {
+        path.push_str(&format!(
+            "{:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c\n",
+            c1.0, c1.1, c2.0, c2.1, end.0, end.1
+        ));
+    }
+    path.push_str("h\n");
+    path
+}
+
+// `text` as a string in the WinAnsi encoding of the standard fonts in PDF, or Latin-1 in the EPS,
+// which match from U+00A0, other characters become `?`
+pub(crate) fn string(text: &str) -> String {
+    let mut string = String::from("(");
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                string.push('\\');
+                string.push(c);
+            }
+            ' '..='~' => string.push(c),
+            '\u{a0}'..='\u{ff}' => string.push_str(&format!("\\{:03o}", c as u32)),
+            _ => string.push('?'),
+        }
+    }
+    string.push(')');
+    string
+}
+
+// The resource name and character width of the font of `style`
+fn font<S: BackendTextStyle>(style: &S) -> (&'static str, f64) {
+    match (style.family(), style.style()) {
+        (FontFamily::Monospace, FontStyle::Bold) => ("F4", COURIER_WIDTH_EM),
+        (FontFamily::Monospace, _) => ("F3", COURIER_WIDTH_EM),
+        (_, FontStyle::Bold) => ("F2", HELVETICA_WIDTH_EM),
+        _ => ("F1", HELVETICA_WIDTH_EM),
+    }
+}
+
+// A PDF of one page of `size` points with `content`, all ASCII
+fn pdf_document(content: &str, size: (f64, f64), title: &str) -> String {
+    let content = content.trim_end();
+    let mut opacities: Vec<&str> = content
+        .match_indices(" gs\n")
+        .filter_map(|(end, _)| content.get(end.checked_sub(4)?..end))
+        .filter(|name| name.starts_with('A'))
+        .collect();
+    opacities.sort_unstable();
+    opacities.dedup();
+
+    let fonts: String = FONTS
+        .iter()
+        .map(|(name, font)| {
+            format!(
+                " /{} << /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                name, font
+            )
+        })
+        .collect();
+    let states: String = opacities
+        .iter()
+        .map(|name| {
+            let alpha = name[1..].parse::<f64>().unwrap_or(100.0) / 100.0;
+            format!(" /{} << /ca {:.2} /CA {:.2} >>", name, alpha, alpha)
+        })
+        .collect();
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] \
+             /Resources << /Font <<{} >> /ExtGState <<{} >> >> /Contents 4 0 R >>",
+            size.0, size.1, fonts, states
+        ),
+        format!(
+            "<< /Length {} >>\nstream\n{}\nendstream",
+            content.len(),
+            content
+        ),
+        format!(
+            "<< /Title {} /Producer (airplane {}) >>",
+            string(title),
+            env!("CARGO_PKG_VERSION")
+        ),
+    ];
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+    }
+    let xref = pdf.len();
+    pdf.push_str(&format!(
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    ));
+    for offset in offsets {
+        pdf.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    pdf.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R /Info {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        objects.len(),
+        xref
+    ));
+    pdf
+}
+
+// An EPS of `size` points with `content`, all ASCII
+fn eps_document(content: &str, size: (f64, f64), title: &str) -> String {
+    let mut eps = format!(
+        "%!PS-Adobe-3.0 EPSF-3.0\n\
+         %%BoundingBox: 0 0 {} {}\n\
+         %%HiResBoundingBox: 0 0 {:.2} {:.2}\n\
+         %%Title: {}\n\
+         %%Creator: airplane {}\n\
+         %%LanguageLevel: 2\n\
+         %%Pages: 1\n\
+         %%EndComments\n\
+         %%BeginProlog\n",
+        size.0.ceil(),
+        size.1.ceil(),
+        size.0,
+        size.1,
+        string(title),
+        env!("CARGO_PKG_VERSION")
+    );
+    eps.push_str(EPS_PROLOG);
+    for (name, font) in FONTS {
+        eps.push_str(&format!("/{} /{} latin1\n", name, font));
+    }
+    eps.push_str("end\n%%EndProlog\n%%Page: 1 1\nsave\nairplane begin\n");
+    eps.push_str(content);
+    eps.push_str("end\nrestore\nshowpage\n%%Trailer\n%%EOF\n");
+    eps
+}
+
+impl DrawingBackend for VectorBackend<'_> {
+    type ErrorType = io::Error;
+
+    fn get_size(&self) -> (u32, u32) {
+        self.dimensions
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<io::Error>> {
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<io::Error>> {
+        if !self.presented {
+            let size = (
+                self.dimensions.0 as f64 * self.scale,
+                self.dimensions.1 as f64 * self.scale,
+            );
+            let document = match self.format {
+                VectorFormat::Pdf => pdf_document(&self.buf, size, &self.title),
+                VectorFormat::Eps => eps_document(&self.buf, size, &self.title),
+            };
+            self.out
+                .write_str(&document)
+                .map_err(|e| DrawingErrorKind::DrawingError(io::Error::other(e)))?;
+            self.presented = true;
+        }
+        Ok(())
+    }
+
+    fn draw_pixel(
+        &mut self,
+        point: BackendCoord,
+        color: BackendColor,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        self.fill(&color, &rectangle(point, (point.0 + 1, point.1 + 1)));
+        Ok(())
+    }
+
+    fn draw_line<S: BackendStyle>(
+        &mut self,
+        from: BackendCoord,
+        to: BackendCoord,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        self.stroke(style, &path([from, to], false));
+        Ok(())
+    }
+
+    fn draw_rect<S: BackendStyle>(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        let rectangle = rectangle(upper_left, bottom_right);
+        match fill {
+            true => self.fill(style, &rectangle),
+            false => self.stroke(style, &rectangle),
+        }
+        Ok(())
+    }
+
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        path: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        self.stroke(style, &self::path(path, false));
+        Ok(())
+    }
+
+    fn draw_circle<S: BackendStyle>(
+        &mut self,
+        center: BackendCoord,
+        radius: u32,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        let circle = circle(center, radius);
+        match fill {
+            true => self.fill(style, &circle),
+            false => self.stroke(style, &circle),
+        }
+        Ok(())
+    }
+
+    fn fill_polygon<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        vert: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        self.fill(style, &path(vert, true));
+        Ok(())
+    }
+
+    fn draw_text<S: BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &S,
+        pos: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        let (font, width_em) = font(style);
+        let size = style.size() / FONT_SCALE;
+        let width = text.chars().count() as f64 * size * width_em;
+
+        // Moved along the text to its anchor, with the baseline where the SVG output has it
+        let dx = match style.anchor().h_pos {
+            HPos::Left => 0.0,
+            HPos::Center => -width / 2.0,
+            HPos::Right => -width,
+        };
+        let dy = match style.anchor().v_pos {
+            VPos::Top => -0.76 * size,
+            VPos::Center => -0.25 * size,
+            VPos::Bottom => 0.25 * size,
+        };
+        // Upright text in the flipped coordinates, rotated clockwise
+        let (a, b, c, d) = match style.transform() {
+            FontTransform::None => (1, 0, 0, -1),
+            FontTransform::Rotate90 => (0, 1, 1, 0),
+            FontTransform::Rotate180 => (-1, 0, 0, 1),
+            FontTransform::Rotate270 => (0, -1, -1, 0),
+        };
+
+        if self.paint(style.color(), "rg") {
+            self.buf.push_str(&format!(
+                "BT /{} {:.2} Tf {} {} {} {} {} {} Tm {:.2} {:.2} Td {} Tj ET Q\n",
+                font,
+                size,
+                a,
+                b,
+                c,
+                d,
+                pos.0,
+                pos.1,
+                dx,
+                dy,
+                string(text)
+            ));
+        }
+        Ok(())
+    }
+
+    fn estimate_text_size<S: BackendTextStyle>(
+        &self,
+        text: &str,
+        style: &S,
+    ) -> Result<(u32, u32), DrawingErrorKind<io::Error>> {
+        let width = text.chars().count() as f64 * style.size() * COURIER_WIDTH_EM;
+        Ok((width.round() as u32, style.size().round() as u32))
+    }
+}
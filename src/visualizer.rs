@@ -1,14 +1,722 @@
-use crate::weight_and_balance::{Airplane, Mass, Volume};
+use crate::error::WeightBalanceError;
+use crate::feasibility::FeasibilityMatrix;
+use crate::locale::{Header, Locale};
+use crate::performance::{self, ClimbPerformance, Conditions, DistancePerformance};
+#[cfg(feature = "json")]
+use crate::profile::ProfileError;
+use crate::report::escaped;
+use crate::svg::{Markup, SvgBackend, SvgOptions};
+use crate::trend::{TrendStatistics, HEAVY_MARGIN};
+use crate::vector::{VectorBackend, VectorFormat};
+use crate::weight_and_balance::{
+    Airplane, CenterOfGravity, LimitKind, Limits, Mass, MassMoment, Moment, Volume,
+    WeightBalanceSummary,
+};
 use core::ops::Range;
-use plotters::{prelude::*, style::full_palette::{GREY, PURPLE}};
+use plotters::{
+    coord::{
+        ranged1d::{DefaultFormatting, KeyPointHint},
+        types::RangedCoordf64,
+        Shift,
+    },
+    element::Drawable,
+    prelude::*,
+    style::{
+        full_palette::{GREY, PURPLE},
+        text_anchor::{HPos, Pos, VPos},
+    },
+};
+use plotters_backend::{BackendColor, BackendCoord, DrawingErrorKind};
+use std::{fmt, io, sync::Arc};
 
 pub enum Visualization {
     Svg(String),
 }
 
+#[deprecated(note = "use WeightBalanceError")]
+pub type VisualizationError = WeightBalanceError;
+
+fn drawing<E: fmt::Display>(what: &'static str) -> impl FnOnce(E) -> WeightBalanceError {
+    move |e| WeightBalanceError::Drawing {
+        what,
+        reason: e.to_string(),
+    }
+}
+
+// More ticks than this fall back to the automatic ones
+const MAX_TICKS: f64 = 1000.0;
+
+// The `<metadata>` element with the JSON of an embedded summary, closed by `SUMMARY_END`
+const SUMMARY_START: &str = "<metadata id=\"weight-and-balance-summary\"><![CDATA[";
+const SUMMARY_END: &str = "]]></metadata>";
+
+// The average width of a character relative to the font size when text is not measured
+const CHAR_WIDTH_EM: f64 = 0.6;
+
+// The width of `text` in characters when it is not measured, the wide characters of Chinese,
+// Japanese and Korean counting twice
+fn text_columns(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x1100..=0x115F
+            | 0x2E80..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x20000..=0x3FFFD => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+// The SVG backend, measuring text by its number of characters instead of with the installed
+// fonts when the text metrics are fixed, or the PDF and EPS backend
+struct Backend<'a> {
+    target: Target<'a>,
+    fixed_text_metrics: bool,
+}
+
+enum Target<'a> {
+    Svg(SvgBackend<'a>),
+    Vector(VectorBackend<'a>),
+}
+
+// Calls `$method` on the backend of the target
+macro_rules! delegate {
+    ($backend:expr, $method:ident($($argument:expr),*)) => {
+        match &mut $backend.target {
+            Target::Svg(svg) => svg.$method($($argument),*),
+            Target::Vector(vector) => vector.$method($($argument),*),
+        }
+    };
+}
+
+impl<'a> Backend<'a> {
+    fn svg(
+        out: &'a mut dyn fmt::Write,
+        dimensions: (u32, u32),
+        options: &SvgOptions<'_>,
+    ) -> Backend<'a> {
+        Backend {
+            target: Target::Svg(SvgBackend::new(out, dimensions, options)),
+            fixed_text_metrics: options.deterministic,
+        }
+    }
+
+    // Text is always measured with fixed metrics in a PDF or EPS, which has no installed fonts
+    fn vector(
+        out: &'a mut dyn fmt::Write,
+        dimensions: (u32, u32),
+        dpi: Option<f64>,
+        title: String,
+        format: VectorFormat,
+    ) -> Backend<'a> {
+        Backend {
+            target: Target::Vector(VectorBackend::new(out, dimensions, dpi, title, format)),
+            fixed_text_metrics: true,
+        }
+    }
+
+    // A PDF or EPS has no groups and cells
+    fn markup(&mut self, markup: &Markup<'_>) -> Result<(), DrawingErrorKind<io::Error>> {
+        match &mut self.target {
+            Target::Svg(svg) => svg.markup(markup),
+            Target::Vector(_) => Ok(()),
+        }
+    }
+}
+
+impl Drawable<Backend<'_>> for Markup<'_> {
+    fn draw<I: Iterator<Item = BackendCoord>>(
+        &self,
+        _: I,
+        backend: &mut Backend<'_>,
+        _: (u32, u32),
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        backend.markup(self)
+    }
+}
+
+impl DrawingBackend for Backend<'_> {
+    type ErrorType = io::Error;
+
+    fn get_size(&self) -> (u32, u32) {
+        match &self.target {
+            Target::Svg(svg) => svg.get_size(),
+            Target::Vector(vector) => vector.get_size(),
+        }
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<io::Error>> {
+        delegate!(self, ensure_prepared())
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<io::Error>> {
+        delegate!(self, present())
+    }
+
+    fn draw_pixel(
+        &mut self,
+        point: BackendCoord,
+        color: BackendColor,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        delegate!(self, draw_pixel(point, color))
+    }
+
+    fn draw_line<S: plotters_backend::BackendStyle>(
+        &mut self,
+        from: BackendCoord,
+        to: BackendCoord,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        delegate!(self, draw_line(from, to, style))
+    }
+
+    fn draw_rect<S: plotters_backend::BackendStyle>(
+        &mut self,
+        upper_left: BackendCoord,
+        bottom_right: BackendCoord,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        delegate!(self, draw_rect(upper_left, bottom_right, style, fill))
+    }
+
+    fn draw_path<S: plotters_backend::BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        path: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        delegate!(self, draw_path(path, style))
+    }
+
+    fn draw_circle<S: plotters_backend::BackendStyle>(
+        &mut self,
+        center: BackendCoord,
+        radius: u32,
+        style: &S,
+        fill: bool,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        delegate!(self, draw_circle(center, radius, style, fill))
+    }
+
+    fn fill_polygon<S: plotters_backend::BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        vert: I,
+        style: &S,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        delegate!(self, fill_polygon(vert, style))
+    }
+
+    fn draw_text<S: plotters_backend::BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &S,
+        pos: BackendCoord,
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        delegate!(self, draw_text(text, style, pos))
+    }
+
+    fn estimate_text_size<S: plotters_backend::BackendTextStyle>(
+        &self,
+        text: &str,
+        style: &S,
+    ) -> Result<(u32, u32), DrawingErrorKind<io::Error>> {
+        match &self.target {
+            Target::Svg(svg) if !self.fixed_text_metrics => svg.estimate_text_size(text, style),
+            _ => {
+                let width = text_columns(text) as f64 * style.size() * CHAR_WIDTH_EM;
+                Ok((width.round() as u32, style.size().round() as u32))
+            }
+        }
+    }
+
+    fn blit_bitmap(
+        &mut self,
+        pos: BackendCoord,
+        size: (u32, u32),
+        src: &[u8],
+    ) -> Result<(), DrawingErrorKind<io::Error>> {
+        delegate!(self, blit_bitmap(pos, size, src))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum LegendMark {
+    Area(RGBAColor),
+    Line(RGBAColor),
+    Point(RGBAColor),
+}
+
+const LEGEND_FONT_SIZE: f64 = 12.0;
+const LEGEND_LINE_HEIGHT: i32 = 15;
+const LEGEND_MARK_WIDTH: i32 = 30;
+
+// The legend in the middle right of `area` like the one of plotters, which measures the labels
+// with the installed fonts, laid out with fixed text metrics instead
+fn draw_legend<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    entries: &[(&str, LegendMark)],
+    margin: i32,
+    family: &str,
+) -> Result<(), WeightBalanceError> {
+    let characters = entries.iter().map(|(label, _)| text_columns(label)).max();
+    let text_width = characters.unwrap_or(0) as f64 * LEGEND_FONT_SIZE * CHAR_WIDTH_EM;
+    let (width, height) = (
+        text_width.round() as i32 + LEGEND_MARK_WIDTH + 2 * margin,
+        entries.len() as i32 * LEGEND_LINE_HEIGHT + 2 * margin,
+    );
+    let (area_width, area_height) = area.dim_in_pixel();
+    let (x, y) = (
+        area_width as i32 - width - 5,
+        (area_height as i32 - height) / 2,
+    );
+
+    let corners = [(x, y), (x + width, y + height)];
+    area.draw(&Rectangle::new(corners, WHITE.mix(0.8).filled()))
+        .map_err(drawing("draw legend"))?;
+    area.draw(&Rectangle::new(corners, BLACK))
+        .map_err(drawing("draw legend"))?;
+
+    let style = TextStyle::from((family, LEGEND_FONT_SIZE).into_font())
+        .color(&BLACK)
+        .pos(Pos::new(HPos::Left, VPos::Center));
+    for (i, (label, mark)) in entries.iter().enumerate() {
+        let y = y + margin + i as i32 * LEGEND_LINE_HEIGHT + LEGEND_LINE_HEIGHT / 2;
+        let x = x + margin + LEGEND_MARK_WIDTH / 2;
+        match mark {
+            LegendMark::Area(color) => area.draw(&Rectangle::new(
+                [(x - 5, y - 5), (x + 5, y + 5)],
+                color.filled(),
+            )),
+            LegendMark::Line(color) => {
+                area.draw(&PathElement::new(vec![(x - 10, y), (x + 10, y)], *color))
+            }
+            LegendMark::Point(color) => area.draw(&Circle::new((x, y), 5, color.filled())),
+        }
+        .map_err(drawing("draw legend"))?;
+        area.draw_text(label, &style, (x + LEGEND_MARK_WIDTH / 2, y))
+            .map_err(drawing("draw legend"))?;
+    }
+
+    Ok(())
+}
+
+/// The base direction of the texts of an SVG, for registrations, names and labels in scripts
+/// written right to left.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum TextDirection {
+    /// Left to right, as the labels in English.
+    #[default]
+    Ltr,
+    /// Right to left, e.g. for labels in Arabic or Hebrew.
+    Rtl,
+    /// By the first letter of every text, so Latin registrations stay left to right among
+    /// Hebrew names.
+    Auto,
+}
+
+// The title, description and groups of an SVG for assistive technologies, whether its output
+// is deterministic and the metadata to embed
+struct SvgDocument {
+    role: &'static str,
+    title: String,
+    description: String,
+    deterministic: bool,
+    metadata: Option<String>,
+    cell_titles: bool,
+    dpi: Option<f64>,
+    vector: Option<VectorFormat>,
+    font_family: Option<String>,
+    text_direction: TextDirection,
+}
+
+impl SvgDocument {
+    fn new(role: &'static str, title: String, description: String) -> SvgDocument {
+        SvgDocument {
+            role,
+            title,
+            description,
+            deterministic: false,
+            metadata: None,
+            cell_titles: false,
+            dpi: None,
+            vector: None,
+            font_family: None,
+            text_direction: TextDirection::Ltr,
+        }
+    }
+
+    fn deterministic(mut self, deterministic: bool) -> SvgDocument {
+        self.deterministic = deterministic;
+        self
+    }
+
+    // Embeds `summary` as JSON when `embed` is set
+    #[cfg(feature = "json")]
+    fn embed_summary(
+        &mut self,
+        summary: &WeightBalanceSummary,
+        embed: bool,
+    ) -> Result<(), WeightBalanceError> {
+        if embed {
+            let json = summary.to_json().map_err(drawing("embed summary"))?;
+            self.metadata = Some(format!(
+                "{}{}{}",
+                SUMMARY_START,
+                json.replace("]]>", "]]]]><![CDATA[>"),
+                SUMMARY_END
+            ));
+        }
+        Ok(())
+    }
+
+    // The size of the SVG in millimeters at `dpi` instead of in pixels when set
+    fn dpi(mut self, dpi: Option<f64>) -> SvgDocument {
+        self.dpi = dpi;
+        self
+    }
+
+    // A PDF or EPS instead of an SVG, with the title but without the groups, cells and metadata
+    fn vector(mut self, format: Option<VectorFormat>) -> SvgDocument {
+        self.vector = format;
+        self
+    }
+
+    fn font_family(mut self, family: Option<String>) -> SvgDocument {
+        self.font_family = family;
+        self
+    }
+
+    // The direction of the texts of the SVG, a PDF or EPS is drawn left to right
+    fn text_direction(mut self, direction: TextDirection) -> SvgDocument {
+        self.text_direction = direction;
+        self
+    }
+
+    // The font family of the texts, `default` when none was given
+    fn family(&self, default: &str) -> String {
+        self.font_family
+            .clone()
+            .unwrap_or_else(|| default.to_string())
+    }
+
+    fn cell_titles(mut self, cell_titles: bool) -> SvgDocument {
+        self.cell_titles = cell_titles;
+        self
+    }
+
+    // The value of the text drawn next on `area`, wrapping it in a group with the value as its
+    // title when the cells have titles
+    fn cell(
+        &self,
+        area: &DrawingArea<Backend<'_>, Shift>,
+        value: CellValue,
+    ) -> Result<(), WeightBalanceError> {
+        match value {
+            Some((value, unit)) if self.cell_titles => area
+                .draw(&Markup::Cell { value, unit })
+                .map_err(drawing("draw cell")),
+            _ => Ok(()),
+        }
+    }
+
+    fn backend<'a>(&self, out: &'a mut dyn fmt::Write, dimensions: (u32, u32)) -> Backend<'a> {
+        match self.vector {
+            Some(format) => Backend::vector(out, dimensions, self.dpi, self.title.clone(), format),
+            None => Backend::svg(
+                out,
+                dimensions,
+                &SvgOptions {
+                    role: self.role,
+                    title: &self.title,
+                    description: &self.description,
+                    metadata: self.metadata.as_deref(),
+                    dpi: self.dpi,
+                    deterministic: self.deterministic,
+                    text_direction: self.text_direction,
+                },
+            ),
+        }
+    }
+
+    // Groups the elements drawn next on `area` until the next group
+    fn group(
+        &self,
+        area: &DrawingArea<Backend<'_>, Shift>,
+        role: &'static str,
+        label: &str,
+    ) -> Result<(), WeightBalanceError> {
+        area.draw(&Markup::Group { role, label })
+            .map_err(drawing("start group"))
+    }
+}
+
+/// The unit and ticks of a chart axis. The unit is only the label, the values are in the
+/// [`ChartUnits`] of the chart. Defaults to the ticks plotters chooses, labelled as whole numbers.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChartAxis {
+    unit: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tick_interval: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    unit_on_ticks: bool,
+}
+
+impl ChartAxis {
+    pub fn new(unit: String) -> ChartAxis {
+        ChartAxis {
+            unit,
+            tick_interval: None,
+            unit_on_ticks: false,
+        }
+    }
+
+    fn mass_moment() -> ChartAxis {
+        ChartAxis::new("kg m".to_string())
+    }
+
+    fn mass() -> ChartAxis {
+        ChartAxis::new("kg".to_string())
+    }
+
+    /// A tick at every multiple of `interval`, e.g. every 25 kg. Intervals that are not positive
+    /// or give more than a thousand ticks are ignored.
+    pub fn with_tick_interval(mut self, interval: f64) -> ChartAxis {
+        self.tick_interval = Some(interval);
+        self
+    }
+
+    /// The unit after every tick label as well, e.g. `700 kg`.
+    pub fn with_unit_on_ticks(mut self, unit_on_ticks: bool) -> ChartAxis {
+        self.unit_on_ticks = unit_on_ticks;
+        self
+    }
+
+    pub fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    pub fn tick_interval(&self) -> Option<f64> {
+        self.tick_interval
+    }
+
+    pub fn unit_on_ticks(&self) -> bool {
+        self.unit_on_ticks
+    }
+
+    fn interval(&self, range: &Range<f64>) -> Option<f64> {
+        self.tick_interval
+            .filter(|i| *i > 0.0 && (range.end - range.start) / i <= MAX_TICKS)
+    }
+
+    fn ticks(&self, range: Range<f64>) -> Ticks {
+        Ticks {
+            interval: self.interval(&range),
+            coord: range.into(),
+        }
+    }
+
+    fn label(&self, range: &Range<f64>, value: f64) -> String {
+        let decimals = match self.interval(range) {
+            Some(interval) => (0..3)
+                .find(|d| (interval * 10f64.powi(*d)).fract().abs() < 1e-9)
+                .unwrap_or(3) as usize,
+            None => 0,
+        };
+        let value = match decimals {
+            0 => format!("{}", value.round()),
+            _ => format!("{:.*}", decimals, value),
+        };
+        match self.unit_on_ticks {
+            true => format!("{} {}", value, self.unit),
+            false => value,
+        }
+    }
+}
+
+// The ticks of a chart axis at every multiple of its interval, with light mesh lines at every
+// fifth of it, or the ones plotters chooses without an interval
+struct Ticks {
+    coord: RangedCoordf64,
+    interval: Option<f64>,
+}
+
+impl Ranged for Ticks {
+    type ValueType = f64;
+    type FormatOption = DefaultFormatting;
+
+    fn map(&self, value: &f64, limit: (i32, i32)) -> i32 {
+        self.coord.map(value, limit)
+    }
+
+    fn key_points<Hint: KeyPointHint>(&self, hint: Hint) -> Vec<f64> {
+        let Some(interval) = self.interval else {
+            return self.coord.key_points(hint);
+        };
+        let step = match hint.weight().allow_light_points() {
+            true => interval / 5.0,
+            false => interval,
+        };
+        let range = self.coord.range();
+        let (first, last) = (
+            (range.start / step).ceil() as i64,
+            (range.end / step).floor() as i64,
+        );
+        (first..=last).map(|i| i as f64 * step).collect()
+    }
+
+    fn range(&self) -> Range<f64> {
+        self.coord.range()
+    }
+}
+
+/// The units a chart is drawn in, converted from the kg and m of the airplane.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum ChartUnits {
+    /// The mass moment in kg m and the mass in kg.
+    #[default]
+    Metric,
+    /// The mass moment in lb in and the mass in lb.
+    Imperial,
+}
+
+impl ChartUnits {
+    fn mass_moment(self) -> &'static str {
+        match self {
+            ChartUnits::Metric => "kg m",
+            ChartUnits::Imperial => "lb in",
+        }
+    }
+
+    fn mass(self) -> &'static str {
+        match self {
+            ChartUnits::Metric => "kg",
+            ChartUnits::Imperial => "lb",
+        }
+    }
+
+    // A mass moment in kg m and a mass in kg in these units
+    fn point(self, (moment, mass): (f64, f64)) -> (f64, f64) {
+        match self {
+            ChartUnits::Metric => (moment, mass),
+            ChartUnits::Imperial => (MassMoment::KgM(moment).lb_in(), Mass::Kilo(mass).pound()),
+        }
+    }
+
+    // A mass in kg and a center of gravity in m, e.g. `750 kg / 0.523 m`
+    fn corner_label(self, mass: f64, cg: f64) -> String {
+        match self {
+            ChartUnits::Metric => format!("{:.0} kg / {:.3} m", mass, cg),
+            ChartUnits::Imperial => format!(
+                "{:.0} lb / {:.2} in",
+                Mass::Kilo(mass).pound(),
+                CenterOfGravity::Meter(cg).inch()
+            ),
+        }
+    }
+}
+
+// The font and label area sizes of a chart
+struct ChartLayout {
+    caption: u32,
+    label: u32,
+    annotation: u32,
+    x_label_area: u32,
+    y_label_area: u32,
+    margin_right: u32,
+    mass_moment: &'static str,
+}
+
+const LAYOUT: ChartLayout = ChartLayout {
+    caption: 50,
+    label: 20,
+    annotation: 15,
+    x_label_area: 50,
+    y_label_area: 80,
+    margin_right: 20,
+    mass_moment: "Mass Moment",
+};
+
+const COMPACT_LAYOUT: ChartLayout = ChartLayout {
+    caption: 18,
+    label: 10,
+    annotation: 9,
+    x_label_area: 25,
+    y_label_area: 40,
+    margin_right: 10,
+    mass_moment: "Moment",
+};
+
+/// Defaults to a 1000x1000 metric chart with the axes fitted to the envelope and the airplane.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WeightBalanceChartVisualization {
     dimensions: (u32, u32),
-    axis: (Range<f64>, Range<f64>),
+    axis: Option<(Range<f64>, Range<f64>)>,
+    #[cfg_attr(feature = "serde", serde(default = "ChartAxis::mass_moment"))]
+    x_axis: ChartAxis,
+    #[cfg_attr(feature = "serde", serde(default = "ChartAxis::mass"))]
+    y_axis: ChartAxis,
+    #[cfg_attr(feature = "serde", serde(default))]
+    deterministic: bool,
+    #[cfg(feature = "json")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    embed_summary: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    corner_labels: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    shade_outside: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    units: ChartUnits,
+    #[cfg_attr(feature = "serde", serde(default))]
+    compact: bool,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    dpi: Option<f64>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    font_family: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    text_direction: TextDirection,
+}
+
+impl Default for WeightBalanceChartVisualization {
+    fn default() -> WeightBalanceChartVisualization {
+        WeightBalanceChartVisualization {
+            dimensions: (1000, 1000),
+            axis: None,
+            x_axis: ChartAxis::mass_moment(),
+            y_axis: ChartAxis::mass(),
+            deterministic: false,
+            #[cfg(feature = "json")]
+            embed_summary: false,
+            corner_labels: false,
+            shade_outside: false,
+            units: ChartUnits::Metric,
+            compact: false,
+            dpi: None,
+            font_family: None,
+            text_direction: TextDirection::Ltr,
+        }
+    }
 }
 
 impl WeightBalanceChartVisualization {
@@ -16,379 +724,2571 @@ impl WeightBalanceChartVisualization {
         dimensions: (u32, u32),
         axis: (Range<f64>, Range<f64>),
     ) -> WeightBalanceChartVisualization {
-        WeightBalanceChartVisualization { dimensions, axis }
+        WeightBalanceChartVisualization {
+            dimensions,
+            axis: Some(axis),
+            x_axis: ChartAxis::mass_moment(),
+            y_axis: ChartAxis::mass(),
+            deterministic: false,
+            #[cfg(feature = "json")]
+            embed_summary: false,
+            corner_labels: false,
+            shade_outside: false,
+            units: ChartUnits::Metric,
+            compact: false,
+            dpi: None,
+            font_family: None,
+            text_direction: TextDirection::Ltr,
+        }
+    }
+
+    pub fn with_dimensions(mut self, dimensions: (u32, u32)) -> WeightBalanceChartVisualization {
+        self.dimensions = dimensions;
+        self
+    }
+
+    /// The dimensions in millimeters, drawn at `dpi` pixels per inch. The SVG has its width and
+    /// height in millimeters so it prints at that size.
+    pub fn with_physical_dimensions(
+        mut self,
+        millimeters: (f64, f64),
+        dpi: f64,
+    ) -> WeightBalanceChartVisualization {
+        self.dimensions = pixels(millimeters, dpi);
+        self.dpi = Some(dpi);
+        self
+    }
+
+    /// The font families of the texts instead of `sans-serif`, e.g.
+    /// `Noto Sans, Noto Sans CJK JP, sans-serif` for registrations and labels in scripts the
+    /// default font does not cover.
+    pub fn with_font_family(mut self, family: &str) -> WeightBalanceChartVisualization {
+        self.font_family = Some(family.to_string());
+        self
+    }
+
+    pub fn with_text_direction(
+        mut self,
+        direction: TextDirection,
+    ) -> WeightBalanceChartVisualization {
+        self.text_direction = direction;
+        self
+    }
+
+    /// The mass moment and mass ranges in the units of the chart.
+    pub fn with_axis(mut self, axis: (Range<f64>, Range<f64>)) -> WeightBalanceChartVisualization {
+        self.axis = Some(axis);
+        self
+    }
+
+    /// The mass moment axis.
+    pub fn with_x_axis(mut self, x_axis: ChartAxis) -> WeightBalanceChartVisualization {
+        self.x_axis = x_axis;
+        self
+    }
+
+    /// The mass axis.
+    pub fn with_y_axis(mut self, y_axis: ChartAxis) -> WeightBalanceChartVisualization {
+        self.y_axis = y_axis;
+        self
+    }
+
+    /// Byte-identical output for identical input on every machine: text is measured by its
+    /// number of characters instead of with the installed fonts, and numbers in the SVG are
+    /// rounded to two decimals.
+    pub fn with_deterministic(mut self, deterministic: bool) -> WeightBalanceChartVisualization {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Embed the [`WeightBalanceSummary`] of the airplane as JSON in a `<metadata>` element, to
+    /// be read back with [`embedded_summary`].
+    #[cfg(feature = "json")]
+    pub fn with_embedded_summary(mut self, embed_summary: bool) -> WeightBalanceChartVisualization {
+        self.embed_summary = embed_summary;
+        self
+    }
+
+    /// Label the corners of the envelope with their mass and center of gravity, e.g.
+    /// `750 kg / 0.523 m`.
+    pub fn with_corner_labels(mut self, corner_labels: bool) -> WeightBalanceChartVisualization {
+        self.corner_labels = corner_labels;
+        self
+    }
+
+    /// Shade everything outside the envelope within the axes.
+    pub fn with_shade_outside(mut self, shade_outside: bool) -> WeightBalanceChartVisualization {
+        self.shade_outside = shade_outside;
+        self
+    }
+
+    /// Draws the chart in `units`, setting the units of the axes as well.
+    pub fn with_units(mut self, units: ChartUnits) -> WeightBalanceChartVisualization {
+        self.units = units;
+        self.x_axis.unit = units.mass_moment().to_string();
+        self.y_axis.unit = units.mass().to_string();
+        self
+    }
+
+    /// A 300x300 compact chart to embed in other documents.
+    pub fn compact() -> WeightBalanceChartVisualization {
+        WeightBalanceChartVisualization::default()
+            .with_dimensions((300, 300))
+            .with_compact(true)
+    }
+
+    /// Smaller fonts and label areas, an abbreviated mass moment axis and no legend, for charts
+    /// of around 300x300.
+    pub fn with_compact(mut self, compact: bool) -> WeightBalanceChartVisualization {
+        self.compact = compact;
+        self
     }
 }
 
-pub struct WeightBalanceTableVisualization {
-    dimensions: (u32, u32),
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum TableUnits {
+    Metric,
+    /// Metric columns each followed by their imperial (lb, in) counterpart.
+    Dual,
 }
 
-pub fn weight_and_balance_table_strings(plane: Airplane) -> Vec<Vec<String>> {
-    let mut table = vec![vec![
-        "Name".to_string(),
-        "Lever Arm [m]".to_string(),
-        "Mass [kg]".to_string(),
-        "Mass Moment [kg m]".to_string(),
-    ]];
-
-    for m in plane.moments().iter() {
-        table.push(vec![
-            match m.mass() {
-                Mass::Avgas(_) | Mass::Mogas(_) => format!("{} ({})", m.name(), m.mass().unit()).replace('.', ","),
-                _ => m.name().clone(),
-            },
-            format!("{:.4}", m.lever_arm().meter()).replace('.', ","),
-            match m.mass() {
-                Mass::Avgas(v) | Mass::Mogas(v) => format!("({}) {:.2}", v.to_string(), m.mass().kilo()).replace('.', ","),
-                _ => format!("{:.2}", m.mass().kilo()).replace('.', ","),
-            },
-            format!("{:.2}", m.total().kgm()).replace('.', ","),
-        ])
-    }
-
-    table.push(vec![
-        "Total".to_string(),
-        format!(
-            "{:.4}",
-            plane.total_mass_moment().kgm() / plane.total_mass().kilo()
-        ).replace('.', ","),
-        format!("{:.2}", plane.total_mass().kilo()).replace('.', ","),
-        format!("{:.2}", plane.total_mass_moment().kgm()).replace('.', ","),
-    ]);
+type Cell<T> = Arc<dyn Fn(&T) -> String + Send + Sync>;
 
-    table
+/// A column added after the others in the weight and balance table, computed for every moment,
+/// e.g. from the metadata of the moment.
+#[derive(Clone)]
+pub struct TableColumn {
+    header: String,
+    cell: Cell<Moment>,
+    total: Option<Cell<Airplane>>,
 }
-impl WeightBalanceTableVisualization {
-    pub fn new(dimensions: (u32, u32)) -> WeightBalanceTableVisualization {
-        WeightBalanceTableVisualization { dimensions }
+
+impl TableColumn {
+    pub fn new(
+        header: String,
+        cell: impl Fn(&Moment) -> String + Send + Sync + 'static,
+    ) -> TableColumn {
+        TableColumn {
+            header,
+            cell: Arc::new(cell),
+            total: None,
+        }
     }
-}
-pub fn weight_and_balance_table(
-    plane: Airplane,
-    visualization: WeightBalanceTableVisualization,
-) -> Visualization {
-    let mut rbuf = String::new();
-    {
-        let right = SVGBackend::with_string(
-            &mut rbuf,
-            (visualization.dimensions.0, visualization.dimensions.1),
-        )
-        .into_drawing_area();
 
-        right
-            .fill(&WHITE)
-            .expect("cannot fill background with white.");
+    /// The cell in the totals row, empty unless given.
+    pub fn with_total(
+        mut self,
+        total: impl Fn(&Airplane) -> String + Send + Sync + 'static,
+    ) -> TableColumn {
+        self.total = Some(Arc::new(total));
+        self
+    }
 
-        let font = ("monospace", 20).into_font();
-        let bold_font = ("monospace", 20).into_font().style(FontStyle::Bold);
-        let text_style = TextStyle::from(font).color(&BLACK);
-        let bold_text_style = TextStyle::from(bold_font).color(&BLACK);
+    /// The [`Airplane::cg_sensitivity`] of every moment of `plane` with two decimals, signed so
+    /// loaders see which way every station moves the CG.
+    pub fn cg_sensitivity(plane: &Airplane, locale: &Locale) -> TableColumn {
+        let (plane, locale) = (plane.unobserved(), locale.clone());
+        let header = format!("{} [mm/kg]", locale.labels().center_of_gravity());
+        TableColumn::new(header, move |m: &Moment| {
+            let sensitivity = plane.cg_sensitivity(m.lever_arm());
+            match sensitivity.is_finite() {
+                true if sensitivity > 0.0 => format!("+{}", locale.number(sensitivity, 2)),
+                true => locale.number(sensitivity, 2),
+                false => "-".to_string(),
+            }
+        })
+    }
 
-        let cell_width = [110, 140, 180, 180];
-        let cell_padding = [10, 70, 21, 114];
-        let cell_height = 30;
+    pub fn header(&self) -> &str {
+        &self.header
+    }
+}
 
-        let start_x = 0;
-        let start_y = 0;
+impl fmt::Debug for TableColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TableColumn")
+            .field("header", &self.header)
+            .finish_non_exhaustive()
+    }
+}
 
-        // Function to pad with non-breaking spaces
-        fn pad_with_nbsp(text: &str, total_length: usize) -> String {
-            let nb_spaces = total_length.saturating_sub(text.len());
-            let mut padded = String::new();
-            for _ in 0..nb_spaces {
-                padded.push('\u{00A0}');
+/// Columns are equal when they have the same header and share their functions.
+impl PartialEq for TableColumn {
+    fn eq(&self, other: &TableColumn) -> bool {
+        self.header == other.header
+            && Arc::ptr_eq(&self.cell, &other.cell)
+            && match (&self.total, &other.total) {
+                (Some(total), Some(other)) => Arc::ptr_eq(total, other),
+                (total, other) => total.is_none() && other.is_none(),
             }
-            padded.push_str(text);
-            padded
-        }
+    }
+}
 
-        // Draw header row with grey background and bold text
-        let total_width: i32 = cell_width.iter().sum();
-        right
-            .draw(&Rectangle::new(
-                [
-                    (start_x, start_y),
-                    (start_x + total_width, start_y + cell_height),
-                ],
-                ShapeStyle {
-                    color: GREY.mix(0.5).to_rgba(),
-                    filled: true,
-                    stroke_width: 0,
-                },
-            ))
-            .expect("cannot draw header rectangle");
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellRole {
+    Header,
+    Row,
+    /// The totals row.
+    Footer,
+}
 
-        right
-            .draw_text("Name", &bold_text_style, (start_x + 10, start_y + 10))
-            .expect("cannot draw text");
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellAlignment {
+    Left,
+    Right,
+}
 
-        let mut current_cell_width = start_x + cell_width[0];
-        right
-            .draw_text(
-                "Lever Arm [m]",
-                &bold_text_style,
-                (current_cell_width + 10, start_y + 10),
-            )
-            .expect("cannot draw text");
+/// A cell of [`weight_and_balance_table_cells`]: the text as this crate renders it and, for the
+/// figures, the full precision value with its unit and how it is formatted, so other renderers
+/// can format the figures themselves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableCell {
+    text: String,
+    value: Option<f64>,
+    unit: Option<&'static str>,
+    decimals: usize,
+    volume: Option<Volume>,
+    role: CellRole,
+    alignment: CellAlignment,
+}
 
-        current_cell_width += cell_width[1];
-        right
-            .draw_text(
-                "Mass [kg]",
-                &bold_text_style,
-                (current_cell_width + 80, start_y + 10),
-            )
-            .expect("cannot draw text");
+impl TableCell {
+    /// The localized text, e.g. `"(55,00L) 39,60"` for a fuel mass.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
 
-        current_cell_width += cell_width[2];
-        right
-            .draw_text(
-                "Mass Moment [kg m]",
-                &bold_text_style,
-                (current_cell_width + 10, start_y + 10),
-            )
-            .expect("cannot draw text");
+    /// `None` for the headers, the names, a missing CG and added columns.
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
 
-        // Draw the rest of the table rows
-        for (i, m) in plane.moments().iter().enumerate() {
-            let y = start_y + (i as i32 + 1) * cell_height;
+    /// `"m"`, `"in"`, `"kg"`, `"lb"`, `"kg m"` or `"lb in"`.
+    pub fn unit(&self) -> Option<&'static str> {
+        self.unit
+    }
 
-            right
-                .draw_text(m.name(), &text_style, (start_x + cell_padding[0], y + 10))
-                .expect("cannot draw text");
+    /// The number of decimals of the value in the text.
+    pub fn decimals(&self) -> usize {
+        self.decimals
+    }
 
-            let mut current_cell_width = start_x + cell_width[0];
-            right
-                .draw_text(
-                    &pad_with_nbsp(&format!("{:.4}", m.lever_arm().meter()), 6),
-                    &text_style,
-                    (current_cell_width + cell_padding[1], y + 10),
+    /// The volume of a fuel mass, shown before the mass.
+    pub fn volume(&self) -> Option<&Volume> {
+        self.volume.as_ref()
+    }
+
+    pub fn role(&self) -> CellRole {
+        self.role
+    }
+
+    /// Names are left aligned, the figures right aligned.
+    pub fn alignment(&self) -> CellAlignment {
+        self.alignment
+    }
+}
+
+impl fmt::Display for TableCell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Defaults to an 800x600 metric table in English that grows when it does not fit.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WeightBalanceTableVisualization {
+    dimensions: (u32, u32),
+    units: TableUnits,
+    locale: Locale,
+    auto_grow: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    notes: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    deterministic: bool,
+    #[cfg(feature = "json")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    embed_summary: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    cell_titles: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    takeoff_landing_totals: bool,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    dpi: Option<f64>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    font_family: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    text_direction: TextDirection,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    columns: Vec<TableColumn>,
+}
+
+impl Default for WeightBalanceTableVisualization {
+    fn default() -> WeightBalanceTableVisualization {
+        WeightBalanceTableVisualization::new((800, 600)).with_auto_grow(true)
+    }
+}
+
+impl WeightBalanceTableVisualization {
+    pub fn new(dimensions: (u32, u32)) -> WeightBalanceTableVisualization {
+        WeightBalanceTableVisualization {
+            dimensions,
+            units: TableUnits::Metric,
+            locale: Locale::english(),
+            auto_grow: false,
+            notes: false,
+            deterministic: false,
+            #[cfg(feature = "json")]
+            embed_summary: false,
+            cell_titles: false,
+            takeoff_landing_totals: false,
+            dpi: None,
+            font_family: None,
+            text_direction: TextDirection::Ltr,
+            columns: vec![],
+        }
+    }
+
+    pub fn with_dimensions(mut self, dimensions: (u32, u32)) -> WeightBalanceTableVisualization {
+        self.dimensions = dimensions;
+        self
+    }
+
+    /// The dimensions in millimeters, drawn at `dpi` pixels per inch. The SVG has its width and
+    /// height in millimeters so it prints at that size, also when it grows to fit the table.
+    pub fn with_physical_dimensions(
+        mut self,
+        millimeters: (f64, f64),
+        dpi: f64,
+    ) -> WeightBalanceTableVisualization {
+        self.dimensions = pixels(millimeters, dpi);
+        self.dpi = Some(dpi);
+        self
+    }
+
+    /// The font families of the texts instead of `monospace`, e.g.
+    /// `Noto Sans Mono, Noto Sans Mono CJK JP, monospace` for names and labels in scripts the
+    /// default font does not cover.
+    pub fn with_font_family(mut self, family: &str) -> WeightBalanceTableVisualization {
+        self.font_family = Some(family.to_string());
+        self
+    }
+
+    pub fn with_text_direction(
+        mut self,
+        direction: TextDirection,
+    ) -> WeightBalanceTableVisualization {
+        self.text_direction = direction;
+        self
+    }
+
+    pub fn with_locale(mut self, locale: Locale) -> WeightBalanceTableVisualization {
+        self.locale = locale;
+        self
+    }
+
+    pub fn with_units(mut self, units: TableUnits) -> WeightBalanceTableVisualization {
+        self.units = units;
+        self
+    }
+
+    /// Enlarge the canvas to fit the table instead of returning
+    /// [`WeightBalanceError::TableDoesNotFit`].
+    pub fn with_auto_grow(mut self, auto_grow: bool) -> WeightBalanceTableVisualization {
+        self.auto_grow = auto_grow;
+        self
+    }
+
+    /// Add the note of a moment to its name.
+    pub fn with_notes(mut self, notes: bool) -> WeightBalanceTableVisualization {
+        self.notes = notes;
+        self
+    }
+
+    /// Byte-identical output for identical input on every machine: text is measured by its
+    /// number of characters instead of with the installed fonts, and numbers in the SVG are
+    /// rounded to two decimals.
+    pub fn with_deterministic(mut self, deterministic: bool) -> WeightBalanceTableVisualization {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Embed the [`WeightBalanceSummary`] of the airplane as JSON in a `<metadata>` element of
+    /// every page, to be read back with [`embedded_summary`].
+    #[cfg(feature = "json")]
+    pub fn with_embedded_summary(mut self, embed_summary: bool) -> WeightBalanceTableVisualization {
+        self.embed_summary = embed_summary;
+        self
+    }
+
+    /// Wrap every number in a group with its unrounded value and unit as `data-value` and
+    /// `data-unit` attributes and as its title, shown when hovering over it in a browser. The
+    /// deterministic output rounds the value to two decimals.
+    pub fn with_cell_titles(mut self, cell_titles: bool) -> WeightBalanceTableVisualization {
+        self.cell_titles = cell_titles;
+        self
+    }
+
+    /// Add the totals at take-off and at landing side by side below the table, as in
+    /// [`weight_and_balance_takeoff_landing_strings`], on the last page of a split table.
+    pub fn with_takeoff_landing_totals(
+        mut self,
+        takeoff_landing_totals: bool,
+    ) -> WeightBalanceTableVisualization {
+        self.takeoff_landing_totals = takeoff_landing_totals;
+        self
+    }
+
+    /// Adds `column` after the other columns. Columns are not serialized.
+    pub fn with_column(mut self, column: TableColumn) -> WeightBalanceTableVisualization {
+        self.columns.push(column);
+        self
+    }
+}
+
+/// Defaults to a 600x400 chart.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PerformanceChartVisualization {
+    dimensions: (u32, u32),
+    #[cfg_attr(feature = "serde", serde(default))]
+    deterministic: bool,
+}
+
+impl Default for PerformanceChartVisualization {
+    fn default() -> PerformanceChartVisualization {
+        PerformanceChartVisualization::new((600, 400))
+    }
+}
+
+impl PerformanceChartVisualization {
+    pub fn new(dimensions: (u32, u32)) -> PerformanceChartVisualization {
+        PerformanceChartVisualization {
+            dimensions,
+            deterministic: false,
+        }
+    }
+
+    pub fn with_dimensions(mut self, dimensions: (u32, u32)) -> PerformanceChartVisualization {
+        self.dimensions = dimensions;
+        self
+    }
+
+    /// Byte-identical output for identical input on every machine: text is measured by its
+    /// number of characters instead of with the installed fonts, and numbers in the SVG are
+    /// rounded to two decimals.
+    pub fn with_deterministic(mut self, deterministic: bool) -> PerformanceChartVisualization {
+        self.deterministic = deterministic;
+        self
+    }
+}
+
+struct CellFormat {
+    fuel_density_in_name: bool,
+    volume_decimals: usize,
+}
+
+const STRINGS_FORMAT: CellFormat = CellFormat {
+    fuel_density_in_name: true,
+    volume_decimals: 2,
+};
+
+const SVG_FORMAT: CellFormat = CellFormat {
+    fuel_density_in_name: false,
+    volume_decimals: 1,
+};
+
+// The full precision value and unit of a cell, `None` for the names
+type CellValue = Option<(f64, &'static str)>;
+
+struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+    footer: Vec<String>,
+    row_values: Vec<Vec<CellValue>>,
+    footer_values: Vec<CellValue>,
+    // Drawn below the footer
+    takeoff_landing: Option<Box<Table>>,
+}
+
+fn table(
+    plane: &Airplane,
+    units: TableUnits,
+    locale: &Locale,
+    format: &CellFormat,
+    notes: bool,
+    columns: &[TableColumn],
+) -> Table {
+    let dual = units == TableUnits::Dual;
+    let labels = locale.labels();
+    let number = |value: f64, decimals: usize| locale.number(value, decimals);
+    let volume_unit = |volume: &Volume| match volume {
+        Volume::Liter(_) => labels.liter(),
+        Volume::Gallon(_) => labels.gallon(),
+    };
+
+    let mut header = vec![labels.header(Header::Name), labels.header(Header::LeverArm)];
+    if dual {
+        header.push(labels.header(Header::LeverArmInch));
+    }
+    header.push(labels.header(Header::Mass));
+    if dual {
+        header.push(labels.header(Header::MassPound));
+    }
+    header.push(labels.header(Header::MassMoment));
+    if dual {
+        header.push(labels.header(Header::MassMomentPoundInch));
+    }
+    header.extend(columns.iter().map(|c| c.header.clone()));
+
+    let mut rows = vec![];
+    let mut row_values = vec![];
+    for m in plane.iter() {
+        let name = match (m.mass(), m.mass().density()) {
+            (Mass::Avgas(v) | Mass::Mogas(v), Some(density)) if format.fuel_density_in_name => {
+                format!("{} ({}kg/{})", m.name(), number(density, 2), volume_unit(v))
+            }
+            _ => m.name().to_string(),
+        };
+        let name = match m.note() {
+            Some(note) if notes => format!("{} - {}", name, note),
+            _ => name,
+        };
+        let mass = match m.mass() {
+            Mass::Avgas(v) | Mass::Mogas(v) => {
+                let volume = match v {
+                    Volume::Liter(l) => *l,
+                    Volume::Gallon(g) => *g,
+                };
+                format!(
+                    "({}{}) {}",
+                    number(volume, format.volume_decimals),
+                    volume_unit(v),
+                    number(m.mass().kilo(), 2)
+                )
+            }
+            Mass::Kilo(_) => number(m.mass().kilo(), 2),
+        };
+
+        let mut row = vec![name, number(m.lever_arm().meter(), 4)];
+        let mut values = vec![None, Some((m.lever_arm().meter(), "m"))];
+        if dual {
+            row.push(number(m.lever_arm().inch(), 2));
+            values.push(Some((m.lever_arm().inch(), "in")));
+        }
+        row.push(mass);
+        values.push(Some((m.mass().kilo(), "kg")));
+        if dual {
+            row.push(number(m.mass().pound(), 2));
+            values.push(Some((m.mass().pound(), "lb")));
+        }
+        row.push(number(m.total().kgm(), 2));
+        values.push(Some((m.total().kgm(), "kg m")));
+        if dual {
+            row.push(number(m.total().lb_in(), 2));
+            values.push(Some((m.total().lb_in(), "lb in")));
+        }
+        row.extend(columns.iter().map(|c| (c.cell)(m)));
+        rows.push(row);
+        row_values.push(values);
+    }
+
+    let total_mass = plane.total_mass();
+    let total_moment = plane.total_mass_moment();
+    // Without mass there is no CG, a dash instead
+    let cg = |moment: f64, mass: f64, decimals: usize, unit: &'static str| {
+        let cg = moment / mass;
+        match cg.is_finite() {
+            true => (number(cg, decimals), Some((cg, unit))),
+            false => ("-".to_string(), None),
+        }
+    };
+    let (text, value) = cg(total_moment.kgm(), total_mass.kilo(), 4, "m");
+    let mut footer = vec![labels.total().to_string(), text];
+    let mut footer_values = vec![None, value];
+    if dual {
+        let (text, value) = cg(total_moment.lb_in(), total_mass.pound(), 2, "in");
+        footer.push(text);
+        footer_values.push(value);
+    }
+    footer.push(number(total_mass.kilo(), 2));
+    footer_values.push(Some((total_mass.kilo(), "kg")));
+    if dual {
+        footer.push(number(total_mass.pound(), 2));
+        footer_values.push(Some((total_mass.pound(), "lb")));
+    }
+    footer.push(number(total_moment.kgm(), 2));
+    footer_values.push(Some((total_moment.kgm(), "kg m")));
+    if dual {
+        footer.push(number(total_moment.lb_in(), 2));
+        footer_values.push(Some((total_moment.lb_in(), "lb in")));
+    }
+    footer.extend(columns.iter().map(|c| match &c.total {
+        Some(total) => total(plane),
+        None => String::new(),
+    }));
+
+    Table {
+        header,
+        rows,
+        footer,
+        row_values,
+        footer_values,
+        takeoff_landing: None,
+    }
+}
+
+// A row of the take-off and landing table, in the unit it is shown in
+#[derive(Clone, Copy)]
+enum Figure {
+    Kilo,
+    Pound,
+    KiloMeter,
+    PoundInch,
+    Meter,
+    Inch,
+}
+
+impl Figure {
+    fn unit(self) -> &'static str {
+        match self {
+            Figure::Kilo => "kg",
+            Figure::Pound => "lb",
+            Figure::KiloMeter => "kg m",
+            Figure::PoundInch => "lb in",
+            Figure::Meter => "m",
+            Figure::Inch => "in",
+        }
+    }
+
+    // The figure of a mass and its moment
+    fn value(self, mass: &Mass, moment: &MassMoment) -> f64 {
+        match self {
+            Figure::Kilo => mass.kilo(),
+            Figure::Pound => mass.pound(),
+            Figure::KiloMeter => moment.kgm(),
+            Figure::PoundInch => moment.lb_in(),
+            Figure::Meter => moment.kgm() / mass.kilo(),
+            Figure::Inch => moment.lb_in() / mass.pound(),
+        }
+    }
+}
+
+// The mass, mass moment and CG at take-off and at landing, without a footer
+fn takeoff_landing_table(plane: &Airplane, units: TableUnits, locale: &Locale) -> Table {
+    let labels = locale.labels();
+    let cg = labels.center_of_gravity();
+    let figures = match units {
+        TableUnits::Metric => vec![
+            (labels.header(Header::Mass), Figure::Kilo, 2),
+            (labels.header(Header::MassMoment), Figure::KiloMeter, 2),
+            (format!("{} [m]", cg), Figure::Meter, 4),
+        ],
+        TableUnits::Dual => vec![
+            (labels.header(Header::Mass), Figure::Kilo, 2),
+            (labels.header(Header::MassPound), Figure::Pound, 2),
+            (labels.header(Header::MassMoment), Figure::KiloMeter, 2),
+            (
+                labels.header(Header::MassMomentPoundInch),
+                Figure::PoundInch,
+                2,
+            ),
+            (format!("{} [m]", cg), Figure::Meter, 4),
+            (format!("{} [in]", cg), Figure::Inch, 2),
+        ],
+    };
+
+    let takeoff = Some((plane.total_mass(), plane.total_mass_moment()));
+    let landing = plane
+        .total_mass_landing()
+        .ok()
+        .zip(plane.total_mass_moment_landing().ok());
+
+    let mut rows = vec![];
+    let mut row_values = vec![];
+    for (label, figure, decimals) in figures {
+        let mut row = vec![label];
+        let mut values = vec![None];
+        for condition in [&takeoff, &landing] {
+            // Without mass there is no CG and without fuel no landing figures, a dash instead
+            match condition.as_ref().map(|(m, mm)| figure.value(m, mm)) {
+                Some(value) if value.is_finite() => {
+                    row.push(locale.number(value, decimals));
+                    values.push(Some((value, figure.unit())));
+                }
+                _ => {
+                    row.push("-".to_string());
+                    values.push(None);
+                }
+            }
+        }
+        rows.push(row);
+        row_values.push(values);
+    }
+
+    Table {
+        header: vec![
+            labels.total().to_string(),
+            labels.takeoff().to_string(),
+            labels.landing().to_string(),
+        ],
+        rows,
+        footer: vec![],
+        row_values,
+        footer_values: vec![],
+        takeoff_landing: None,
+    }
+}
+
+/// English labels with a decimal comma, as this function has always produced.
+pub fn weight_and_balance_table_strings(plane: &Airplane) -> Vec<Vec<String>> {
+    weight_and_balance_table_strings_with_units(plane, TableUnits::Metric)
+}
+
+pub fn weight_and_balance_table_strings_with_units(
+    plane: &Airplane,
+    units: TableUnits,
+) -> Vec<Vec<String>> {
+    weight_and_balance_table_strings_localized(
+        plane,
+        units,
+        &Locale::english().with_decimal_separator(','),
+    )
+}
+
+pub fn weight_and_balance_table_strings_localized(
+    plane: &Airplane,
+    units: TableUnits,
+    locale: &Locale,
+) -> Vec<Vec<String>> {
+    weight_and_balance_table_strings_with_columns(plane, units, locale, &[])
+}
+
+/// Like [`weight_and_balance_table_strings_localized`] with `columns` after the other columns.
+pub fn weight_and_balance_table_strings_with_columns(
+    plane: &Airplane,
+    units: TableUnits,
+    locale: &Locale,
+    columns: &[TableColumn],
+) -> Vec<Vec<String>> {
+    weight_and_balance_table_cells(plane, units, locale, columns)
+        .into_iter()
+        .map(|row| row.into_iter().map(|cell| cell.text).collect())
+        .collect()
+}
+
+/// The header, a row for every moment and the totals row as typed cells, with the same texts as
+/// [`weight_and_balance_table_strings_with_columns`].
+pub fn weight_and_balance_table_cells(
+    plane: &Airplane,
+    units: TableUnits,
+    locale: &Locale,
+    columns: &[TableColumn],
+) -> Vec<Vec<TableCell>> {
+    let Table {
+        header,
+        rows,
+        footer,
+        row_values,
+        footer_values,
+        ..
+    } = table(plane, units, locale, &STRINGS_FORMAT, false, columns);
+    let row_cells = |texts: Vec<String>, values: &[CellValue], volume: Option<&Volume>, role| {
+        texts
+            .into_iter()
+            .enumerate()
+            .map(|(j, text)| {
+                let value = values.get(j).copied().flatten();
+                TableCell {
+                    text,
+                    value: value.map(|(value, _)| value),
+                    unit: value.map(|(_, unit)| unit),
+                    // Lever arms and CGs in meters have four decimals, the other figures two
+                    decimals: match value {
+                        Some((_, "m")) => 4,
+                        Some(_) => 2,
+                        None => 0,
+                    },
+                    volume: volume.filter(|_| matches!(value, Some((_, "kg")))).cloned(),
+                    role,
+                    alignment: match j {
+                        0 => CellAlignment::Left,
+                        _ => CellAlignment::Right,
+                    },
+                }
+            })
+            .collect()
+    };
+
+    let mut cells = vec![row_cells(header, &[], None, CellRole::Header)];
+    for ((row, values), m) in rows.into_iter().zip(&row_values).zip(plane.iter()) {
+        cells.push(row_cells(row, values, m.mass().volume(), CellRole::Row));
+    }
+    cells.push(row_cells(footer, &footer_values, None, CellRole::Footer));
+    cells
+}
+
+/// The mass, mass moment and CG at take-off and at landing side by side as on a load sheet, a
+/// header followed by a row for every figure. A dash where a figure cannot be computed, e.g. the
+/// landing figures without fuel.
+pub fn weight_and_balance_takeoff_landing_strings(
+    plane: &Airplane,
+    units: TableUnits,
+    locale: &Locale,
+) -> Vec<Vec<String>> {
+    let table = takeoff_landing_table(plane, units, locale);
+
+    let mut strings = vec![table.header];
+    strings.extend(table.rows);
+    strings
+}
+
+// The font renders at ~16px, monospace glyphs are 0.6 em wide
+const CHAR_WIDTH: i32 = 10;
+const CELL_PADDING: i32 = 10;
+const CELL_HEIGHT: i32 = 30;
+
+fn column_widths(table: &Table) -> Vec<i32> {
+    (0..table.header.len())
+        .map(|j| {
+            std::iter::once(&table.header)
+                .chain(table.rows.iter())
+                .chain(std::iter::once(&table.footer))
+                .filter_map(|row| row.get(j))
+                .map(|text| text_columns(text) as i32)
+                .max()
+                .unwrap_or(0)
+                * CHAR_WIDTH
+                + 2 * CELL_PADDING
+        })
+        .collect()
+}
+
+// The header, the rows in `rows` and the footer when `footer` is set
+fn draw_table_page(
+    buf: &mut dyn fmt::Write,
+    dimensions: (u32, u32),
+    cell_width: &[i32],
+    table: &Table,
+    rows: Range<usize>,
+    footer: bool,
+    document: SvgDocument,
+) -> Result<(), WeightBalanceError> {
+    let header = &table.header;
+    let values = &table.row_values[rows.clone()];
+    let rows = &table.rows[rows];
+    let footer = footer.then_some(&table.footer);
+
+    {
+        let right = document.backend(buf, dimensions).into_drawing_area();
+
+        right
+            .fill(&WHITE)
+            .map_err(drawing("fill background with white"))?;
+
+        let family = document.family("monospace");
+        let font = (family.as_str(), 20).into_font();
+        let bold_font = (family.as_str(), 20).into_font().style(FontStyle::Bold);
+        let text_style = TextStyle::from(font).color(&BLACK);
+        let bold_text_style = TextStyle::from(bold_font).color(&BLACK);
+
+        let start_x = 0;
+        let start_y = 0;
+        let total_width: i32 = cell_width.iter().sum();
+        let row_count = rows.len() + 1 + footer.map_or(0, |_| 1);
+
+        // The name column is left aligned, the numbers are right aligned
+        let draw_row =
+            |widths: &[i32], row: &[String], values: &[CellValue], y: i32, style: &TextStyle| {
+                let mut x = start_x;
+                for (j, text) in row.iter().enumerate() {
+                    document.cell(&right, values.get(j).copied().flatten())?;
+                    if j == 0 {
+                        right
+                            .draw_text(text, style, (x + CELL_PADDING, y + 10))
+                            .map_err(drawing("draw text"))?;
+                    } else {
+                        right
+                            .draw_text(
+                                text,
+                                &style.pos(Pos::new(HPos::Right, VPos::Top)),
+                                (x + widths[j] - CELL_PADDING, y + 10),
+                            )
+                            .map_err(drawing("draw text"))?;
+                    }
+                    x += widths[j];
+                }
+                Ok::<(), WeightBalanceError>(())
+            };
+
+        // The grey background of a header or footer row
+        let draw_band = |y: i32, width: i32, what: &'static str| {
+            right
+                .draw(&Rectangle::new(
+                    [(start_x, y), (start_x + width, y + CELL_HEIGHT)],
+                    ShapeStyle {
+                        color: GREY.mix(0.5).to_rgba(),
+                        filled: true,
+                        stroke_width: 0,
+                    },
+                ))
+                .map_err(drawing(what))
+        };
+
+        // The horizontal and then the vertical lines of `rows` rows from `y`
+        let draw_grid = |y: i32, rows: usize, widths: &[i32]| {
+            let width: i32 = widths.iter().sum();
+            for i in 0..=rows {
+                let y = y + i as i32 * CELL_HEIGHT;
+                right
+                    .draw(&PathElement::new(
+                        vec![(start_x, y), (start_x + width, y)],
+                        BLACK,
+                    ))
+                    .map_err(drawing("draw lines"))?;
+            }
+
+            let mut x = start_x;
+            for width in std::iter::once(&0).chain(widths) {
+                x += width;
+                right
+                    .draw(&PathElement::new(
+                        vec![(x, y), (x, y + CELL_HEIGHT * rows as i32)],
+                        BLACK,
+                    ))
+                    .map_err(drawing("draw lines"))?;
+            }
+            Ok::<(), WeightBalanceError>(())
+        };
+
+        // Draw header row with grey background and bold text
+        document.group(&right, "row", &header[0])?;
+        draw_band(start_y, total_width, "draw header rectangle")?;
+        draw_row(cell_width, header, &[], start_y, &bold_text_style)?;
+
+        // Draw the rest of the table rows
+        for (i, (row, values)) in rows.iter().zip(values).enumerate() {
+            document.group(&right, "row", &row[0])?;
+            draw_row(
+                cell_width,
+                row,
+                values,
+                start_y + (i as i32 + 1) * CELL_HEIGHT,
+                &text_style,
+            )?;
+        }
+
+        // Draw footer row with grey background and bold text
+        if let Some(footer) = footer {
+            let y = start_y + (rows.len() + 1) as i32 * CELL_HEIGHT;
+
+            document.group(&right, "row", &footer[0])?;
+            draw_band(y, total_width, "draw footer rectangle")?;
+            draw_row(
+                cell_width,
+                footer,
+                &table.footer_values,
+                y,
+                &bold_text_style,
+            )?;
+        }
+
+        // Draw the lines of the table
+        document.group(&right, "presentation", "Grid")?;
+        draw_grid(start_y, row_count, cell_width)?;
+
+        // The take-off and landing totals below the footer, a row apart
+        if let (Some(totals), Some(_)) = (&table.takeoff_landing, footer) {
+            let widths = column_widths(totals);
+            let y = start_y + (row_count + 1) as i32 * CELL_HEIGHT;
+
+            document.group(&right, "row", &totals.header[0])?;
+            draw_band(y, widths.iter().sum(), "draw header rectangle")?;
+            draw_row(&widths, &totals.header, &[], y, &bold_text_style)?;
+            for (i, (row, values)) in totals.rows.iter().zip(&totals.row_values).enumerate() {
+                document.group(&right, "row", &row[0])?;
+                draw_row(
+                    &widths,
+                    row,
+                    values,
+                    y + (i as i32 + 1) * CELL_HEIGHT,
+                    &text_style,
+                )?;
+            }
+
+            document.group(&right, "presentation", "Grid")?;
+            draw_grid(y, totals.rows.len() + 1, &widths)?;
+        }
+
+        right.present().map_err(drawing("write to buffer"))?;
+    }
+
+    Ok(())
+}
+
+/// Grows `dimensions` to `required` when allowed, otherwise fails if the table does not fit.
+fn fit_dimensions(
+    required: (u32, u32),
+    visualization: &WeightBalanceTableVisualization,
+) -> Result<(u32, u32), WeightBalanceError> {
+    let available = visualization.dimensions;
+
+    if available.0 >= required.0 && available.1 >= required.1 {
+        Ok(available)
+    } else if visualization.auto_grow {
+        Ok((available.0.max(required.0), available.1.max(required.1)))
+    } else {
+        Err(WeightBalanceError::TableDoesNotFit {
+            required,
+            available,
+        })
+    }
+}
+
+fn table_to_draw(plane: &Airplane, visualization: &WeightBalanceTableVisualization) -> Table {
+    let mut table = table(
+        plane,
+        visualization.units,
+        &visualization.locale,
+        &SVG_FORMAT,
+        visualization.notes,
+        &visualization.columns,
+    );
+    if visualization.takeoff_landing_totals {
+        table.takeoff_landing = Some(Box::new(takeoff_landing_table(
+            plane,
+            visualization.units,
+            &visualization.locale,
+        )));
+    }
+    table
+}
+
+// The width of the take-off and landing totals and the rows they take, a row apart included
+fn takeoff_landing_extent(table: &Table) -> (i32, usize) {
+    table.takeoff_landing.as_ref().map_or((0, 0), |totals| {
+        (column_widths(totals).iter().sum(), totals.rows.len() + 2)
+    })
+}
+
+// The page and the number of pages when the table is split
+#[cfg_attr(not(feature = "json"), allow(unused_mut))]
+fn table_document(
+    plane: &Airplane,
+    visualization: &WeightBalanceTableVisualization,
+    page: Option<(usize, usize)>,
+) -> Result<SvgDocument, WeightBalanceError> {
+    let title = format!("Weight and balance of {}", plane.registration());
+    let mut document = SvgDocument::new(
+        "table",
+        match page {
+            Some((page, pages)) => format!("{}, page {} of {}", title, page, pages),
+            None => title,
+        },
+        "The lever arm, mass and mass moment of every item and their totals".to_string(),
+    )
+    .deterministic(visualization.deterministic)
+    .dpi(visualization.dpi)
+    .font_family(visualization.font_family.clone())
+    .text_direction(visualization.text_direction)
+    .cell_titles(visualization.cell_titles);
+
+    #[cfg(feature = "json")]
+    document.embed_summary(&plane.summary(), visualization.embed_summary)?;
+    Ok(document)
+}
+
+pub fn weight_and_balance_table(
+    plane: &Airplane,
+    visualization: WeightBalanceTableVisualization,
+) -> Result<Visualization, WeightBalanceError> {
+    let mut buf = String::new();
+    weight_and_balance_table_into(plane, visualization, &mut buf)?;
+    Ok(Visualization::Svg(buf))
+}
+
+/// Like [`weight_and_balance_table`], writing the SVG to `buf` as it is drawn. Clear and reuse a
+/// `String` to render many documents without reallocating.
+pub fn weight_and_balance_table_into<W: fmt::Write>(
+    plane: &Airplane,
+    visualization: WeightBalanceTableVisualization,
+    buf: &mut W,
+) -> Result<(), WeightBalanceError> {
+    table_into(plane, visualization, buf, None)
+}
+
+/// The table of [`weight_and_balance_table`] as a one page PDF with vector graphics and the
+/// standard PDF fonts. A pixel is a point, or the size is given by its physical dimensions.
+#[cfg(feature = "pdf")]
+pub fn weight_and_balance_table_pdf(
+    plane: &Airplane,
+    visualization: WeightBalanceTableVisualization,
+) -> Result<Vec<u8>, WeightBalanceError> {
+    let mut buf = String::new();
+    table_into(plane, visualization, &mut buf, Some(VectorFormat::Pdf))?;
+    Ok(buf.into_bytes())
+}
+
+/// The table of [`weight_and_balance_table`] as an encapsulated PostScript file for print
+/// shops, drawn like [`weight_and_balance_table_pdf`].
+#[cfg(feature = "eps")]
+pub fn weight_and_balance_table_eps(
+    plane: &Airplane,
+    visualization: WeightBalanceTableVisualization,
+) -> Result<Vec<u8>, WeightBalanceError> {
+    let mut buf = String::new();
+    table_into(plane, visualization, &mut buf, Some(VectorFormat::Eps))?;
+    Ok(buf.into_bytes())
+}
+
+fn table_into(
+    plane: &Airplane,
+    visualization: WeightBalanceTableVisualization,
+    buf: &mut dyn fmt::Write,
+    format: Option<VectorFormat>,
+) -> Result<(), WeightBalanceError> {
+    plane.check_finite()?;
+    let table = table_to_draw(plane, &visualization);
+    let cell_width = column_widths(&table);
+    let totals = takeoff_landing_extent(&table);
+
+    // Lines are drawn on the edges, so the last one needs a pixel of its own
+    let required = (
+        cell_width.iter().sum::<i32>().max(totals.0) as u32 + 1,
+        (table.rows.len() + 2 + totals.1) as u32 * CELL_HEIGHT as u32 + 1,
+    );
+    let dimensions = fit_dimensions(required, &visualization)?;
+
+    draw_table_page(
+        buf,
+        dimensions,
+        &cell_width,
+        &table,
+        0..table.rows.len(),
+        true,
+        table_document(plane, &visualization, None)?.vector(format),
+    )
+}
+
+// Writes to an `io::Write`, keeping the error it failed with
+struct IoWriter<'w, W: io::Write> {
+    out: &'w mut W,
+    error: Option<io::Error>,
+}
+
+impl<'w, W: io::Write> IoWriter<'w, W> {
+    fn new(out: &'w mut W) -> IoWriter<'w, W> {
+        IoWriter { out, error: None }
+    }
+
+    // The error of the output in place of the drawing error it caused
+    fn finish(self, result: Result<(), WeightBalanceError>) -> Result<(), WeightBalanceError> {
+        match self.error {
+            Some(error) => Err(drawing("write output")(error)),
+            None => result,
+        }
+    }
+}
+
+impl<W: io::Write> fmt::Write for IoWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.out.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+/// Like [`weight_and_balance_table`], writing the SVG to `out` as it is drawn.
+pub fn write_weight_and_balance_table<W: io::Write>(
+    plane: &Airplane,
+    visualization: WeightBalanceTableVisualization,
+    out: &mut W,
+) -> Result<(), WeightBalanceError> {
+    let mut writer = IoWriter::new(out);
+    let result = weight_and_balance_table_into(plane, visualization, &mut writer);
+    writer.finish(result)
+}
+
+/// Rows per page when every page repeats the header, the `reserved` rows of the totals only go
+/// on the last page.
+fn page_row_counts(rows: usize, height: u32, reserved: usize) -> Vec<usize> {
+    let capacity = ((height as i32 - 1) / CELL_HEIGHT - 1).max(1) as usize;
+
+    let mut pages: Vec<usize> = (0..rows)
+        .step_by(capacity)
+        .map(|start| capacity.min(rows - start))
+        .collect();
+
+    // The totals need free slots on the last page
+    if pages.last().is_none_or(|last| *last + reserved > capacity) {
+        pages.push(0);
+    }
+
+    pages
+}
+
+/// Splits the table over as many pages of the requested dimensions as needed, each page
+/// repeating the header row. Columns have the same width on every page, a page must at least
+/// fit the header and one row.
+pub fn weight_and_balance_table_pages(
+    plane: &Airplane,
+    visualization: WeightBalanceTableVisualization,
+) -> Result<Vec<Visualization>, WeightBalanceError> {
+    plane.check_finite()?;
+    let table = table_to_draw(plane, &visualization);
+    let cell_width = column_widths(&table);
+    let totals = takeoff_landing_extent(&table);
+
+    let required = (
+        cell_width.iter().sum::<i32>().max(totals.0) as u32 + 1,
+        (2 + totals.1) as u32 * CELL_HEIGHT as u32 + 1,
+    );
+    let dimensions = fit_dimensions(required, &visualization)?;
+    let pages = page_row_counts(table.rows.len(), dimensions.1, 1 + totals.1);
+
+    let mut start = 0;
+    pages
+        .iter()
+        .enumerate()
+        .map(|(i, rows)| {
+            let page_rows = start..start + rows;
+            start += rows;
+
+            let mut buf = String::new();
+            draw_table_page(
+                &mut buf,
+                dimensions,
+                &cell_width,
+                &table,
+                page_rows,
+                i == pages.len() - 1,
+                table_document(plane, &visualization, Some((i + 1, pages.len())))?,
+            )?;
+            Ok(Visualization::Svg(buf))
+        })
+        .collect()
+}
+
+/// The registration and table strings of every airplane as in
+/// [`weight_and_balance_table_strings`], in the order of `planes`, for the loading sheets of a
+/// fleet.
+pub fn weight_and_balance_tables(planes: &[Airplane]) -> Vec<(String, Vec<Vec<String>>)> {
+    planes
+        .iter()
+        .map(|plane| {
+            (
+                plane.registration().to_string(),
+                weight_and_balance_table_strings(plane),
+            )
+        })
+        .collect()
+}
+
+// The width and height in pixels of the view box of the `<svg>` element that `svg` starts with
+fn svg_dimensions(svg: &str) -> Option<(u32, u32)> {
+    let start = svg.find(" viewBox=\"0 0 ")? + 14;
+    let end = start + svg[start..].find('"')?;
+    let (width, height) = svg[start..end].split_once(' ')?;
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+const MILLIMETERS_IN_INCH: f64 = 25.4;
+
+// The pixels of `millimeters` at `dpi`
+fn pixels(millimeters: (f64, f64), dpi: f64) -> (u32, u32) {
+    let pixels = |millimeters: f64| (millimeters / MILLIMETERS_IN_INCH * dpi).round() as u32;
+    (pixels(millimeters.0), pixels(millimeters.1))
+}
+
+// The millimeters of `pixels` at `dpi`
+pub(crate) fn millimeters(pixels: u32, dpi: f64) -> f64 {
+    pixels as f64 / dpi * MILLIMETERS_IN_INCH
+}
+
+// The `<svg>` tag in `line` with its width and height in millimeters at `dpi`, the view box
+// stays in pixels
+fn in_millimeters(line: &str, dpi: f64) -> String {
+    let Some((width, height)) = svg_dimensions(line) else {
+        return line.to_string();
+    };
+    line.replacen(
+        &format!(" width=\"{}\" height=\"{}\"", width, height),
+        &format!(
+            " width=\"{:.2}mm\" height=\"{:.2}mm\"",
+            millimeters(width, dpi),
+            millimeters(height, dpi)
+        ),
+        1,
+    )
+}
+
+/// The table of every airplane in one SVG, one section below the other in the order of
+/// `planes`, each drawn with `visualization` as by [`weight_and_balance_table`].
+pub fn weight_and_balance_tables_document(
+    planes: &[Airplane],
+    visualization: WeightBalanceTableVisualization,
+) -> Result<Visualization, WeightBalanceError> {
+    let mut sections = String::new();
+    let (mut width, mut height) = (0, 0);
+    for plane in planes {
+        let mut section = visualization.clone();
+        section.dpi = None;
+        let Visualization::Svg(svg) = weight_and_balance_table(plane, section)?;
+        let dimensions = svg_dimensions(&svg).ok_or(WeightBalanceError::Drawing {
+            what: "table section",
+            reason: "no dimensions".to_string(),
+        })?;
+
+        sections.push_str(&format!("<svg y=\"{}\"", height));
+        sections.push_str(svg.trim_start_matches("<svg"));
+        width = width.max(dimensions.0);
+        height += dimensions.1;
+    }
+
+    let registrations: Vec<String> = planes
+        .iter()
+        .map(|plane| plane.registration().to_string())
+        .collect();
+    let svg = format!(
+        "<svg width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\" \
+         xmlns=\"http://www.w3.org/2000/svg\" role=\"document\">\n",
+        width, height
+    );
+    let mut buf = match visualization.dpi {
+        Some(dpi) => in_millimeters(&svg, dpi),
+        None => svg,
+    };
+    buf.push_str(&format!(
+        "<title>Weight and balance of {}</title>\n",
+        escaped(&registrations.join(", "))
+    ));
+    buf.push_str(&sections);
+    buf.push_str("</svg>\n");
+    Ok(Visualization::Svg(buf))
+}
+
+// The moment and mass at the corners of the envelope of `limits`
+fn corners(limits: &Limits) -> [(f64, f64); 4] {
+    let (forward, rearward) = (
+        limits.forward_cg_limit().meter(),
+        limits.rearward_cg_limit().meter(),
+    );
+    let (minimum, mtow) = (limits.minimum_weight().kilo(), limits.mtow().kilo());
+    [
+        (forward * minimum, minimum),
+        (rearward * minimum, minimum),
+        (rearward * mtow, mtow),
+        (forward * mtow, mtow),
+    ]
+}
+
+// The envelope, take-off and landing points with a margin of 5% on every side
+fn fitted_axis(plane: &Airplane) -> (Range<f64>, Range<f64>) {
+    let limits = plane.limits();
+    let (forward, rearward) = (
+        limits.forward_cg_limit().meter(),
+        limits.rearward_cg_limit().meter(),
+    );
+    let (minimum, mtow) = (limits.minimum_weight().kilo(), limits.mtow().kilo());
+
+    let mut points = vec![
+        (forward * minimum, minimum),
+        (rearward * minimum, minimum),
+        (rearward * mtow, mtow),
+        (forward * mtow, mtow),
+        (plane.total_mass_moment().kgm(), plane.total_mass().kilo()),
+    ];
+    for limits in plane.tagged_limits() {
+        points.extend(corners(limits));
+    }
+    if let (Ok(moment), Ok(mass)) = (
+        plane.total_mass_moment_landing(),
+        plane.total_mass_landing(),
+    ) {
+        points.push((moment.kgm(), mass.kilo()));
+    }
+    if let (Ok(moment), Ok(mass)) = (
+        plane.total_mass_moment_alternate_landing(),
+        plane.total_mass_alternate_landing(),
+    ) {
+        points.push((moment.kgm(), mass.kilo()));
+    }
+
+    let range = |values: Vec<f64>| {
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let margin = ((max - min) * 0.05).max(1.0);
+        min - margin..max + margin
+    };
+
+    (
+        range(points.iter().map(|p| p.0).collect()),
+        range(points.iter().map(|p| p.1).collect()),
+    )
+}
+
+pub fn weight_and_balance_chart(
+    plane: &Airplane,
+    visualization: WeightBalanceChartVisualization,
+) -> Result<Visualization, WeightBalanceError> {
+    let mut buf = String::new();
+    weight_and_balance_chart_into(plane, visualization, &mut buf)?;
+    Ok(Visualization::Svg(buf))
+}
+
+/// Like [`weight_and_balance_chart`], writing the SVG to `buf` as it is drawn.
+pub fn weight_and_balance_chart_into<W: fmt::Write>(
+    plane: &Airplane,
+    visualization: WeightBalanceChartVisualization,
+    buf: &mut W,
+) -> Result<(), WeightBalanceError> {
+    chart_into(plane, visualization, buf, None)
+}
+
+/// The chart of [`weight_and_balance_chart`] as a one page PDF with vector graphics and the
+/// standard PDF fonts. A pixel is a point, or the size is given by its physical dimensions.
+#[cfg(feature = "pdf")]
+pub fn weight_and_balance_chart_pdf(
+    plane: &Airplane,
+    visualization: WeightBalanceChartVisualization,
+) -> Result<Vec<u8>, WeightBalanceError> {
+    let mut buf = String::new();
+    chart_into(plane, visualization, &mut buf, Some(VectorFormat::Pdf))?;
+    Ok(buf.into_bytes())
+}
+
+/// The chart of [`weight_and_balance_chart`] as an encapsulated PostScript file for print shops,
+/// drawn like [`weight_and_balance_chart_pdf`].
+#[cfg(feature = "eps")]
+pub fn weight_and_balance_chart_eps(
+    plane: &Airplane,
+    visualization: WeightBalanceChartVisualization,
+) -> Result<Vec<u8>, WeightBalanceError> {
+    let mut buf = String::new();
+    chart_into(plane, visualization, &mut buf, Some(VectorFormat::Eps))?;
+    Ok(buf.into_bytes())
+}
+
+fn chart_into(
+    plane: &Airplane,
+    visualization: WeightBalanceChartVisualization,
+    buf: &mut dyn fmt::Write,
+    format: Option<VectorFormat>,
+) -> Result<(), WeightBalanceError> {
+    plane.check_finite()?;
+    let units = visualization.units;
+    let layout = match visualization.compact {
+        true => &COMPACT_LAYOUT,
+        false => &LAYOUT,
+    };
+    let axis = visualization.axis.clone().unwrap_or_else(|| {
+        let (x, y) = fitted_axis(plane);
+        let (start, end) = (units.point((x.start, y.start)), units.point((x.end, y.end)));
+        (start.0..end.0, start.1..end.1)
+    });
+
+    let summary = plane.summary();
+    let mut description = format!(
+        "Take-off mass {:.1} kg with the center of gravity at {:.1} mm, {} the limits",
+        summary.takeoff_mass().kilo(),
+        summary.takeoff_center_of_gravity().meter() * 1000.0,
+        if summary.within_limits() {
+            "within"
+        } else {
+            "outside"
+        }
+    );
+    if let Some(landing) = summary.landing_mass() {
+        description.push_str(&format!(", landing mass {:.1} kg", landing.kilo()));
+    }
+    #[cfg_attr(not(feature = "json"), allow(unused_mut))]
+    let mut document = SvgDocument::new(
+        "graphics-document",
+        format!("Weight and balance of {}", plane.registration()),
+        description,
+    )
+    .deterministic(visualization.deterministic)
+    .dpi(visualization.dpi)
+    .font_family(visualization.font_family.clone())
+    .text_direction(visualization.text_direction)
+    .vector(format);
+    #[cfg(feature = "json")]
+    document.embed_summary(&summary, visualization.embed_summary)?;
+
+    let family = document.family("sans-serif");
+
+    {
+        let left = document
+            .backend(buf, visualization.dimensions)
+            .into_drawing_area();
+
+        left.fill(&WHITE)
+            .map_err(drawing("fill background with white"))?;
+
+        let mut chart = ChartBuilder::on(&left)
+            .caption(
+                plane.registration().to_string(),
+                (family.as_str(), layout.caption).into_font(),
+            )
+            .margin(5)
+            .margin_right(layout.margin_right)
+            .x_label_area_size(layout.x_label_area)
+            .y_label_area_size(layout.y_label_area)
+            .build_cartesian_2d(
+                visualization.x_axis.ticks(axis.0.clone()),
+                visualization.y_axis.ticks(axis.1.clone()),
+            )
+            .map_err(drawing("build chart"))?;
+
+        document.group(&left, "group", "Axes")?;
+        chart
+            .configure_mesh()
+            .x_desc(format!(
+                "{} [{}]",
+                layout.mass_moment, visualization.x_axis.unit
+            ))
+            .x_label_style((family.as_str(), layout.label).into_font())
+            .y_desc(format!("Mass [{}]", visualization.y_axis.unit))
+            .y_label_style((family.as_str(), layout.label).into_font())
+            .x_label_formatter(&|x| visualization.x_axis.label(&axis.0, *x))
+            .y_label_formatter(&|y| visualization.y_axis.label(&axis.1, *y))
+            .draw()
+            .map_err(drawing("configure mesh"))?;
+
+        let kg_mtow = plane.limits().mtow().kilo();
+        let m_forward_cg_moment = plane.limits().forward_cg_limit().meter();
+        let m_rearward_cg_moment = plane.limits().rearward_cg_limit().meter();
+        let kg_minimum_weight = plane.limits().minimum_weight().kilo();
+        let square_points = vec![
+            units.point((m_forward_cg_moment * kg_minimum_weight, kg_minimum_weight)),
+            units.point((m_rearward_cg_moment * kg_minimum_weight, kg_minimum_weight)),
+            units.point((m_rearward_cg_moment * kg_mtow, kg_mtow)),
+            units.point((m_forward_cg_moment * kg_mtow, kg_mtow)),
+        ];
+
+        let mut legend = vec![];
+
+        // Shade the area between the edges of the axes and the envelope, split in a quadrilateral
+        // below, right of, above and left of the envelope
+        if visualization.shade_outside {
+            let (x, y) = (&axis.0, &axis.1);
+            let edges = [
+                (x.start, y.start),
+                (x.end, y.start),
+                (x.end, y.end),
+                (x.start, y.end),
+            ];
+            let envelope = corners(plane.limits()).map(|corner| units.point(corner));
+            let style = GREY.mix(0.3);
+
+            legend.push(("Outside Limits", LegendMark::Area(style)));
+            document.group(&left, "group", "Outside Limits")?;
+            chart
+                .draw_series((0..4).map(|i| {
+                    let next = (i + 1) % 4;
+                    Polygon::new(
+                        vec![edges[i], edges[next], envelope[next], envelope[i]],
+                        style,
+                    )
+                }))
+                .map_err(drawing("draw outside limits"))?
+                .label("Outside Limits")
+                .legend(move |(x, y)| {
+                    Rectangle::new([(x - 5, y - 5), (x + 5, y + 5)], style.filled())
+                });
+        }
+
+        // Draw the square (CG envelope)
+        legend.push(("CG Envelope", LegendMark::Area(RED.mix(0.2))));
+        document.group(&left, "group", "CG Envelope")?;
+        chart
+            .draw_series(std::iter::once(Polygon::new(square_points, RED.mix(0.2))))
+            .map_err(drawing("draw polygon"))?
+            .label("CG Envelope")
+            .legend(|(x, y)| Rectangle::new([(x - 5, y - 5), (x + 5, y + 5)], RED.mix(0.2).filled()));
+
+        // Label the corners inside the envelope, the forward ones to the right of the corner
+        if visualization.corner_labels {
+            document.group(&left, "group", "Envelope Corners")?;
+            let limits = plane.limits();
+            let style =
+                TextStyle::from((family.as_str(), layout.annotation).into_font()).color(&BLACK);
+            let centers_of_gravity = [
+                limits.forward_cg_limit().meter(),
+                limits.rearward_cg_limit().meter(),
+                limits.rearward_cg_limit().meter(),
+                limits.forward_cg_limit().meter(),
+            ];
+            for (i, ((moment, mass), cg)) in corners(limits)
+                .into_iter()
+                .zip(centers_of_gravity)
+                .enumerate()
+            {
+                let (h_pos, dx) = match i {
+                    0 | 3 => (HPos::Left, 8),
+                    _ => (HPos::Right, -8),
+                };
+                let (v_pos, dy) = match i {
+                    0 | 1 => (VPos::Bottom, -8),
+                    _ => (VPos::Top, 8),
+                };
+                chart
+                    .draw_series(std::iter::once(
+                        EmptyElement::at(units.point((moment, mass)))
+                            + Text::new(
+                                units.corner_label(mass, cg),
+                                (dx, dy),
+                                style.pos(Pos::new(h_pos, v_pos)),
+                            ),
+                    ))
+                    .map_err(drawing("draw corner label"))?;
+            }
+        }
+
+        // Draw the outline of the added limits, policy limits dashed
+        for limits in plane.tagged_limits() {
+            let mut outline: Vec<(f64, f64)> = corners(limits)
+                .into_iter()
+                .map(|corner| units.point(corner))
+                .collect();
+            outline.push(outline[0]);
+            let style = BLUE.stroke_width(2);
+            let label = match limits.kind() {
+                LimitKind::Structural => "Structural Limits",
+                LimitKind::Operational => "Operational Limits",
+                LimitKind::Policy => "Policy Limits",
+            };
+
+            legend.push((label, LegendMark::Line(BLUE.to_rgba())));
+            document.group(&left, "group", label)?;
+            match limits.kind() {
+                LimitKind::Policy => {
+                    chart.draw_series(DashedLineSeries::new(outline, 10, 5, style))
+                }
+                _ => chart.draw_series(LineSeries::new(outline, style)),
+            }
+            .map_err(drawing("draw limits"))?
+            .label(label)
+            .legend(|(x, y)| PathElement::new(vec![(x - 10, y), (x + 10, y)], BLUE));
+        }
+
+        // Draw the total mass and moment point
+        legend.push(("Take-off Point", LegendMark::Point(GREEN.to_rgba())));
+        document.group(&left, "group", "Take-off Point")?;
+        chart
+            .draw_series(PointSeries::of_element(
+                vec![units.point((plane.total_mass_moment().kgm(), plane.total_mass().kilo()))],
+                5,
+                if plane.within_limits() { GREEN } else { RED },
+                &|c, s, st| EmptyElement::at(c) + Circle::new((0, 0), s, st.filled()),
+            ))
+            .map_err(drawing("draw point"))?
+            .label("Take-off Point")
+            .legend(|(x, y)| Circle::new((x, y), 5, GREEN.filled()));
+
+        // Draw the landing mass and moment point
+        legend.push(("Landing Point", LegendMark::Point(PURPLE.to_rgba())));
+        document.group(&left, "group", "Landing Point")?;
+        chart
+            .draw_series(PointSeries::of_element(
+                vec![units.point((
+                    plane.total_mass_moment_landing()?.kgm(),
+                    plane.total_mass_landing()?.kilo(),
+                ))],
+                5,
+                PURPLE,
+                &|c, s, st| EmptyElement::at(c) + Circle::new((0, 0), s, st.filled()),
+            ))
+            .map_err(drawing("draw point"))?
+            .label("Landing Point")
+            .legend(|(x, y)| Circle::new((x, y), 5, PURPLE.filled()));
+
+        // Draw the alternate landing point when there is diversion fuel
+        if let Ok(within_limits) = plane.alternate_landing_within_limits() {
+            legend.push((
+                "Alternate Landing Point",
+                LegendMark::Point(MAGENTA.to_rgba()),
+            ));
+            document.group(&left, "group", "Alternate Landing Point")?;
+            chart
+                .draw_series(PointSeries::of_element(
+                    vec![units.point((
+                        plane.total_mass_moment_alternate_landing()?.kgm(),
+                        plane.total_mass_alternate_landing()?.kilo(),
+                    ))],
+                    5,
+                    if within_limits { MAGENTA } else { RED },
+                    &|c, s, st| EmptyElement::at(c) + Circle::new((0, 0), s, st.filled()),
+                ))
+                .map_err(drawing("draw point"))?
+                .label("Alternate Landing Point")
+                .legend(|(x, y)| Circle::new((x, y), 5, MAGENTA.filled()));
+        }
+
+        // Configure and draw the legend, compact charts have none
+        if !visualization.compact {
+            document.group(&left, "group", "Legend")?;
+            if visualization.deterministic {
+                draw_legend(
+                    &chart.plotting_area().strip_coord_spec(),
+                    &legend,
+                    20,
+                    &family,
+                )?;
+            } else {
+                chart
+                    .configure_series_labels()
+                    .label_font((family.as_str(), LEGEND_FONT_SIZE))
+                    .border_style(BLACK)
+                    .margin(20)
+                    .background_style(WHITE.mix(0.8))
+                    .draw()
+                    .map_err(drawing("draw legend"))?;
+            }
+        }
+
+        left.present().map_err(drawing("write to buffer"))?;
+    }
+
+    Ok(())
+}
+
+/// Like [`weight_and_balance_chart`], writing the SVG to `out` as it is drawn.
+pub fn write_weight_and_balance_chart<W: io::Write>(
+    plane: &Airplane,
+    visualization: WeightBalanceChartVisualization,
+    out: &mut W,
+) -> Result<(), WeightBalanceError> {
+    let mut writer = IoWriter::new(out);
+    let result = weight_and_balance_chart_into(plane, visualization, &mut writer);
+    writer.finish(result)
+}
+
+/// A labeled point of the weight and balance chart, a mass moment in kg m and a mass in kg.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChartPoint {
+    label: String,
+    mass_moment: f64,
+    mass: f64,
+    within_limits: bool,
+}
+
+impl ChartPoint {
+    fn new(label: String, (mass_moment, mass): (f64, f64), within_limits: bool) -> ChartPoint {
+        ChartPoint {
+            label,
+            mass_moment,
+            mass,
+            within_limits,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn mass_moment(&self) -> f64 {
+        self.mass_moment
+    }
+
+    pub fn mass(&self) -> f64 {
+        self.mass
+    }
+
+    /// Drawn in red on the chart when not, the corners of the envelope always are.
+    pub fn within_limits(&self) -> bool {
+        self.within_limits
+    }
+}
+
+/// A labeled outline of the chart, the corners as a mass moment in kg m and a mass in kg.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChartOutline {
+    label: String,
+    points: Vec<(f64, f64)>,
+    dashed: bool,
+}
+
+impl ChartOutline {
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The corners in order, without repeating the first one.
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    /// Whether the outline is dashed, as the policy limits are.
+    pub fn dashed(&self) -> bool {
+        self.dashed
+    }
+}
+
+/// Everything [`weight_and_balance_chart`] draws as plain data, in kg m and kg, for charts drawn
+/// with other plotting libraries.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChartSeriesData {
+    caption: String,
+    x_label: String,
+    y_label: String,
+    x_axis: Range<f64>,
+    y_axis: Range<f64>,
+    envelope: ChartOutline,
+    limits: Vec<ChartOutline>,
+    corners: Vec<ChartPoint>,
+    takeoff: ChartPoint,
+    landing: Option<ChartPoint>,
+    alternate_landing: Option<ChartPoint>,
+}
+
+impl ChartSeriesData {
+    /// The formatted registration, e.g. `PH-DHA`.
+    pub fn caption(&self) -> &str {
+        &self.caption
+    }
+
+    pub fn x_label(&self) -> &str {
+        &self.x_label
+    }
+
+    pub fn y_label(&self) -> &str {
+        &self.y_label
+    }
+
+    /// The mass moments of the default axis, fitted to the envelope and the points.
+    pub fn x_axis(&self) -> &Range<f64> {
+        &self.x_axis
+    }
+
+    pub fn y_axis(&self) -> &Range<f64> {
+        &self.y_axis
+    }
+
+    pub fn envelope(&self) -> &ChartOutline {
+        &self.envelope
+    }
+
+    /// The structural, operational and policy limits added to the airplane.
+    pub fn limits(&self) -> &[ChartOutline] {
+        &self.limits
+    }
+
+    /// The corners of the envelope labeled with their mass and center of gravity, e.g.
+    /// `750 kg / 0.523 m`.
+    pub fn corners(&self) -> &[ChartPoint] {
+        &self.corners
+    }
+
+    pub fn takeoff(&self) -> &ChartPoint {
+        &self.takeoff
+    }
+
+    /// `None` when the landing mass or moment cannot be computed.
+    pub fn landing(&self) -> Option<&ChartPoint> {
+        self.landing.as_ref()
+    }
+
+    /// `None` without diversion fuel.
+    pub fn alternate_landing(&self) -> Option<&ChartPoint> {
+        self.alternate_landing.as_ref()
+    }
+}
+
+/// The envelope, limits, points and labels of the weight and balance chart of `plane`.
+pub fn chart_series_data(plane: &Airplane) -> Result<ChartSeriesData, WeightBalanceError> {
+    plane.check_finite()?;
+    let limits = plane.limits();
+    let (x_axis, y_axis) = fitted_axis(plane);
+    let centers_of_gravity = [
+        limits.forward_cg_limit().meter(),
+        limits.rearward_cg_limit().meter(),
+        limits.rearward_cg_limit().meter(),
+        limits.forward_cg_limit().meter(),
+    ];
+
+    let landing = match (
+        plane.total_mass_moment_landing(),
+        plane.total_mass_landing(),
+    ) {
+        (Ok(moment), Ok(mass)) => Some(ChartPoint::new(
+            "Landing Point".to_string(),
+            (moment.kgm(), mass.kilo()),
+            limits.contains(&mass, &CenterOfGravity::Meter(moment.kgm() / mass.kilo())),
+        )),
+        _ => None,
+    };
+    let alternate_landing = match plane.alternate_landing_within_limits() {
+        Ok(within_limits) => Some(ChartPoint::new(
+            "Alternate Landing Point".to_string(),
+            (
+                plane.total_mass_moment_alternate_landing()?.kgm(),
+                plane.total_mass_alternate_landing()?.kilo(),
+            ),
+            within_limits,
+        )),
+        Err(_) => None,
+    };
+
+    Ok(ChartSeriesData {
+        caption: plane.registration().to_string(),
+        x_label: format!(
+            "{} [{}]",
+            LAYOUT.mass_moment,
+            ChartUnits::Metric.mass_moment()
+        ),
+        y_label: format!("Mass [{}]", ChartUnits::Metric.mass()),
+        x_axis,
+        y_axis,
+        envelope: ChartOutline {
+            label: "CG Envelope".to_string(),
+            points: corners(limits).to_vec(),
+            dashed: false,
+        },
+        limits: plane
+            .tagged_limits()
+            .iter()
+            .map(|limits| ChartOutline {
+                label: match limits.kind() {
+                    LimitKind::Structural => "Structural Limits",
+                    LimitKind::Operational => "Operational Limits",
+                    LimitKind::Policy => "Policy Limits",
+                }
+                .to_string(),
+                points: corners(limits).to_vec(),
+                dashed: limits.kind() == LimitKind::Policy,
+            })
+            .collect(),
+        corners: corners(limits)
+            .into_iter()
+            .zip(centers_of_gravity)
+            .map(|((moment, mass), cg)| {
+                ChartPoint::new(
+                    ChartUnits::Metric.corner_label(mass, cg),
+                    (moment, mass),
+                    true,
                 )
-                .expect("cannot draw text");
+            })
+            .collect(),
+        takeoff: ChartPoint::new(
+            "Take-off Point".to_string(),
+            (plane.total_mass_moment().kgm(), plane.total_mass().kilo()),
+            plane.within_limits(),
+        ),
+        landing,
+        alternate_landing,
+    })
+}
+
+/// The summary embedded in an SVG with `with_embedded_summary`, `None` when there is none.
+#[cfg(feature = "json")]
+pub fn embedded_summary(svg: &str) -> Result<Option<WeightBalanceSummary>, ProfileError> {
+    let Some(start) = svg.find(SUMMARY_START) else {
+        return Ok(None);
+    };
+    let start = start + SUMMARY_START.len();
+    let Some(end) = svg[start..].find(SUMMARY_END).map(|end| start + end) else {
+        return Ok(None);
+    };
+
+    let json = svg[start..end].replace("]]]]><![CDATA[>", "]]>");
+    WeightBalanceSummary::from_json(&json).map(Some)
+}
+
+const PERFORMANCE_CHART_SAMPLES: usize = 50;
+
+/// The rate of climb against density altitude up to the absolute ceiling, at the reference mass
+/// and at the take-off mass of the airplane.
+pub fn climb_chart(
+    plane: &Airplane,
+    climb: &ClimbPerformance,
+    visualization: PerformanceChartVisualization,
+) -> Result<Visualization, WeightBalanceError> {
+    let mut buf = String::new();
+    climb_chart_into(plane, climb, visualization, &mut buf)?;
+    Ok(Visualization::Svg(buf))
+}
+
+/// Like [`climb_chart`], writing the SVG to `buf` as it is drawn.
+pub fn climb_chart_into<W: fmt::Write>(
+    plane: &Airplane,
+    climb: &ClimbPerformance,
+    visualization: PerformanceChartVisualization,
+    buf: &mut W,
+) -> Result<(), WeightBalanceError> {
+    plane.check_finite()?;
+    let ceiling = climb.absolute_ceiling_ft();
+    let curve = |mass: &Mass| -> Vec<(f64, f64)> {
+        (0..=PERFORMANCE_CHART_SAMPLES)
+            .map(|i| {
+                let altitude = ceiling * i as f64 / PERFORMANCE_CHART_SAMPLES as f64;
+                (altitude, climb.rate_of_climb_fpm(mass, altitude))
+            })
+            .collect()
+    };
+    let reference = curve(climb.reference_mass());
+    let takeoff = curve(&plane.total_mass());
+    let max_rate = reference[0].1.max(takeoff[0].1) * 1.1;
+    let document = SvgDocument::new(
+        "graphics-document",
+        format!("Rate of climb of {}", plane.registration()),
+        format!(
+            "Rate of climb at the reference and take-off mass up to {:.0} ft",
+            ceiling
+        ),
+    )
+    .deterministic(visualization.deterministic);
+
+    {
+        let area = document
+            .backend(buf, visualization.dimensions)
+            .into_drawing_area();
+
+        area.fill(&WHITE)
+            .map_err(drawing("fill background with white"))?;
+
+        let mut chart = ChartBuilder::on(&area)
+            .caption(
+                plane.registration().to_string(),
+                ("sans-serif", 30).into_font(),
+            )
+            .margin(5)
+            .margin_right(20)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(0.0..ceiling, 0.0..max_rate)
+            .map_err(drawing("build chart"))?;
+
+        document.group(&area, "group", "Axes")?;
+        chart
+            .configure_mesh()
+            .x_desc("Density Altitude [ft]")
+            .y_desc("Rate of Climb [ft/min]")
+            .x_label_formatter(&|x| format!("{}", x.round()))
+            .y_label_formatter(&|y| format!("{}", y.round()))
+            .draw()
+            .map_err(drawing("configure mesh"))?;
+
+        let mut legend = vec![("Reference Mass", LegendMark::Line(GREY.to_rgba()))];
+        document.group(&area, "group", "Reference Mass")?;
+        chart
+            .draw_series(LineSeries::new(reference, GREY.stroke_width(2)))
+            .map_err(drawing("draw line"))?
+            .label("Reference Mass")
+            .legend(|(x, y)| PathElement::new(vec![(x - 10, y), (x + 10, y)], GREY));
+
+        legend.push(("Take-off Mass", LegendMark::Line(BLUE.to_rgba())));
+        document.group(&area, "group", "Take-off Mass")?;
+        chart
+            .draw_series(LineSeries::new(takeoff, BLUE.stroke_width(2)))
+            .map_err(drawing("draw line"))?
+            .label("Take-off Mass")
+            .legend(|(x, y)| PathElement::new(vec![(x - 10, y), (x + 10, y)], BLUE));
+
+        document.group(&area, "group", "Legend")?;
+        if visualization.deterministic {
+            draw_legend(
+                &chart.plotting_area().strip_coord_spec(),
+                &legend,
+                10,
+                "sans-serif",
+            )?;
+        } else {
+            chart
+                .configure_series_labels()
+                .border_style(BLACK)
+                .background_style(WHITE.mix(0.8))
+                .draw()
+                .map_err(drawing("draw legend"))?;
+        }
+
+        area.present().map_err(drawing("write to buffer"))?;
+    }
+
+    Ok(())
+}
+
+/// Like [`climb_chart`], writing the SVG to `out` as it is drawn.
+pub fn write_climb_chart<W: io::Write>(
+    plane: &Airplane,
+    climb: &ClimbPerformance,
+    visualization: PerformanceChartVisualization,
+    out: &mut W,
+) -> Result<(), WeightBalanceError> {
+    let mut writer = IoWriter::new(out);
+    let result = climb_chart_into(plane, climb, visualization, &mut writer);
+    writer.finish(result)
+}
+
+/// The take-off distance at the take-off mass and the landing distance at the landing mass
+/// against density altitude, over the pressure altitudes of the tables. The temperature follows
+/// the ISA deviation of the conditions, the wind and runway are those of the conditions.
+pub fn takeoff_landing_chart(
+    plane: &Airplane,
+    takeoff: &DistancePerformance,
+    landing: &DistancePerformance,
+    conditions: &Conditions,
+    visualization: PerformanceChartVisualization,
+) -> Result<Visualization, WeightBalanceError> {
+    let mut buf = String::new();
+    takeoff_landing_chart_into(plane, takeoff, landing, conditions, visualization, &mut buf)?;
+    Ok(Visualization::Svg(buf))
+}
+
+/// Like [`takeoff_landing_chart`], writing the SVG to `buf` as it is drawn.
+pub fn takeoff_landing_chart_into<W: fmt::Write>(
+    plane: &Airplane,
+    takeoff: &DistancePerformance,
+    landing: &DistancePerformance,
+    conditions: &Conditions,
+    visualization: PerformanceChartVisualization,
+    buf: &mut W,
+) -> Result<(), WeightBalanceError> {
+    plane.check_finite()?;
+    let deviation = conditions.temperature_c()
+        - performance::isa_temperature_c(conditions.pressure_altitude_ft());
+    let curve = |distances: &DistancePerformance, mass: &Mass| -> Vec<(f64, f64)> {
+        let altitudes = distances.table().pressure_altitudes_ft();
+        let (lowest, highest) = (altitudes[0], altitudes[altitudes.len() - 1]);
+
+        (0..=PERFORMANCE_CHART_SAMPLES)
+            .filter_map(|i| {
+                let altitude =
+                    lowest + (highest - lowest) * i as f64 / PERFORMANCE_CHART_SAMPLES as f64;
+                let temperature = performance::isa_temperature_c(altitude) + deviation;
+                let at = Conditions::new(
+                    altitude,
+                    temperature,
+                    conditions.headwind_kt(),
+                    conditions.runway(),
+                );
+                distances
+                    .distance(mass, &at)
+                    .ok()
+                    .map(|d| (at.density_altitude_ft(), d))
+            })
+            .collect()
+    };
+    let takeoff = curve(takeoff, &plane.total_mass());
+    let landing = curve(landing, &plane.total_mass_landing()?);
+
+    let points = takeoff.iter().chain(landing.iter());
+    let (min_altitude, max_altitude) = points
+        .clone()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), p| {
+            (min.min(p.0), max.max(p.0))
+        });
+    let max_distance = points.map(|p| p.1).fold(0.0, f64::max) * 1.1;
+    if min_altitude >= max_altitude {
+        return Err(WeightBalanceError::Drawing {
+            what: "performance chart",
+            reason: "the airplane is outside the tables".to_string(),
+        });
+    }
+    let document = SvgDocument::new(
+        "graphics-document",
+        format!("Take-off and landing distance of {}", plane.registration()),
+        format!(
+            "Take-off and landing distance from {:.0} to {:.0} ft density altitude",
+            min_altitude, max_altitude
+        ),
+    )
+    .deterministic(visualization.deterministic);
+
+    {
+        let area = document
+            .backend(buf, visualization.dimensions)
+            .into_drawing_area();
+
+        area.fill(&WHITE)
+            .map_err(drawing("fill background with white"))?;
+
+        let mut chart = ChartBuilder::on(&area)
+            .caption(
+                plane.registration().to_string(),
+                ("sans-serif", 30).into_font(),
+            )
+            .margin(5)
+            .margin_right(20)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(min_altitude..max_altitude, 0.0..max_distance)
+            .map_err(drawing("build chart"))?;
+
+        document.group(&area, "group", "Axes")?;
+        chart
+            .configure_mesh()
+            .x_desc("Density Altitude [ft]")
+            .y_desc("Distance [m]")
+            .x_label_formatter(&|x| format!("{}", x.round()))
+            .y_label_formatter(&|y| format!("{}", y.round()))
+            .draw()
+            .map_err(drawing("configure mesh"))?;
+
+        let mut legend = vec![("Take-off Distance", LegendMark::Line(GREEN.to_rgba()))];
+        document.group(&area, "group", "Take-off Distance")?;
+        chart
+            .draw_series(LineSeries::new(takeoff, GREEN.stroke_width(2)))
+            .map_err(drawing("draw line"))?
+            .label("Take-off Distance")
+            .legend(|(x, y)| PathElement::new(vec![(x - 10, y), (x + 10, y)], GREEN));
 
-            current_cell_width += cell_width[1];
+        legend.push(("Landing Distance", LegendMark::Line(PURPLE.to_rgba())));
+        document.group(&area, "group", "Landing Distance")?;
+        chart
+            .draw_series(LineSeries::new(landing, PURPLE.stroke_width(2)))
+            .map_err(drawing("draw line"))?
+            .label("Landing Distance")
+            .legend(|(x, y)| PathElement::new(vec![(x - 10, y), (x + 10, y)], PURPLE));
 
-            let mass_str = match m.mass() {
-                Mass::Avgas(Volume::Liter(l)) => format!("({:.1}L) {:.2}", l, m.mass().kilo()),
-                Mass::Avgas(Volume::Gallon(g)) => format!("({:.1}gal) {:.2}", g, m.mass().kilo()),
-                Mass::Mogas(Volume::Liter(l)) => format!("({:.1}L) {:.2}", l, m.mass().kilo()),
-                Mass::Mogas(Volume::Gallon(g)) => format!("({:.1}gal) {:.2}", g, m.mass().kilo()),
-                Mass::Kilo(_) => format!("{:.2}", m.mass().kilo()),
-            };
+        document.group(&area, "group", "Legend")?;
+        if visualization.deterministic {
+            draw_legend(
+                &chart.plotting_area().strip_coord_spec(),
+                &legend,
+                10,
+                "sans-serif",
+            )?;
+        } else {
+            chart
+                .configure_series_labels()
+                .border_style(BLACK)
+                .background_style(WHITE.mix(0.8))
+                .draw()
+                .map_err(drawing("draw legend"))?;
+        }
 
-            right
-                .draw_text(
-                    &pad_with_nbsp(&mass_str, 16),
-                    &text_style,
-                    (current_cell_width + cell_padding[2], y + 10),
-                )
-                .expect("cannot draw text");
+        area.present().map_err(drawing("write to buffer"))?;
+    }
 
-            current_cell_width += cell_width[2];
-            right
-                .draw_text(
-                    &pad_with_nbsp(&format!("{:.2}", m.total().kgm()), 6),
-                    &text_style,
-                    (current_cell_width + cell_padding[3], y + 10),
-                )
-                .expect("cannot draw text");
-        }
+    Ok(())
+}
 
-        let y = start_y + (plane.moments().len() + 1) as i32 * cell_height;
+/// Like [`takeoff_landing_chart`], writing the SVG to `out` as it is drawn.
+pub fn write_takeoff_landing_chart<W: io::Write>(
+    plane: &Airplane,
+    takeoff: &DistancePerformance,
+    landing: &DistancePerformance,
+    conditions: &Conditions,
+    visualization: PerformanceChartVisualization,
+    out: &mut W,
+) -> Result<(), WeightBalanceError> {
+    let mut writer = IoWriter::new(out);
+    let result = takeoff_landing_chart_into(
+        plane,
+        takeoff,
+        landing,
+        conditions,
+        visualization,
+        &mut writer,
+    );
+    writer.finish(result)
+}
 
-        // Draw footer row with grey background and bold text
-        right
-            .draw(&Rectangle::new(
-                [(start_x, y), (start_x + total_width, y + cell_height)],
-                ShapeStyle {
-                    color: GREY.mix(0.5).to_rgba(),
-                    filled: true,
-                    stroke_width: 0,
-                },
-            ))
-            .expect("cannot draw footer rectangle");
+/// The feasibility matrix as a table with a header row of fuel states and a column of occupant
+/// masses, every cell shaded by its maximum baggage in kg and red when outside the limits.
+pub fn feasibility_table(
+    matrix: &FeasibilityMatrix,
+    visualization: PerformanceChartVisualization,
+) -> Result<Visualization, WeightBalanceError> {
+    let mut buf = String::new();
+    feasibility_table_into(matrix, visualization, &mut buf)?;
+    Ok(Visualization::Svg(buf))
+}
 
-        right
-            .draw_text(
-                "Total",
-                &bold_text_style,
-                (start_x + cell_padding[0], y + 10),
-            )
-            .expect("cannot draw text");
+/// Like [`feasibility_table`], writing the SVG to `buf` as it is drawn.
+pub fn feasibility_table_into<W: fmt::Write>(
+    matrix: &FeasibilityMatrix,
+    visualization: PerformanceChartVisualization,
+    buf: &mut W,
+) -> Result<(), WeightBalanceError> {
+    let (columns, rows) = (
+        matrix.fuel_states().len() as i32 + 1,
+        matrix.occupant_masses().len() as i32 + 1,
+    );
+    let (width, height) = (
+        visualization.dimensions.0 as i32 / columns,
+        visualization.dimensions.1 as i32 / rows,
+    );
+    let most = matrix
+        .rows()
+        .iter()
+        .flatten()
+        .flatten()
+        .map(|m| m.kilo())
+        .fold(0.0, f64::max);
+    let document = SvgDocument::new(
+        "table",
+        "Maximum baggage".to_string(),
+        "The maximum baggage in kg by occupant mass and fuel, a dash when outside the limits"
+            .to_string(),
+    )
+    .deterministic(visualization.deterministic);
 
-        let mut current_cell_width = start_x + cell_width[0];
-        right
-            .draw_text(
-                &pad_with_nbsp(
-                    &format!(
-                        "{:.4}",
-                        plane.total_mass_moment().kgm() / plane.total_mass().kilo()
-                    ),
-                    6,
-                ),
-                &bold_text_style,
-                (current_cell_width + cell_padding[1], y + 10),
-            )
-            .expect("cannot draw text");
+    {
+        let area = document
+            .backend(buf, visualization.dimensions)
+            .into_drawing_area();
 
-        current_cell_width += cell_width[1];
-        right
-            .draw_text(
-                &pad_with_nbsp(&format!("{:.2}", plane.total_mass().kilo()), 16),
-                &bold_text_style,
-                (current_cell_width + cell_padding[2], y + 10),
-            )
-            .expect("cannot draw text");
+        area.fill(&WHITE)
+            .map_err(drawing("fill background with white"))?;
 
-        current_cell_width += cell_width[2];
-        right
-            .draw_text(
-                &pad_with_nbsp(&format!("{:.2}", plane.total_mass_moment().kgm()), 6),
-                &bold_text_style,
-                (current_cell_width + cell_padding[3], y + 10),
-            )
-            .expect("cannot draw text");
+        let text_style = TextStyle::from(("monospace", 16).into_font())
+            .color(&BLACK)
+            .pos(Pos::new(HPos::Center, VPos::Center));
+        let cell = |column: i32, row: i32, text: &str, color: RGBAColor| {
+            let (x, y) = (column * width, row * height);
+            area.draw(&Rectangle::new(
+                [(x, y), (x + width, y + height)],
+                color.filled(),
+            ))
+            .map_err(drawing("draw cell"))?;
+            area.draw(&Rectangle::new([(x, y), (x + width, y + height)], BLACK))
+                .map_err(drawing("draw cell border"))?;
+            area.draw_text(text, &text_style, (x + width / 2, y + height / 2))
+                .map_err(drawing("draw text"))
+        };
 
-        // Draw horizontal lines for the table
-        for i in 0..=(plane.moments().len() + 2) {
-            let y = start_y + i as i32 * cell_height;
-            right
-                .draw(&PathElement::new(
-                    vec![(start_x, y), (start_x + total_width, y)],
-                    BLACK,
-                ))
-                .expect("cannot draw lines");
+        document.group(&area, "row", "Fuel")?;
+        cell(0, 0, "kg", GREY.mix(0.5))?;
+        for (j, fuel) in matrix.fuel_states().iter().enumerate() {
+            let text = match fuel {
+                Mass::Avgas(v) | Mass::Mogas(v) => v.to_string(),
+                Mass::Kilo(kg) => format!("{:.0}kg", kg),
+            };
+            cell(j as i32 + 1, 0, &text, GREY.mix(0.5))?;
         }
 
-        // Draw vertical lines for the table
-        let mut x = start_x;
-        right
-            .draw(&PathElement::new(
-                vec![
-                    (x, start_y),
-                    (
-                        x,
-                        start_y + cell_height * (plane.moments().len() + 2) as i32,
-                    ),
-                ],
-                BLACK,
-            ))
-            .expect("cannot draw lines");
+        for (i, (occupants, row)) in matrix
+            .occupant_masses()
+            .iter()
+            .zip(matrix.rows())
+            .enumerate()
+        {
+            let i = i as i32 + 1;
+            let occupants = format!("{:.0}", occupants.kilo());
+            document.group(&area, "row", &occupants)?;
+            cell(0, i, &occupants, GREY.mix(0.5))?;
 
-        for j in cell_width.iter() {
-            x += j;
-            right
-                .draw(&PathElement::new(
-                    vec![
-                        (x, start_y),
-                        (
-                            x,
-                            start_y + cell_height * (plane.moments().len() + 2) as i32,
-                        ),
-                    ],
-                    BLACK,
-                ))
-                .expect("cannot draw lines");
+            for (j, baggage) in row.iter().enumerate() {
+                match baggage {
+                    Some(baggage) => {
+                        let shade = if most > 0.0 {
+                            0.2 + 0.6 * baggage.kilo() / most
+                        } else {
+                            0.2
+                        };
+                        cell(
+                            j as i32 + 1,
+                            i,
+                            &format!("{:.0}", baggage.kilo()),
+                            GREEN.mix(shade),
+                        )?
+                    }
+                    None => cell(j as i32 + 1, i, "-", RED.mix(0.3))?,
+                }
+            }
         }
 
-        right.present().expect("cannot write to buffer.");
+        area.present().map_err(drawing("write to buffer"))?;
     }
 
-    Visualization::Svg(rbuf)
+    Ok(())
 }
 
-pub fn weight_and_balance_chart(
-    plane: Airplane,
-    visualization: WeightBalanceChartVisualization,
-) -> Visualization {
-    let mut lbuf = String::new();
+/// Like [`feasibility_table`], writing the SVG to `out` as it is drawn.
+pub fn write_feasibility_table<W: io::Write>(
+    matrix: &FeasibilityMatrix,
+    visualization: PerformanceChartVisualization,
+    out: &mut W,
+) -> Result<(), WeightBalanceError> {
+    let mut writer = IoWriter::new(out);
+    let result = feasibility_table_into(matrix, visualization, &mut writer);
+    writer.finish(result)
+}
+
+/// The take-off and landing mass of every flight in `history` in order, with the MTOW of each
+/// flight. Take-offs within [`HEAVY_MARGIN`] of the MTOW are drawn in red.
+pub fn trend_chart(
+    history: &[WeightBalanceSummary],
+    visualization: PerformanceChartVisualization,
+) -> Result<Visualization, WeightBalanceError> {
+    let mut buf = String::new();
+    trend_chart_into(history, visualization, &mut buf)?;
+    Ok(Visualization::Svg(buf))
+}
+
+/// Like [`trend_chart`], writing the SVG to `buf` as it is drawn.
+pub fn trend_chart_into<W: fmt::Write>(
+    history: &[WeightBalanceSummary],
+    visualization: PerformanceChartVisualization,
+    buf: &mut W,
+) -> Result<(), WeightBalanceError> {
+    let flight = |i: usize| (i + 1) as f64;
+    let takeoff: Vec<(f64, f64, f64)> = history
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (flight(i), s.takeoff_mass().kilo(), s.mtow_margin().kilo()))
+        .collect();
+    let landing: Vec<(f64, f64)> = history
+        .iter()
+        .enumerate()
+        .filter_map(|(i, s)| s.landing_mass().map(|m| (flight(i), m.kilo())))
+        .collect();
+    let mtow: Vec<(f64, f64)> = takeoff
+        .iter()
+        .map(|(x, mass, margin)| (*x, mass + margin))
+        .collect();
+
+    let masses = || {
+        takeoff
+            .iter()
+            .map(|(_, mass, _)| *mass)
+            .chain(landing.iter().map(|(_, mass)| *mass))
+            .chain(mtow.iter().map(|(_, mass)| *mass))
+    };
+    let y_range = match (masses().reduce(f64::min), masses().reduce(f64::max)) {
+        (Some(min), Some(max)) => (min - 20.0)..(max + 20.0),
+        _ => 0.0..1000.0,
+    };
+    let x_range = 0.5..(history.len().max(1) as f64 + 0.5);
+    let description = match TrendStatistics::new(history) {
+        Some(statistics) => format!(
+            "{} flights, average take-off mass {:.1} kg, {:.0}% within {} kg of the MTOW",
+            statistics.flights(),
+            statistics.average_takeoff_mass().kilo(),
+            statistics.heavy_percentage(),
+            HEAVY_MARGIN.kilo()
+        ),
+        None => "No flights".to_string(),
+    };
+    let document = SvgDocument::new(
+        "graphics-document",
+        "Take-off and landing mass".to_string(),
+        description,
+    )
+    .deterministic(visualization.deterministic);
 
     {
-        let left = SVGBackend::with_string(
-            &mut lbuf,
-            (visualization.dimensions.0, visualization.dimensions.1),
-        )
-        .into_drawing_area();
+        let area = document
+            .backend(buf, visualization.dimensions)
+            .into_drawing_area();
 
-        left.fill(&WHITE)
-            .expect("cannot fill background with white.");
+        area.fill(&WHITE)
+            .map_err(drawing("fill background with white"))?;
 
-        let mut chart = ChartBuilder::on(&left)
-            .caption(plane.callsign(), ("sans-serif", 50).into_font())
+        let mut chart = ChartBuilder::on(&area)
+            .caption("Take-off and Landing Mass", ("sans-serif", 30).into_font())
             .margin(5)
             .margin_right(20)
-            .x_label_area_size(50)
-            .y_label_area_size(80)
-            .build_cartesian_2d(visualization.axis.0.clone(), visualization.axis.1.clone())
-            .expect("cannot build chart.");
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(x_range, y_range)
+            .map_err(drawing("build chart"))?;
 
+        document.group(&area, "group", "Axes")?;
         chart
             .configure_mesh()
-            .x_desc("Mass Moment [kg m]")
-            .x_label_style(("sans-serif", 20).into_font())
+            .x_desc("Flight")
             .y_desc("Mass [kg]")
-            .y_label_style(("sans-serif", 20).into_font())
+            .x_labels(history.len().clamp(1, 20))
             .x_label_formatter(&|x| format!("{}", x.round()))
             .y_label_formatter(&|y| format!("{}", y.round()))
             .draw()
-            .expect("cannot configure mesh.");
-
-        let kg_mtow = plane.limits().mtow().kilo();
-        let m_forward_cg_moment = plane.limits().forward_cg_limit().meter();
-        let m_rearward_cg_moment = plane.limits().rearward_cg_limit().meter();
-        let kg_minimum_weight = plane.limits().minimum_weight().kilo();
-        let square_points = vec![
-            (m_forward_cg_moment * kg_minimum_weight, kg_minimum_weight),
-            (m_rearward_cg_moment * kg_minimum_weight, kg_minimum_weight),
-            (m_rearward_cg_moment * kg_mtow, kg_mtow),
-            (m_forward_cg_moment * kg_mtow, kg_mtow),
-        ];
+            .map_err(drawing("configure mesh"))?;
 
-        // Draw the square (CG envelope)
+        let mut legend = vec![("MTOW", LegendMark::Line(RED.to_rgba()))];
+        document.group(&area, "group", "MTOW")?;
         chart
-            .draw_series(std::iter::once(Polygon::new(square_points, RED.mix(0.2))))
-            .expect("cannot draw polygon.")
-            .label("CG Envelope")
-            .legend(|(x, y)| Rectangle::new([(x - 5, y - 5), (x + 5, y + 5)], RED.mix(0.2).filled()));
+            .draw_series(DashedLineSeries::new(mtow, 10, 5, RED.stroke_width(2)))
+            .map_err(drawing("draw line"))?
+            .label("MTOW")
+            .legend(|(x, y)| PathElement::new(vec![(x - 10, y), (x + 10, y)], RED));
 
-        // Draw the total mass and moment point
+        legend.push(("Take-off Mass", LegendMark::Line(BLUE.to_rgba())));
+        document.group(&area, "group", "Take-off Mass")?;
         chart
-            .draw_series(PointSeries::of_element(
-                vec![(plane.total_mass_moment().kgm(), plane.total_mass().kilo())],
-                5,
-                if plane.within_limits() { GREEN } else { RED },
-                &|c, s, st| EmptyElement::at(c) + Circle::new((0, 0), s, st.filled()),
+            .draw_series(LineSeries::new(
+                takeoff.iter().map(|(x, mass, _)| (*x, *mass)),
+                BLUE.stroke_width(2),
             ))
-            .expect("cannot draw point.")
-            .label("Take-off Point")
-            .legend(|(x, y)| Circle::new((x, y), 5, GREEN.filled()));
+            .map_err(drawing("draw line"))?
+            .label("Take-off Mass")
+            .legend(|(x, y)| PathElement::new(vec![(x - 10, y), (x + 10, y)], BLUE));
 
-        // Draw the landing mass and moment point
         chart
-            .draw_series(PointSeries::of_element(
-                vec![(plane.total_mass_moment_landing().kgm(), plane.total_mass_landing().kilo())],
-                5,
-                PURPLE,
-                &|c, s, st| EmptyElement::at(c) + Circle::new((0, 0), s, st.filled()),
-            ))
-            .expect("cannot draw point.")
-            .label("Landing Point")
-            .legend(|(x, y)| Circle::new((x, y), 5, PURPLE.filled()));
+            .draw_series(takeoff.iter().map(|(x, mass, margin)| {
+                let color = match *margin <= HEAVY_MARGIN.kilo() {
+                    true => RED,
+                    false => BLUE,
+                };
+                Circle::new((*x, *mass), 4, color.filled())
+            }))
+            .map_err(drawing("draw point"))?;
 
-        // Configure and draw the legend
+        legend.push(("Landing Mass", LegendMark::Line(GREEN.to_rgba())));
+        document.group(&area, "group", "Landing Mass")?;
         chart
-            .configure_series_labels()
-            .border_style(BLACK)
-            .margin(20)
-            .background_style(WHITE.mix(0.8))
-            .draw()
-            .expect("cannot draw legend");
+            .draw_series(LineSeries::new(landing.clone(), GREEN.stroke_width(2)))
+            .map_err(drawing("draw line"))?
+            .label("Landing Mass")
+            .legend(|(x, y)| PathElement::new(vec![(x - 10, y), (x + 10, y)], GREEN));
+
+        chart
+            .draw_series(
+                landing
+                    .iter()
+                    .map(|point| Circle::new(*point, 4, GREEN.filled())),
+            )
+            .map_err(drawing("draw point"))?;
+
+        document.group(&area, "group", "Legend")?;
+        if visualization.deterministic {
+            draw_legend(
+                &chart.plotting_area().strip_coord_spec(),
+                &legend,
+                10,
+                "sans-serif",
+            )?;
+        } else {
+            chart
+                .configure_series_labels()
+                .border_style(BLACK)
+                .background_style(WHITE.mix(0.8))
+                .draw()
+                .map_err(drawing("draw legend"))?;
+        }
 
-        left.present().expect("cannot write to buffer.");
+        area.present().map_err(drawing("write to buffer"))?;
     }
 
-    Visualization::Svg(lbuf)
+    Ok(())
+}
+
+/// Like [`trend_chart`], writing the SVG to `out` as it is drawn.
+pub fn write_trend_chart<W: io::Write>(
+    history: &[WeightBalanceSummary],
+    visualization: PerformanceChartVisualization,
+    out: &mut W,
+) -> Result<(), WeightBalanceError> {
+    let mut writer = IoWriter::new(out);
+    let result = trend_chart_into(history, visualization, &mut writer);
+    writer.finish(result)
 }
 
 //pub fn weight_and_balance_chart(
@@ -464,3 +3364,969 @@ pub fn weight_and_balance_chart(
 //
 //    Visualization::Svg(lbuf)
 //}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::registration::Registration;
+    use crate::weight_and_balance::{CenterOfGravity, LeverArm, Limits};
+
+    fn airplane() -> Airplane {
+        Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![
+                Moment::new(
+                    "Empty".to_string(),
+                    LeverArm::Meter(0.4294),
+                    Mass::Kilo(517.0),
+                ),
+                Moment::new(
+                    "Pilot".to_string(),
+                    LeverArm::Meter(0.515),
+                    Mass::Kilo(80.0),
+                ),
+                Moment::new(
+                    "Fuel".to_string(),
+                    LeverArm::Meter(0.325),
+                    Mass::Avgas(Volume::Liter(55.0)),
+                ),
+            ],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(17.0),
+        )
+    }
+
+    #[test]
+    fn metric_table_strings() {
+        let table = weight_and_balance_table_strings(&airplane());
+
+        assert_eq!(5, table.len());
+        assert_eq!(
+            vec!["Fuel (0,72kg/L)", "0,3250", "(55,00L) 39,60", "12,87"],
+            table[3]
+        );
+        assert_eq!(vec!["Total", "0,4337", "636,60", "276,07"], table[4]);
+    }
+
+    #[test]
+    fn dual_unit_table_strings() {
+        let table = weight_and_balance_table_strings_with_units(&airplane(), TableUnits::Dual);
+
+        assert_eq!(
+            vec![
+                "Name",
+                "Lever Arm [m]",
+                "Lever Arm [in]",
+                "Mass [kg]",
+                "Mass [lb]",
+                "Mass Moment [kg m]",
+                "Mass Moment [lb in]"
+            ],
+            table[0]
+        );
+        assert_eq!(
+            vec!["Pilot", "0,5150", "20,28", "80,00", "176,37", "41,20", "3576,00"],
+            table[2]
+        );
+    }
+
+    #[test]
+    fn table_without_mass() {
+        let plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![],
+            airplane().limits().clone(),
+            Volume::Liter(17.0),
+        );
+
+        let table = weight_and_balance_table_strings_with_units(&plane, TableUnits::Dual);
+        assert_eq!(
+            vec!["Total", "-", "-", "0,00", "0,00", "0,00", "0,00"],
+            table[1]
+        );
+
+        let Visualization::Svg(svg) = weight_and_balance_table(
+            &plane,
+            WeightBalanceTableVisualization::default().with_cell_titles(true),
+        )
+        .unwrap();
+        assert!(!svg.contains("NaN"));
+    }
+
+    #[test]
+    fn not_finite() {
+        let mut plane = airplane();
+        plane.add_moment(Moment::new(
+            String::from("Baggage"),
+            LeverArm::Meter(1.3),
+            Mass::Kilo(f64::NAN),
+        ));
+
+        let error = WeightBalanceError::NotFinite(String::from("mass of Baggage"));
+        assert_eq!(
+            Some(error.clone()),
+            weight_and_balance_chart(&plane, WeightBalanceChartVisualization::default()).err()
+        );
+        assert_eq!(
+            Some(error),
+            weight_and_balance_table(&plane, WeightBalanceTableVisualization::default()).err()
+        );
+    }
+
+    #[test]
+    fn localized_table_strings() {
+        let table = weight_and_balance_table_strings_localized(
+            &airplane(),
+            TableUnits::Metric,
+            &Locale::dutch(),
+        );
+
+        assert_eq!(
+            vec!["Naam", "Arm [m]", "Massa [kg]", "Moment [kg m]"],
+            table[0]
+        );
+        assert_eq!(
+            vec!["Fuel (0,72kg/l)", "0,3250", "(55,00l) 39,60", "12,87"],
+            table[3]
+        );
+        assert_eq!("Totaal", table[4][0]);
+    }
+
+    #[test]
+    fn typed_table_cells() {
+        let locale = Locale::english().with_decimal_separator(',');
+        let cells = weight_and_balance_table_cells(&airplane(), TableUnits::Dual, &locale, &[]);
+        let texts: Vec<Vec<String>> = cells
+            .iter()
+            .map(|row| row.iter().map(ToString::to_string).collect())
+            .collect();
+        assert_eq!(
+            weight_and_balance_table_strings_with_units(&airplane(), TableUnits::Dual),
+            texts
+        );
+
+        let fuel = &cells[3];
+        assert_eq!(CellRole::Row, fuel[0].role());
+        assert_eq!(CellAlignment::Left, fuel[0].alignment());
+        assert_eq!(None, fuel[0].value());
+        assert_eq!(Some(0.325), fuel[1].value());
+        assert_eq!((Some("m"), 4), (fuel[1].unit(), fuel[1].decimals()));
+        assert_eq!(Some("in"), fuel[2].unit());
+        assert_eq!(CellAlignment::Right, fuel[3].alignment());
+        assert_eq!((Some("kg"), 2), (fuel[3].unit(), fuel[3].decimals()));
+        assert_eq!(Some(&Volume::Liter(55.0)), fuel[3].volume());
+        assert_eq!(None, fuel[4].volume());
+        assert_eq!(Some("lb in"), fuel[6].unit());
+
+        assert_eq!(CellRole::Header, cells[0][1].role());
+        assert_eq!(CellRole::Footer, cells[4][0].role());
+        assert!((cells[4][3].value().unwrap() - 636.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fleet_tables() {
+        let other = Airplane::new(
+            Registration::parse("PHABC").unwrap(),
+            vec![Moment::new(
+                String::from("Empty"),
+                LeverArm::Meter(0.4),
+                Mass::Kilo(520.0),
+            )],
+            airplane().limits().clone(),
+            Volume::Liter(17.0),
+        );
+        let planes = [airplane(), other];
+
+        let tables = weight_and_balance_tables(&planes);
+        assert_eq!(
+            vec!["PH-DHA", "PH-ABC"],
+            tables.iter().map(|t| t.0.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!(weight_and_balance_table_strings(&planes[1]), tables[1].1);
+
+        let Visualization::Svg(svg) = weight_and_balance_tables_document(
+            &planes,
+            WeightBalanceTableVisualization::new((700, 300)),
+        )
+        .unwrap();
+        assert_eq!(Some((700, 600)), svg_dimensions(&svg));
+        assert!(svg.contains("<title>Weight and balance of PH-DHA, PH-ABC</title>"));
+        assert!(svg.contains("<svg y=\"300\" width=\"700\""));
+        assert_eq!(3, svg.matches("</svg>").count());
+    }
+
+    #[test]
+    fn physical_dimensions() {
+        // 10 inches by 5 inches
+        let table = WeightBalanceTableVisualization::new((0, 0))
+            .with_physical_dimensions((254.0, 127.0), 100.0);
+        let Visualization::Svg(svg) = weight_and_balance_table(&airplane(), table.clone()).unwrap();
+        assert!(svg.contains(r#"<svg width="254.00mm" height="127.00mm" viewBox="0 0 1000 500""#));
+
+        let Visualization::Svg(svg) = weight_and_balance_chart(
+            &airplane(),
+            WeightBalanceChartVisualization::default()
+                .with_physical_dimensions((101.6, 101.6), 300.0),
+        )
+        .unwrap();
+        assert!(svg.contains(r#"width="101.60mm" height="101.60mm" viewBox="0 0 1200 1200""#));
+
+        // The sections stay in pixels within the document
+        let Visualization::Svg(svg) =
+            weight_and_balance_tables_document(&[airplane(), airplane()], table).unwrap();
+        assert!(
+            svg.starts_with(r#"<svg width="254.00mm" height="254.00mm" viewBox="0 0 1000 1000""#)
+        );
+        assert!(svg.contains(r#"<svg y="500" width="1000" height="500""#));
+    }
+
+    #[test]
+    fn text_direction_and_font_family() {
+        let plane = Airplane::new(
+            Registration::parse("4X-CAB").unwrap(),
+            vec![
+                Moment::new(
+                    "Empty".to_string(),
+                    LeverArm::Meter(0.4294),
+                    Mass::Kilo(517.0),
+                ),
+                Moment::new(
+                    "\u{5d8}\u{5d9}\u{5d9}\u{5e1}".to_string(),
+                    LeverArm::Meter(0.515),
+                    Mass::Kilo(80.0),
+                ),
+                Moment::new(
+                    "\u{71c3}\u{6599}".to_string(),
+                    LeverArm::Meter(0.325),
+                    Mass::Avgas(Volume::Liter(55.0)),
+                ),
+            ],
+            airplane().limits().clone(),
+            Volume::Liter(17.0),
+        );
+        let table = WeightBalanceTableVisualization::new((0, 0))
+            .with_auto_grow(true)
+            .with_deterministic(true)
+            .with_font_family("Noto Sans Mono, \"Noto Sans Mono CJK JP\", monospace");
+
+        let Visualization::Svg(svg) = weight_and_balance_table(
+            &plane,
+            table.clone().with_text_direction(TextDirection::Auto),
+        )
+        .unwrap();
+        assert!(svg.contains(
+            r#"font-family="Noto Sans Mono, &quot;Noto Sans Mono CJK JP&quot;, monospace""#
+        ));
+        let rtl: Vec<&str> = svg
+            .lines()
+            .filter(|l| l.contains("direction=\"rtl\""))
+            .collect();
+        assert_eq!(1, rtl.len());
+        assert!(rtl[0].contains(r#"text-anchor="end""#));
+
+        let Visualization::Svg(svg) =
+            weight_and_balance_table(&plane, table.with_text_direction(TextDirection::Rtl))
+                .unwrap();
+        assert!(svg
+            .lines()
+            .filter(|l| l.starts_with("<text "))
+            .all(|l| l.contains("rtl")));
+
+        // The two wide characters of the fuel take four columns
+        assert_eq!(4, text_columns("\u{71c3}\u{6599}"));
+        assert!(crate::svg::starts_rtl("& \u{5d8}"));
+        assert!(!crate::svg::starts_rtl("4X-CAB \u{5d8}"));
+    }
+
+    #[cfg(feature = "pdf")]
+    #[test]
+    fn vector_pdf() {
+        let table = WeightBalanceTableVisualization::new((0, 0))
+            .with_physical_dimensions((254.0, 127.0), 100.0);
+        let pdf =
+            String::from_utf8(weight_and_balance_table_pdf(&airplane(), table).unwrap()).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("/MediaBox [0 0 720.00 360.00]"));
+        assert!(pdf.contains("(Total) Tj"));
+        assert!(pdf.contains("/Title (Weight and balance of PH-DHA)"));
+
+        // The cross-reference table is where the trailer says
+        let startxref = pdf.rsplit("startxref\n").next().unwrap();
+        let offset: usize = startxref.lines().next().unwrap().parse().unwrap();
+        assert!(pdf[offset..].starts_with("xref\n"));
+
+        let chart = WeightBalanceChartVisualization::default();
+        let pdf =
+            String::from_utf8(weight_and_balance_chart_pdf(&airplane(), chart).unwrap()).unwrap();
+        assert!(pdf.contains("/BaseFont /Helvetica"));
+        assert!(pdf.contains(" re\nf Q"));
+        // The groups of the SVG draw nothing in a PDF
+        assert!(!pdf.contains("0.004 0.008 0.012 rg"));
+    }
+
+    #[cfg(feature = "eps")]
+    #[test]
+    fn encapsulated_postscript() {
+        let table = WeightBalanceTableVisualization::new((0, 0))
+            .with_physical_dimensions((254.0, 127.0), 100.0);
+        let eps =
+            String::from_utf8(weight_and_balance_table_eps(&airplane(), table).unwrap()).unwrap();
+        assert!(eps.starts_with("%!PS-Adobe-3.0 EPSF-3.0\n%%BoundingBox: 0 0 720 360\n"));
+        assert!(eps.contains("/F3 /Courier latin1\n"));
+        assert!(eps.contains("(Total) Tj"));
+        assert!(eps.ends_with("showpage\n%%Trailer\n%%EOF\n"));
+
+        // Without transparency the legend background is mixed with white
+        let chart = WeightBalanceChartVisualization::default();
+        let eps =
+            String::from_utf8(weight_and_balance_chart_eps(&airplane(), chart).unwrap()).unwrap();
+        assert!(!eps.contains(" gs\n"));
+        assert!(eps.is_ascii());
+    }
+
+    #[test]
+    fn table_pages_repeat_header_and_end_with_totals() {
+        // 100px fits the header and two more rows
+        assert_eq!(vec![2, 2, 1], page_row_counts(5, 100, 1));
+        assert_eq!(vec![2, 2, 0], page_row_counts(4, 100, 1));
+        assert_eq!(vec![0], page_row_counts(0, 100, 1));
+
+        let pages = weight_and_balance_table_pages(
+            &airplane(),
+            WeightBalanceTableVisualization::new((800, 100)),
+        )
+        .unwrap();
+        assert_eq!(2, pages.len());
+    }
+
+    #[test]
+    fn takeoff_and_landing_totals() {
+        let english = Locale::english();
+        let table =
+            weight_and_balance_takeoff_landing_strings(&airplane(), TableUnits::Metric, &english);
+        assert_eq!(
+            vec![
+                vec!["Total", "Takeoff", "Landing"],
+                vec!["Mass [kg]", "636.60", "624.36"],
+                vec!["Mass Moment [kg m]", "276.07", "272.09"],
+                vec!["CG [m]", "0.4337", "0.4358"],
+            ],
+            table
+        );
+        let dutch = Locale::dutch();
+        let dual =
+            weight_and_balance_takeoff_landing_strings(&airplane(), TableUnits::Dual, &dutch);
+        assert_eq!(vec!["Totaal", "Start", "Landing"], dual[0]);
+        assert_eq!("Zwaartepunt [in]", dual[6][0]);
+
+        let mut plane = airplane();
+        plane.add_moment(Moment::new(
+            "Baggage".to_string(),
+            LeverArm::Meter(1.3),
+            Mass::Kilo(5.0),
+        ));
+        let table =
+            weight_and_balance_takeoff_landing_strings(&plane, TableUnits::Metric, &english);
+        assert_eq!(vec!["Mass [kg]", "641.60", "-"], table[1]);
+
+        let visualization = WeightBalanceTableVisualization::new((400, 100))
+            .with_takeoff_landing_totals(true)
+            .with_deterministic(true);
+        let Visualization::Svg(svg) =
+            weight_and_balance_table(&airplane(), visualization.clone().with_auto_grow(true))
+                .unwrap();
+        assert!(svg.contains(r#"width="571" height="301""#));
+        assert!(svg.contains("\nLanding\n"));
+
+        let visualization = visualization.with_dimensions((800, 220));
+        let pages: Vec<String> = weight_and_balance_table_pages(&airplane(), visualization)
+            .unwrap()
+            .into_iter()
+            .map(|Visualization::Svg(svg)| svg)
+            .collect();
+        assert_eq!(2, pages.len());
+        assert!(!pages[0].contains("\nTakeoff\n"));
+        assert!(pages[1].contains("\nTakeoff\n"));
+    }
+
+    #[test]
+    fn table_must_fit_canvas() {
+        let required = (571, 151);
+
+        assert_eq!(
+            Some(WeightBalanceError::TableDoesNotFit {
+                required,
+                available: (800, 100),
+            }),
+            weight_and_balance_table(
+                &airplane(),
+                WeightBalanceTableVisualization::new((800, 100))
+            )
+            .err()
+        );
+
+        let Visualization::Svg(svg) = weight_and_balance_table(
+            &airplane(),
+            WeightBalanceTableVisualization::new((400, 100)).with_auto_grow(true),
+        )
+        .unwrap();
+        assert!(svg.contains(r#"width="571" height="151""#));
+    }
+
+    #[test]
+    fn table_with_notes() {
+        let mut plane = airplane();
+        plane.add_moment(
+            Moment::new("Baggage".to_string(), LeverArm::Meter(1.3), Mass::Kilo(5.0))
+                .with_note("Headsets".to_string()),
+        );
+
+        let table = |notes: bool| {
+            let visualization = WeightBalanceTableVisualization::default().with_notes(notes);
+            let Visualization::Svg(svg) = weight_and_balance_table(&plane, visualization).unwrap();
+            svg
+        };
+        assert!(table(true).contains("Baggage - Headsets"));
+        assert!(!table(false).contains("Headsets"));
+    }
+
+    #[test]
+    fn default_chart_axis_fits_envelope() {
+        let plane = airplane();
+        let (moment, mass) = fitted_axis(&plane);
+
+        assert!(moment.start < 0.427 * 558.0 && moment.end > 0.523 * 750.0);
+        assert!(mass.start < 558.0 && mass.end > 750.0);
+        assert!(
+            weight_and_balance_chart(&plane, WeightBalanceChartVisualization::default()).is_ok()
+        );
+    }
+
+    #[test]
+    fn chart_series_as_plain_data() {
+        let plane = airplane().with_fuel_consumption_diversion(Volume::Liter(10.0));
+        let data = chart_series_data(&plane).unwrap();
+
+        assert_eq!("PH-DHA", data.caption());
+        assert_eq!("Mass [kg]", data.y_label());
+        assert_eq!(&fitted_axis(&plane).0, data.x_axis());
+        assert_eq!(&corners(plane.limits())[..], data.envelope().points());
+        assert_eq!("750 kg / 0.523 m", data.corners()[2].label());
+        assert_eq!(plane.total_mass().kilo(), data.takeoff().mass());
+        assert_eq!(plane.within_limits(), data.takeoff().within_limits());
+        assert_eq!(
+            plane.total_mass_landing().unwrap().kilo(),
+            data.landing().unwrap().mass()
+        );
+        assert!(data.alternate_landing().is_some());
+        assert!(chart_series_data(&airplane())
+            .unwrap()
+            .alternate_landing()
+            .is_none());
+    }
+
+    #[test]
+    fn chart_with_alternate_landing() {
+        let Visualization::Svg(svg) =
+            weight_and_balance_chart(&airplane(), WeightBalanceChartVisualization::default())
+                .unwrap();
+        assert!(!svg.contains("Alternate Landing Point"));
+
+        let plane = airplane().with_fuel_consumption_diversion(Volume::Liter(10.0));
+        let Visualization::Svg(svg) =
+            weight_and_balance_chart(&plane, WeightBalanceChartVisualization::default()).unwrap();
+        assert!(svg.contains("Alternate Landing Point"));
+    }
+
+    #[test]
+    fn chart_with_policy_limits() {
+        let mut plane = airplane();
+        plane.add_limits(
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(700.0),
+                CenterOfGravity::Millimeter(440.0),
+                CenterOfGravity::Millimeter(510.0),
+            )
+            .with_kind(LimitKind::Policy),
+        );
+
+        let Visualization::Svg(svg) =
+            weight_and_balance_chart(&plane, WeightBalanceChartVisualization::default()).unwrap();
+        assert!(svg.contains("Policy Limits"));
+    }
+
+    #[test]
+    fn chart_axis_ticks_and_units() {
+        let visualization = WeightBalanceChartVisualization::default()
+            .with_axis((190.0..410.0, 540.0..760.0))
+            .with_x_axis(ChartAxis::new("kg·m".to_string()).with_tick_interval(12.5))
+            .with_y_axis(
+                ChartAxis::new("kg".to_string())
+                    .with_tick_interval(25.0)
+                    .with_unit_on_ticks(true),
+            );
+        let Visualization::Svg(svg) = weight_and_balance_chart(&airplane(), visualization).unwrap();
+
+        assert!(svg.contains("Mass Moment [kg·m]"));
+        assert!(svg.contains("\n200.0\n") && svg.contains("\n212.5\n"));
+        assert_eq!(
+            (550..=750).step_by(25).count(),
+            svg.matches(" kg\n").count()
+        );
+        assert!(svg.contains("\n575 kg\n"));
+
+        let axis = ChartAxis::new("kg".to_string()).with_tick_interval(0.0);
+        assert_eq!(None, axis.interval(&(0.0..10.0)));
+        assert_eq!("3", axis.label(&(0.0..10.0), 2.6));
+    }
+
+    #[test]
+    fn accessible_svg() {
+        let plane = airplane();
+        let Visualization::Svg(svg) =
+            weight_and_balance_chart(&plane, WeightBalanceChartVisualization::default()).unwrap();
+        assert!(svg.contains("role=\"graphics-document\">\n<title>Weight and balance of PH-DHA"));
+        assert!(svg.contains("</title>\n<desc>Take-off mass 636.6 kg"));
+        for series in [
+            "Axes",
+            "CG Envelope",
+            "Take-off Point",
+            "Landing Point",
+            "Legend",
+        ] {
+            assert!(svg.contains(&format!("<g role=\"group\" aria-label=\"{}\">", series)));
+        }
+        assert_eq!(svg.matches("<g ").count(), svg.matches("</g>").count());
+        assert!(svg.ends_with("</g>\n</svg>\n"));
+
+        let Visualization::Svg(svg) =
+            weight_and_balance_table(&plane, WeightBalanceTableVisualization::default()).unwrap();
+        assert!(svg.contains("role=\"table\""));
+        assert_eq!(
+            plane.iter().count() + 2,
+            svg.matches("<g role=\"row\"").count()
+        );
+        assert!(svg.contains("<g role=\"row\" aria-label=\"Pilot\">"));
+    }
+
+    // Labels that look like the markup of the SVG are escaped text, they do not open or close
+    // groups
+    #[test]
+    fn groups_with_markup_labels() {
+        let label =
+            r##"<rect x="0" y="0" width="1" height="1" opacity="0.25" fill="#010203"/></g></svg>"##;
+        let mut moments: Vec<Moment> = airplane().iter().cloned().collect();
+        moments.insert(
+            2,
+            Moment::new(label.to_string(), LeverArm::Meter(0.515), Mass::Kilo(1.0)),
+        );
+        let plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            moments,
+            airplane().limits().clone(),
+            Volume::Liter(17.0),
+        );
+        let groups = |svg: &str| -> Vec<String> {
+            crate::xml::elements(svg)
+                .unwrap()
+                .into_iter()
+                .filter(|element| element.name == "g")
+                .filter_map(|element| element.attribute("aria-label").map(str::to_string))
+                .collect()
+        };
+
+        let Visualization::Svg(svg) =
+            weight_and_balance_table(&plane, WeightBalanceTableVisualization::default()).unwrap();
+        assert_eq!(
+            vec!["Name", "Empty", "Pilot", label, "Fuel", "Total", "Grid"],
+            groups(&svg)
+        );
+        assert_eq!(1, svg.matches("</svg>").count());
+
+        let visualization = WeightBalanceChartVisualization::default()
+            .with_x_axis(ChartAxis::new(label.to_string()))
+            .with_deterministic(true);
+        let Visualization::Svg(svg) = weight_and_balance_chart(&plane, visualization).unwrap();
+        assert_eq!(
+            vec![
+                "Axes",
+                "CG Envelope",
+                "Take-off Point",
+                "Landing Point",
+                "Legend"
+            ],
+            groups(&svg)
+        );
+        assert_eq!(svg.matches("<g ").count(), svg.matches("</g>").count());
+        assert!(svg.ends_with("</g>\n</svg>\n"));
+    }
+
+    #[test]
+    fn deterministic_svg() {
+        let plane = airplane();
+        let visualization = WeightBalanceChartVisualization::default().with_deterministic(true);
+        let Visualization::Svg(svg) =
+            weight_and_balance_chart(&plane, visualization.clone()).unwrap();
+        let Visualization::Svg(again) = weight_and_balance_chart(&plane, visualization).unwrap();
+        assert_eq!(svg, again);
+        assert!(svg.contains("Take-off Point"));
+        assert!(!svg
+            .lines()
+            .filter(|line| line.starts_with('<'))
+            .any(|line| line.contains(".129")));
+        for element in crate::xml::elements(&svg).unwrap() {
+            for (name, value) in &element.attributes {
+                let decimals = value.parse::<f64>().ok().and(value.split_once('.'));
+                assert!(
+                    decimals.is_none_or(|(_, decimals)| decimals.len() <= 2),
+                    "{}=\"{}\"",
+                    name,
+                    value
+                );
+            }
+        }
+
+        // Text is measured without the fonts, so the layout differs from the default one
+        let Visualization::Svg(default) =
+            weight_and_balance_chart(&plane, WeightBalanceChartVisualization::default()).unwrap();
+        assert_ne!(svg, default);
+
+        // The cell values of a table are rounded like the coordinates
+        let plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![
+                airplane().iter().next().unwrap().clone(),
+                Moment::new(
+                    "Pilot".to_string(),
+                    LeverArm::Meter(0.515),
+                    Mass::Kilo(0.1 + 0.2),
+                ),
+            ],
+            airplane().limits().clone(),
+            Volume::Liter(17.0),
+        );
+        let table = |deterministic| {
+            let visualization = WeightBalanceTableVisualization::default()
+                .with_cell_titles(true)
+                .with_deterministic(deterministic);
+            let Visualization::Svg(svg) = weight_and_balance_table(&plane, visualization).unwrap();
+            svg
+        };
+        assert!(table(false).contains("<title>0.30000000000000004 kg</title>"));
+        let svg = table(true);
+        assert!(svg.contains("<g data-value=\"0.3\" data-unit=\"kg\"><title>0.3 kg</title>"));
+        assert!(svg.contains("<g data-value=\"0.43\" data-unit=\"m\"><title>0.43 m</title>"));
+        assert_eq!(svg, table(true));
+    }
+
+    #[test]
+    fn envelope_corner_labels() {
+        let plane = airplane();
+        let Visualization::Svg(svg) = weight_and_balance_chart(
+            &plane,
+            WeightBalanceChartVisualization::default().with_corner_labels(true),
+        )
+        .unwrap();
+        assert!(svg.contains("<g role=\"group\" aria-label=\"Envelope Corners\">"));
+        for label in [
+            "558 kg / 0.427 m",
+            "558 kg / 0.523 m",
+            "750 kg / 0.523 m",
+            "750 kg / 0.427 m",
+        ] {
+            assert!(svg.contains(&format!("\n{}\n", label)));
+        }
+
+        let Visualization::Svg(svg) =
+            weight_and_balance_chart(&plane, WeightBalanceChartVisualization::default()).unwrap();
+        assert!(!svg.contains("750 kg / 0.523 m"));
+    }
+
+    #[test]
+    fn shaded_outside_limits() {
+        let plane = airplane();
+        let Visualization::Svg(svg) = weight_and_balance_chart(
+            &plane,
+            WeightBalanceChartVisualization::default().with_shade_outside(true),
+        )
+        .unwrap();
+        let start = svg
+            .find("<g role=\"group\" aria-label=\"Outside Limits\">")
+            .unwrap();
+        let group = &svg[start..start + svg[start..].find("</g>").unwrap()];
+        assert_eq!(4, group.matches("<polygon ").count());
+        assert!(svg.contains("\nOutside Limits\n"));
+    }
+
+    #[test]
+    fn imperial_chart() {
+        let plane = airplane();
+        let Visualization::Svg(svg) = weight_and_balance_chart(
+            &plane,
+            WeightBalanceChartVisualization::default()
+                .with_units(ChartUnits::Imperial)
+                .with_corner_labels(true),
+        )
+        .unwrap();
+        assert!(svg.contains("Mass Moment [lb in]"));
+        assert!(svg.contains("Mass [lb]"));
+        assert!(svg.contains("\n1653 lb / 20.59 in\n"));
+
+        let (moment, mass) = fitted_axis(&plane);
+        assert_eq!(
+            (
+                MassMoment::KgM(moment.end).lb_in(),
+                Mass::Kilo(mass.end).pound()
+            ),
+            ChartUnits::Imperial.point((moment.end, mass.end))
+        );
+    }
+
+    #[test]
+    fn compact_chart() {
+        let plane = airplane();
+        let Visualization::Svg(svg) =
+            weight_and_balance_chart(&plane, WeightBalanceChartVisualization::compact()).unwrap();
+        assert!(svg.contains("width=\"300\" height=\"300\""));
+        assert!(svg.contains("\nMoment [kg m]\n"));
+        assert!(!svg.contains("aria-label=\"Legend\""));
+        assert!(!svg.contains("font-size=\"50"));
+    }
+
+    #[test]
+    fn table_columns() {
+        let plane = airplane();
+        let share = TableColumn::new("Share [%]".to_string(), |m: &Moment| {
+            format!("{:.0}", m.mass().kilo() / 750.0 * 100.0)
+        })
+        .with_total(|plane: &Airplane| format!("{:.0}", plane.total_mass().kilo() / 750.0 * 100.0));
+        let code = TableColumn::new("Code".to_string(), |m: &Moment| {
+            m.name()[..1].to_uppercase()
+        });
+
+        let strings = weight_and_balance_table_strings_with_columns(
+            &plane,
+            TableUnits::Metric,
+            &Locale::english(),
+            &[share.clone(), code.clone()],
+        );
+        assert_eq!(vec!["Share [%]", "Code"], strings[0][4..]);
+        assert_eq!(vec!["69", "E"], strings[1][4..]);
+        assert_eq!(vec!["85", ""], strings.last().unwrap()[4..]);
+        assert_eq!(4, weight_and_balance_table_strings(&plane)[0].len());
+
+        let visualization = WeightBalanceTableVisualization::default().with_column(share);
+        assert_eq!(visualization.clone(), visualization);
+        let Visualization::Svg(svg) = weight_and_balance_table(&plane, visualization).unwrap();
+        assert!(svg.contains("\nShare [%]\n"));
+        assert!(svg.contains("\n85\n"));
+    }
+
+    #[test]
+    fn cg_sensitivity_column() {
+        let plane = airplane();
+        let sensitivity = plane.cg_sensitivity_per_station();
+        assert_eq!("Pilot", sensitivity[1].0);
+        let cg = plane.center_of_gravity().meter();
+        assert!((sensitivity[1].1 - (0.515 - cg) / 636.6 * 1000.0).abs() < 1e-9);
+        assert!(sensitivity[2].1 < 0.0);
+
+        // Adding a kilogram at a station moves the CG by about its sensitivity
+        let mut heavier = plane.clone();
+        heavier.add_moment(Moment::new(
+            "Extra".to_string(),
+            LeverArm::Meter(0.515),
+            Mass::Kilo(1.0),
+        ));
+        let shift = (heavier.center_of_gravity().meter() - cg) * 1000.0;
+        assert!((shift - sensitivity[1].1).abs() < 1e-3);
+
+        let locale = Locale::english();
+        let strings = weight_and_balance_table_strings_with_columns(
+            &plane,
+            TableUnits::Metric,
+            &locale,
+            &[TableColumn::cg_sensitivity(&plane, &locale)],
+        );
+        assert_eq!("CG [mm/kg]", strings[0][4]);
+        assert_eq!(format!("+{:.2}", sensitivity[1].1), strings[2][4]);
+        assert_eq!(format!("{:.2}", sensitivity[2].1), strings[3][4]);
+        assert_eq!("", strings[4][4]);
+    }
+
+    #[test]
+    fn table_cell_titles() {
+        let plane = airplane();
+        let Visualization::Svg(svg) = weight_and_balance_table(
+            &plane,
+            WeightBalanceTableVisualization::default().with_cell_titles(true),
+        )
+        .unwrap();
+        assert!(svg.contains("<g data-value=\"0.4294\" data-unit=\"m\"><title>0.4294 m</title>"));
+        let moments = plane.iter().count() + 1;
+        assert_eq!(3 * moments, svg.matches("<g data-value=").count());
+        assert_eq!(svg.matches("<g ").count(), svg.matches("</g>").count());
+
+        let Visualization::Svg(svg) =
+            weight_and_balance_table(&plane, WeightBalanceTableVisualization::default()).unwrap();
+        assert!(!svg.contains("data-value"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn summary_in_svg() {
+        let plane = airplane();
+        let Visualization::Svg(svg) = weight_and_balance_chart(
+            &plane,
+            WeightBalanceChartVisualization::default().with_embedded_summary(true),
+        )
+        .unwrap();
+        assert!(svg.contains("</desc>\n<metadata id=\"weight-and-balance-summary\">"));
+        assert_eq!(Some(plane.summary()), embedded_summary(&svg).unwrap());
+
+        let Visualization::Svg(svg) = weight_and_balance_table(
+            &plane,
+            WeightBalanceTableVisualization::default().with_embedded_summary(true),
+        )
+        .unwrap();
+        assert_eq!(Some(plane.summary()), embedded_summary(&svg).unwrap());
+
+        let Visualization::Svg(svg) =
+            weight_and_balance_chart(&plane, WeightBalanceChartVisualization::default()).unwrap();
+        assert_eq!(None, embedded_summary(&svg).unwrap());
+        assert!(embedded_summary(&format!("{}{{}}{}", SUMMARY_START, SUMMARY_END)).is_err());
+    }
+
+    #[test]
+    fn trend_of_flights() {
+        let light = airplane().summary();
+        let mut plane = airplane();
+        plane.add_moment(Moment::new(
+            "Baggage".to_string(),
+            LeverArm::Meter(0.5),
+            Mass::Kilo(110.0),
+        ));
+        let heavy = plane.summary();
+
+        let Visualization::Svg(svg) = trend_chart(
+            &[light.clone(), heavy, light],
+            PerformanceChartVisualization::default(),
+        )
+        .unwrap();
+        assert!(svg.contains("Landing Mass"));
+        assert!(svg.contains("MTOW"));
+        assert_eq!(1, svg.matches(r##"fill="#FF0000""##).count());
+
+        assert!(trend_chart(&[], PerformanceChartVisualization::default()).is_ok());
+    }
+
+    #[test]
+    fn svg_into_buffer_and_writer() {
+        let plane = airplane();
+        let Visualization::Svg(svg) =
+            weight_and_balance_chart(&plane, WeightBalanceChartVisualization::default()).unwrap();
+
+        let mut buf = String::new();
+        weight_and_balance_chart_into(&plane, WeightBalanceChartVisualization::default(), &mut buf)
+            .unwrap();
+        assert_eq!(svg, buf);
+
+        let mut out: Vec<u8> = Vec::new();
+        write_weight_and_balance_table(
+            &plane,
+            WeightBalanceTableVisualization::default(),
+            &mut out,
+        )
+        .unwrap();
+        weight_and_balance_table_into(&plane, WeightBalanceTableVisualization::default(), &mut buf)
+            .unwrap();
+        assert!(buf.ends_with(std::str::from_utf8(&out).unwrap()));
+
+        // The SVG is written while it is drawn, the drawing stops at the error of the output
+        struct Failing(usize);
+        impl io::Write for Failing {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0 += 1;
+                match self.0 {
+                    1..=3 => Ok(buf.len()),
+                    _ => Err(io::Error::other("disk full")),
+                }
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let mut failing = Failing(0);
+        let error = write_weight_and_balance_chart(
+            &plane,
+            WeightBalanceChartVisualization::default(),
+            &mut failing,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("disk full"), "{}", error);
+        assert_eq!(4, failing.0);
+    }
+
+    #[test]
+    fn climb_chart_svg() {
+        let climb = ClimbPerformance::new(700.0, Mass::Kilo(750.0), 14000.0, 65.0);
+        let Visualization::Svg(svg) = climb_chart(
+            &airplane(),
+            &climb,
+            PerformanceChartVisualization::default(),
+        )
+        .unwrap();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("Take-off Mass"));
+    }
+
+    #[test]
+    fn takeoff_landing_chart_svg() {
+        let table = performance::PerformanceTable::new(
+            vec![0.0, 4000.0],
+            vec![-10.0, 30.0],
+            vec![600.0, 750.0],
+            vec![300.0, 340.0, 360.0, 410.0, 380.0, 430.0, 450.0, 520.0],
+        )
+        .unwrap();
+        let conditions = Conditions::new(0.0, 15.0, 5.0, performance::RunwayCondition::PavedDry);
+
+        let Visualization::Svg(svg) = takeoff_landing_chart(
+            &airplane(),
+            &DistancePerformance::takeoff(table.clone()),
+            &DistancePerformance::landing(table),
+            &conditions,
+            PerformanceChartVisualization::default(),
+        )
+        .unwrap();
+
+        assert!(svg.contains("Take-off Distance"));
+        assert!(svg.contains("Landing Distance"));
+    }
+
+    #[test]
+    fn feasibility_table_svg() {
+        let matrix = crate::feasibility::Feasibility::new(
+            LeverArm::Meter(0.515),
+            LeverArm::Meter(0.325),
+            LeverArm::Meter(1.3),
+        )
+        .with_occupant_masses(vec![Mass::Kilo(80.0), Mass::Kilo(250.0)])
+        .with_fuel_states(vec![Mass::Avgas(Volume::Liter(40.0))])
+        .matrix(&airplane());
+
+        let Visualization::Svg(svg) =
+            feasibility_table(&matrix, PerformanceChartVisualization::default()).unwrap();
+
+        assert!(svg.contains("40.00L"));
+    }
+}
@@ -0,0 +1,195 @@
+//! `wasm-bindgen` bindings for client side weight and balance calculators.
+//!
+//! The bindings take plain numbers in kilograms, meters and liters. Build the crate with
+//! `wasm-pack build --features wasm` from a `cdylib` crate depending on this one:
+//!
+//! ```js
+//! const plane = new Airplane("PHDHA", 558, 750, 0.427, 0.523, 17);
+//! plane.addMoment("Empty", 0.4294, 517);
+//! plane.addMoment("Pilot", 0.515, 80);
+//! plane.addFuel("Fuel", 0.325, 40, "avgas");
+//! plane.withinLimits();
+//! document.body.innerHTML = plane.chartSvg(600, 600);
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::registration::Registration;
+use crate::visualizer::{
+    weight_and_balance_chart, weight_and_balance_table, Visualization,
+    WeightBalanceChartVisualization, WeightBalanceTableVisualization,
+};
+use crate::weight_and_balance::{
+    Airplane, CenterOfGravity, LeverArm, Limits, Mass, Moment, Volume,
+};
+
+#[wasm_bindgen(js_name = Airplane)]
+pub struct WasmAirplane {
+    plane: Airplane,
+}
+
+fn svg(visualization: Visualization) -> String {
+    match visualization {
+        Visualization::Svg(svg) => svg,
+    }
+}
+
+#[wasm_bindgen(js_class = Airplane)]
+impl WasmAirplane {
+    /// Throws when `registration` is not a valid registration.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        registration: &str,
+        minimum_weight_kg: f64,
+        mtow_kg: f64,
+        forward_cg_m: f64,
+        rearward_cg_m: f64,
+        trip_fuel_liter: f64,
+    ) -> Result<WasmAirplane, JsError> {
+        let registration =
+            Registration::parse(registration).map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(WasmAirplane {
+            plane: Airplane::new(
+                registration,
+                vec![],
+                Limits::new(
+                    Mass::Kilo(minimum_weight_kg),
+                    Mass::Kilo(mtow_kg),
+                    CenterOfGravity::Meter(forward_cg_m),
+                    CenterOfGravity::Meter(rearward_cg_m),
+                ),
+                Volume::Liter(trip_fuel_liter),
+            ),
+        })
+    }
+
+    #[wasm_bindgen(js_name = addMoment)]
+    pub fn add_moment(&mut self, name: String, arm_m: f64, mass_kg: f64) {
+        self.plane.add_moment(Moment::new(
+            name,
+            LeverArm::Meter(arm_m),
+            Mass::Kilo(mass_kg),
+        ));
+    }
+
+    /// `fuel` is `"avgas"` or `"mogas"`. Add the fuel last for the landing figures.
+    #[wasm_bindgen(js_name = addFuel)]
+    pub fn add_fuel(
+        &mut self,
+        name: String,
+        arm_m: f64,
+        liter: f64,
+        fuel: &str,
+    ) -> Result<(), JsError> {
+        let mass = match fuel.to_ascii_lowercase().as_str() {
+            "avgas" => Mass::Avgas(Volume::Liter(liter)),
+            "mogas" => Mass::Mogas(Volume::Liter(liter)),
+            _ => return Err(JsError::new(&format!("unknown fuel {}", fuel))),
+        };
+
+        self.plane
+            .add_moment(Moment::new(name, LeverArm::Meter(arm_m), mass));
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = withinLimits)]
+    pub fn within_limits(&self) -> bool {
+        self.plane.within_limits()
+    }
+
+    #[wasm_bindgen(js_name = totalMassKg)]
+    pub fn total_mass_kg(&self) -> f64 {
+        self.plane.total_mass().kilo()
+    }
+
+    #[wasm_bindgen(js_name = centerOfGravityM)]
+    pub fn center_of_gravity_m(&self) -> f64 {
+        self.plane.summary().takeoff_center_of_gravity().meter()
+    }
+
+    /// The axes are the mass moment in kg m and the mass in kilograms, fitted to the envelope and
+    /// the take-off and landing points. Needs the fuel as the last moment for the landing point.
+    #[wasm_bindgen(js_name = chartSvg)]
+    pub fn chart_svg(&self, width: u32, height: u32) -> Result<String, JsError> {
+        Ok(svg(weight_and_balance_chart(
+            &self.plane,
+            WeightBalanceChartVisualization::default().with_dimensions((width, height)),
+        )?))
+    }
+
+    /// Grows the canvas when the table does not fit the requested size.
+    #[wasm_bindgen(js_name = tableSvg)]
+    pub fn table_svg(&self, width: u32, height: u32) -> Result<String, JsError> {
+        Ok(svg(weight_and_balance_table(
+            &self.plane,
+            WeightBalanceTableVisualization::new((width, height)).with_auto_grow(true),
+        )?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::xml::{self, Node};
+
+    // The circle centers and polygon corners of the chart, by the label of their group
+    fn shapes(svg: &str) -> Vec<(String, f64, f64)> {
+        let mut groups: Vec<String> = vec![];
+        let mut shapes = vec![];
+        for node in xml::nodes(svg).unwrap() {
+            let element = match node {
+                Node::Start(element) => element,
+                Node::End(name) if name == "g" => {
+                    groups.pop();
+                    continue;
+                }
+                _ => continue,
+            };
+            let group = groups.last().cloned().unwrap_or_default();
+            match element.name.as_str() {
+                "g" => groups.push(
+                    element
+                        .attribute("aria-label")
+                        .unwrap_or(&group)
+                        .to_string(),
+                ),
+                "circle" => {
+                    let coordinate =
+                        |name| -> f64 { element.attribute(name).unwrap().parse().unwrap() };
+                    shapes.push((group, coordinate("cx"), coordinate("cy")));
+                }
+                "polygon" => {
+                    for point in element.attribute("points").unwrap().split_whitespace() {
+                        let (x, y) = point.split_once(',').unwrap();
+                        shapes.push((group.clone(), x.parse().unwrap(), y.parse().unwrap()));
+                    }
+                }
+                _ => {}
+            }
+        }
+        shapes
+    }
+
+    #[test]
+    fn calculator() {
+        let mut plane = WasmAirplane::new("PHDHA", 558.0, 750.0, 0.427, 0.523, 17.0).unwrap();
+        plane.add_moment("Empty".to_string(), 0.4294, 517.0);
+        plane.add_moment("Pilot".to_string(), 0.515, 80.0);
+        assert!(plane
+            .add_fuel("Fuel".to_string(), 0.325, 40.0, "Avgas")
+            .is_ok());
+
+        assert!(plane.within_limits());
+        assert!((plane.total_mass_kg() - 625.8).abs() < 0.01);
+
+        let shapes = shapes(&plane.chart_svg(600, 600).unwrap());
+        for label in ["CG Envelope", "Take-off Point", "Landing Point"] {
+            let points: Vec<_> = shapes.iter().filter(|s| s.0 == label).collect();
+            assert!(!points.is_empty(), "no {}", label);
+            for (_, x, y) in points {
+                assert!((0.0..=600.0).contains(x) && (0.0..=600.0).contains(y));
+            }
+        }
+        assert!(plane.table_svg(10, 10).unwrap().contains("Pilot"));
+    }
+}
@@ -0,0 +1,251 @@
+//! Empty mass and center of gravity from the scale readings of a weighing.
+//!
+//! A weighing report is a CSV file with one scale position per line. Arms are in meters
+//! (`arm_m`) or inches (`arm_in`), readings and the optional tare of chocks and blocks in
+//! kilograms (`_kg`) or pounds (`_lb`):
+//!
+//! ```text
+//! # PH-DHA weighing 2024-03-12
+//! position,arm_m,reading_kg,tare_kg
+//! Nose wheel,-0.612,128.5,1.2
+//! Left main,0.788,196.0,0.0
+//! Right main,0.788,194.5,0.0
+//! ```
+//!
+//! Lines starting with `#` and empty lines are ignored.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::weight_and_balance::{CenterOfGravity, LeverArm, Mass, Moment};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WeighingError {
+    NoReadings,
+    MissingColumn(&'static str),
+    /// The line number (1-based) and the value that is not a number.
+    InvalidNumber(usize, String),
+    /// The line number (1-based) of a line with fewer fields than the header.
+    MissingField(usize),
+}
+
+impl fmt::Display for WeighingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeighingError::NoReadings => write!(f, "weighing report has no readings"),
+            WeighingError::MissingColumn(column) => {
+                write!(f, "weighing report has no {} column", column)
+            }
+            WeighingError::InvalidNumber(line, value) => {
+                write!(f, "line {}: {} is not a number", line, value)
+            }
+            WeighingError::MissingField(line) => write!(f, "line {}: missing field", line),
+        }
+    }
+}
+
+impl core::error::Error for WeighingError {}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScaleReading {
+    position: String,
+    lever_arm: LeverArm,
+    reading: Mass,
+    tare: Mass,
+}
+
+impl ScaleReading {
+    pub fn new(position: String, lever_arm: LeverArm, reading: Mass, tare: Mass) -> ScaleReading {
+        ScaleReading {
+            position,
+            lever_arm,
+            reading,
+            tare,
+        }
+    }
+
+    pub fn position(&self) -> &str {
+        &self.position
+    }
+
+    pub fn lever_arm(&self) -> &LeverArm {
+        &self.lever_arm
+    }
+
+    pub fn reading(&self) -> &Mass {
+        &self.reading
+    }
+
+    pub fn tare(&self) -> &Mass {
+        &self.tare
+    }
+
+    pub fn net(&self) -> Mass {
+        Mass::Kilo(self.reading.kilo() - self.tare.kilo())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Weighing {
+    readings: Vec<ScaleReading>,
+}
+
+impl Weighing {
+    pub fn new() -> Weighing {
+        Weighing::default()
+    }
+
+    pub fn add_reading(&mut self, reading: ScaleReading) {
+        self.readings.push(reading);
+    }
+
+    pub fn readings(&self) -> &Vec<ScaleReading> {
+        &self.readings
+    }
+
+    pub fn empty_mass(&self) -> Mass {
+        Mass::Kilo(self.readings.iter().map(|r| r.net().kilo()).sum())
+    }
+
+    pub fn center_of_gravity(&self) -> Result<CenterOfGravity, WeighingError> {
+        let mass = self.empty_mass().kilo();
+        if self.readings.is_empty() || mass == 0.0 {
+            return Err(WeighingError::NoReadings);
+        }
+
+        let moment: f64 = self
+            .readings
+            .iter()
+            .map(|r| r.net().kilo() * r.lever_arm().meter())
+            .sum();
+        Ok(CenterOfGravity::Meter(moment / mass))
+    }
+
+    /// The empty aircraft moment for [`Airplane::new`](crate::weight_and_balance::Airplane::new).
+    pub fn moment(&self, name: String) -> Result<Moment, WeighingError> {
+        let cg = self.center_of_gravity()?;
+        Ok(Moment::new(
+            name,
+            LeverArm::Meter(cg.meter()),
+            self.empty_mass(),
+        ))
+    }
+
+    pub fn from_csv(csv: &str) -> Result<Weighing, WeighingError> {
+        let mut lines = csv
+            .lines()
+            .enumerate()
+            .map(|(i, l)| (i + 1, l.trim()))
+            .filter(|(_, l)| !l.is_empty() && !l.starts_with('#'));
+
+        let (_, header) = lines.next().ok_or(WeighingError::NoReadings)?;
+        let header: Vec<String> = fields(header).map(|h| h.to_ascii_lowercase()).collect();
+        let column = |names: &[&str]| header.iter().position(|h| names.contains(&h.as_str()));
+
+        let position = column(&["position"]).ok_or(WeighingError::MissingColumn("position"))?;
+        let arm = column(&["arm_m", "arm_in"]).ok_or(WeighingError::MissingColumn("arm"))?;
+        let reading =
+            column(&["reading_kg", "reading_lb"]).ok_or(WeighingError::MissingColumn("reading"))?;
+        let tare = column(&["tare_kg", "tare_lb"]);
+
+        let inch = header[arm] == "arm_in";
+        let mass = |column: usize, value: f64| {
+            if header[column].ends_with("_lb") {
+                Mass::from_pound(value)
+            } else {
+                Mass::Kilo(value)
+            }
+        };
+
+        let mut weighing = Weighing::new();
+        for (line, l) in lines {
+            let values: Vec<&str> = fields(l).collect();
+            let value = |column: usize| values.get(column).ok_or(WeighingError::MissingField(line));
+            let number = |column: usize| {
+                let v = value(column)?;
+                v.parse::<f64>()
+                    .map_err(|_| WeighingError::InvalidNumber(line, v.to_string()))
+            };
+
+            let lever_arm = if inch {
+                LeverArm::from_inch(number(arm)?)
+            } else {
+                LeverArm::Meter(number(arm)?)
+            };
+            let tare = match tare {
+                Some(column) => mass(column, number(column)?),
+                None => Mass::Kilo(0.0),
+            };
+
+            weighing.add_reading(ScaleReading::new(
+                value(position)?.to_string(),
+                lever_arm,
+                mass(reading, number(reading)?),
+                tare,
+            ));
+        }
+
+        if weighing.readings.is_empty() {
+            return Err(WeighingError::NoReadings);
+        }
+        Ok(weighing)
+    }
+}
+
+// Fields are separated by commas, a field may be wrapped in double quotes
+fn fields(line: &str) -> impl Iterator<Item = &str> {
+    line.split(',').map(|f| f.trim().trim_matches('"'))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn empty_weight_from_csv() {
+        let weighing = Weighing::from_csv(
+            "# PH-DHA weighing
+position,arm_m,reading_kg,tare_kg
+Nose wheel,-0.612,128.5,1.2
+Left main,0.788,196.0,0.0
+\"Right main\",0.788,194.5,0.0
+",
+        )
+        .unwrap();
+
+        assert_eq!(3, weighing.readings().len());
+        assert!((weighing.empty_mass().kilo() - 517.8).abs() < 1e-9);
+
+        let expected = (127.3 * -0.612 + 390.5 * 0.788) / 517.8;
+        let cg = weighing.center_of_gravity().unwrap();
+        assert!((cg.meter() - expected).abs() < 1e-9);
+        assert_eq!("Right main", weighing.readings()[2].position());
+
+        let moment = weighing.moment("Empty".to_string()).unwrap();
+        assert!((moment.lever_arm().meter() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn imperial_and_invalid_reports() {
+        let weighing =
+            Weighing::from_csv("position,arm_in,reading_lb\nNose,40,200\nMains,80,800\n").unwrap();
+        assert!((weighing.empty_mass().pound() - 1000.0).abs() < 1e-9);
+        assert!((weighing.center_of_gravity().unwrap().inch() - 72.0).abs() < 1e-9);
+
+        assert_eq!(
+            Some(WeighingError::MissingColumn("reading")),
+            Weighing::from_csv("position,arm_m\nNose,1.0\n").err()
+        );
+        assert_eq!(
+            Some(WeighingError::InvalidNumber(2, "x".to_string())),
+            Weighing::from_csv("position,arm_m,reading_kg\nNose,1.0,x\n").err()
+        );
+        assert_eq!(
+            Some(WeighingError::NoReadings),
+            Weighing::from_csv("position,arm_m,reading_kg\n").err()
+        );
+    }
+}
@@ -1,3 +1,13 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::error::WeightBalanceError;
+use crate::journal::{Change, Journal, Observers};
+use crate::registration::Registration;
 use crate::types::{FuelType, VolumeType};
 
 const AVGAS_FUEL_DENSITY_KG_LITER: f64 = 0.72;
@@ -5,20 +15,51 @@ const MOGAS_FUEL_DENSITY_KG_LITER: f64 = 0.74;
 
 const LITERS_IN_GALLON: f64 = 378541.0 / 100000.0;
 
-#[derive(Clone)]
+const KILOS_IN_POUND: f64 = 0.45359237;
+const METERS_IN_INCH: f64 = 0.0254;
+
+// Arms closer than this are the same station
+const ARM_TOLERANCE_METER: f64 = 1e-9;
+
+// Volumes this close to a whole number of increments are that number, the float error of a
+// conversion between liters and gallons
+const INCREMENT_TOLERANCE: f64 = 1e-9;
+
+// Trip fuel this small left after the tanks run dry is float error
+const FUEL_TOLERANCE_LITER: f64 = 1e-9;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum LeverArm {
     Meter(f64),
 }
 
 impl LeverArm {
+    pub fn from_inch(inch: f64) -> LeverArm {
+        LeverArm::Meter(inch * METERS_IN_INCH)
+    }
+
     pub fn meter(&self) -> f64 {
         match self {
             LeverArm::Meter(m) => *m,
         }
     }
+
+    pub fn inch(&self) -> f64 {
+        self.meter() / METERS_IN_INCH
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum Volume {
     Liter(f64),
     Gallon(f64),
@@ -39,15 +80,60 @@ impl Volume {
         }
     }
 
-    pub fn to_string(&self) -> String {
+    /// The volume rounded up to a whole number of `increment`, in the unit of the increment, e.g.
+    /// 23.37 L in increments of 0.5 gal is 6.5 gal. An increment that is not positive leaves the
+    /// volume as it is.
+    pub fn rounded_up_to(&self, increment: &Volume) -> Volume {
+        self.rounded_to(increment, true)
+    }
+
+    /// Like [`Volume::rounded_up_to`], rounded down.
+    pub fn rounded_down_to(&self, increment: &Volume) -> Volume {
+        self.rounded_to(increment, false)
+    }
+
+    // Without std's f64::ceil and f64::floor
+    fn rounded_to(&self, increment: &Volume, up: bool) -> Volume {
+        let size = increment.to_liter();
+        if size.is_nan() || size <= 0.0 {
+            return self.clone();
+        }
+
+        let ratio = self.to_liter() / size;
+        let truncated = ratio as i64 as f64;
+        let nearest = (ratio + if ratio < 0.0 { -0.5 } else { 0.5 }) as i64 as f64;
+        let increments = if (ratio - nearest).abs() < INCREMENT_TOLERANCE {
+            nearest
+        } else if up && ratio > truncated {
+            truncated + 1.0
+        } else if !up && ratio < truncated {
+            truncated - 1.0
+        } else {
+            truncated
+        };
+
+        match increment {
+            Volume::Liter(l) => Volume::Liter(increments * l),
+            Volume::Gallon(g) => Volume::Gallon(increments * g),
+        }
+    }
+}
+
+impl fmt::Display for Volume {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Volume::Liter(v) => format!("{:.2}L", v),
-            Volume::Gallon(v) => format!("{:.2}gal", v),
+            Volume::Liter(v) => write!(f, "{:.2}L", v),
+            Volume::Gallon(v) => write!(f, "{:.2}gal", v),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum Mass {
     Kilo(f64),
     Avgas(Volume),
@@ -55,6 +141,10 @@ pub enum Mass {
 }
 
 impl Mass {
+    pub fn from_pound(pound: f64) -> Mass {
+        Mass::Kilo(pound * KILOS_IN_POUND)
+    }
+
     pub fn kilo(&self) -> f64 {
         match self {
             Mass::Kilo(kg) => *kg,
@@ -69,6 +159,10 @@ impl Mass {
         }
     }
 
+    pub fn pound(&self) -> f64 {
+        self.kilo() / KILOS_IN_POUND
+    }
+
     pub fn to_avgas(&self) -> Mass {
         let liter = self.kilo() / AVGAS_FUEL_DENSITY_KG_LITER;
         Mass::Avgas(Volume::Liter(liter))
@@ -79,6 +173,29 @@ impl Mass {
         Mass::Mogas(Volume::Liter(liter))
     }
 
+    pub fn is_fuel(&self) -> bool {
+        matches!(self, Mass::Avgas(_) | Mass::Mogas(_))
+    }
+
+    /// The volume of fuel, `None` for a mass in kg.
+    pub fn volume(&self) -> Option<&Volume> {
+        match self {
+            Mass::Kilo(_) => None,
+            Mass::Avgas(v) | Mass::Mogas(v) => Some(v),
+        }
+    }
+
+    /// Fuel density in kg per liter or gallon, following the unit of the fuel volume.
+    pub fn density(&self) -> Option<f64> {
+        match self {
+            Mass::Kilo(_) => None,
+            Mass::Avgas(Volume::Liter(_)) => Some(AVGAS_FUEL_DENSITY_KG_LITER),
+            Mass::Avgas(Volume::Gallon(_)) => Some(AVGAS_FUEL_DENSITY_KG_LITER * LITERS_IN_GALLON),
+            Mass::Mogas(Volume::Liter(_)) => Some(MOGAS_FUEL_DENSITY_KG_LITER),
+            Mass::Mogas(Volume::Gallon(_)) => Some(MOGAS_FUEL_DENSITY_KG_LITER * LITERS_IN_GALLON),
+        }
+    }
+
     pub fn unit(&self) -> String {
         match self {
             Mass::Kilo(_) => "kg".to_string(),
@@ -100,11 +217,22 @@ impl Mass {
     }
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Moment {
     name: String,
     lever_arm: LeverArm,
     mass: Mass,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    note: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    metadata: BTreeMap<String, String>,
 }
 
 impl Moment {
@@ -113,9 +241,31 @@ impl Moment {
             name,
             lever_arm,
             mass,
+            note: None,
+            metadata: BTreeMap::new(),
         }
     }
 
+    /// A free text note, e.g. "child seat installed".
+    pub fn with_note(mut self, note: String) -> Moment {
+        self.note = Some(note);
+        self
+    }
+
+    /// Adds a key and value, e.g. the items of a baggage compartment.
+    pub fn with_metadata(mut self, key: String, value: String) -> Moment {
+        self.metadata.insert(key, value);
+        self
+    }
+
+    pub fn note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
     pub fn lever_arm(&self) -> &LeverArm {
         &self.lever_arm
     }
@@ -125,27 +275,155 @@ impl Moment {
     }
 
     pub fn total(&self) -> MassMoment {
-        MassMoment::KgM(self.mass.kilo() * self.lever_arm.meter())
+        MassMoment::from_mass_and_arm(&self.mass, &self.lever_arm)
     }
 
-    pub fn name(&self) -> &String {
+    pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// A [`MomentBuilder`] that checks the name, lever arm and mass before making the moment.
+    pub fn builder() -> MomentBuilder {
+        MomentBuilder::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MomentError {
+    EmptyName,
+    /// The named moment has no lever arm or mass, the name of the field.
+    Missing(String, &'static str),
+    /// The lever arm of the named moment is not a finite number.
+    InvalidLeverArm(String),
+    /// The mass of the named moment is negative or not a finite number.
+    InvalidMass(String),
+}
+
+impl fmt::Display for MomentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MomentError::EmptyName => write!(f, "moment has no name"),
+            MomentError::Missing(name, field) => write!(f, "moment {} has no {}", name, field),
+            MomentError::InvalidLeverArm(name) => {
+                write!(f, "moment {} has an invalid lever arm", name)
+            }
+            MomentError::InvalidMass(name) => write!(f, "moment {} has an invalid mass", name),
+        }
+    }
+}
+
+impl core::error::Error for MomentError {}
+
+#[derive(Debug, Clone, Default)]
+pub struct MomentBuilder {
+    name: String,
+    lever_arm: Option<LeverArm>,
+    mass: Option<Mass>,
+    note: Option<String>,
+    metadata: BTreeMap<String, String>,
+}
+
+impl MomentBuilder {
+    pub fn name(mut self, name: String) -> MomentBuilder {
+        self.name = name;
+        self
+    }
+
+    pub fn lever_arm(mut self, lever_arm: LeverArm) -> MomentBuilder {
+        self.lever_arm = Some(lever_arm);
+        self
+    }
+
+    pub fn mass(mut self, mass: Mass) -> MomentBuilder {
+        self.mass = Some(mass);
+        self
+    }
+
+    pub fn note(mut self, note: String) -> MomentBuilder {
+        self.note = Some(note);
+        self
+    }
+
+    pub fn metadata(mut self, key: String, value: String) -> MomentBuilder {
+        self.metadata.insert(key, value);
+        self
+    }
+
+    /// Fails on an empty name, a lever arm that is not finite or a mass that is negative or not
+    /// finite.
+    pub fn build(self) -> Result<Moment, MomentError> {
+        if self.name.trim().is_empty() {
+            return Err(MomentError::EmptyName);
+        }
+        let lever_arm = self
+            .lever_arm
+            .ok_or_else(|| MomentError::Missing(self.name.clone(), "lever arm"))?;
+        let mass = self
+            .mass
+            .ok_or_else(|| MomentError::Missing(self.name.clone(), "mass"))?;
+        if !lever_arm.meter().is_finite() {
+            return Err(MomentError::InvalidLeverArm(self.name));
+        }
+        if !mass.kilo().is_finite() || mass.kilo() < 0.0 {
+            return Err(MomentError::InvalidMass(self.name));
+        }
+
+        Ok(Moment {
+            name: self.name,
+            lever_arm,
+            mass,
+            note: self.note,
+            metadata: self.metadata,
+        })
+    }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum MassMoment {
     KgM(f64),
 }
 
 impl MassMoment {
+    /// The moment of `mass` at `arm`, whatever units they were given in.
+    pub fn from_mass_and_arm(mass: &Mass, arm: &LeverArm) -> MassMoment {
+        MassMoment::KgM(mass.kilo() * arm.meter())
+    }
+
+    pub fn from_lb_in(lb_in: f64) -> MassMoment {
+        MassMoment::KgM(lb_in * KILOS_IN_POUND * METERS_IN_INCH)
+    }
+
+    pub fn from_kg_mm(kg_mm: f64) -> MassMoment {
+        MassMoment::KgM(kg_mm / 1000.0)
+    }
+
     pub fn kgm(&self) -> f64 {
         match self {
             MassMoment::KgM(kgm) => *kgm,
         }
     }
+
+    pub fn lb_in(&self) -> f64 {
+        self.kgm() / KILOS_IN_POUND / METERS_IN_INCH
+    }
+
+    pub fn kg_mm(&self) -> f64 {
+        self.kgm() * 1000.0
+    }
 }
 
 /// Positive numbers represent reference aft of datum.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum CenterOfGravity {
     Meter(f64),
     Millimeter(f64),
@@ -158,13 +436,43 @@ impl CenterOfGravity {
             CenterOfGravity::Millimeter(mm) => mm / 1000.0,
         }
     }
+
+    pub fn inch(&self) -> f64 {
+        self.meter() / METERS_IN_INCH
+    }
+}
+
+/// Where a set of limits comes from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum LimitKind {
+    /// The certified envelope of the POH.
+    #[default]
+    Structural,
+    /// Limits of the operator, e.g. of the operations manual.
+    Operational,
+    /// Stricter margins of a club or school on top of the certified envelope.
+    Policy,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Limits {
     minimum_weight: Mass,
     mtow: Mass,
     forward_cg_limit: CenterOfGravity,
     rearward_cg_limit: CenterOfGravity,
+    #[cfg_attr(feature = "serde", serde(default))]
+    kind: LimitKind,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    altitude_mtows: Vec<(f64, Mass)>,
 }
 
 impl Limits {
@@ -179,6 +487,44 @@ impl Limits {
             mtow,
             forward_cg_limit,
             rearward_cg_limit,
+            kind: LimitKind::Structural,
+            altitude_mtows: Vec::new(),
+        }
+    }
+
+    pub fn with_kind(mut self, kind: LimitKind) -> Limits {
+        self.kind = kind;
+        self
+    }
+
+    pub fn kind(&self) -> LimitKind {
+        self.kind
+    }
+
+    /// The MTOW is at most `mtow` at and above `density_altitude_ft`, as on a hot-and-high
+    /// placard. [`Limits::at_density_altitude`] applies it.
+    pub fn with_altitude_mtow(mut self, density_altitude_ft: f64, mtow: Mass) -> Limits {
+        self.altitude_mtows.push((density_altitude_ft, mtow));
+        self
+    }
+
+    /// The density altitudes in feet and the MTOW from there up.
+    pub fn altitude_mtows(&self) -> &Vec<(f64, Mass)> {
+        &self.altitude_mtows
+    }
+
+    /// The limits with the lowest MTOW of the entries at or below `density_altitude_ft`.
+    pub fn at_density_altitude(&self, density_altitude_ft: f64) -> Limits {
+        let mtow = self
+            .altitude_mtows
+            .iter()
+            .filter(|(altitude, _)| *altitude <= density_altitude_ft)
+            .map(|(_, mtow)| mtow.kilo())
+            .fold(self.mtow.kilo(), f64::min);
+
+        Limits {
+            mtow: Mass::Kilo(mtow),
+            ..self.clone()
         }
     }
 
@@ -197,213 +543,1557 @@ impl Limits {
     pub fn rearward_cg_limit(&self) -> &CenterOfGravity {
         &self.rearward_cg_limit
     }
+
+    /// Policy limits inside these, e.g. the buffer of a flight school: the MTOW reduced by
+    /// `mtow_fraction` of it, 0.02 for 2%, and both CG limits moved `cg_margin` inwards.
+    pub fn with_margins(&self, mtow_fraction: f64, cg_margin: &CenterOfGravity) -> Limits {
+        Limits {
+            mtow: Mass::Kilo(self.mtow.kilo() * (1.0 - mtow_fraction)),
+            forward_cg_limit: CenterOfGravity::Meter(
+                self.forward_cg_limit.meter() + cg_margin.meter(),
+            ),
+            rearward_cg_limit: CenterOfGravity::Meter(
+                self.rearward_cg_limit.meter() - cg_margin.meter(),
+            ),
+            kind: LimitKind::Policy,
+            ..self.clone()
+        }
+    }
+
+    /// Whether `mass` is at most the MTOW with its CG `cg` within the CG limits.
+    pub fn contains(&self, mass: &Mass, cg: &CenterOfGravity) -> bool {
+        mass.kilo() <= self.mtow.kilo()
+            && cg.meter() <= self.rearward_cg_limit.meter()
+            && cg.meter() >= self.forward_cg_limit.meter()
+    }
 }
 
-pub struct Airplane {
-    callsign: String,
-    moments: Vec<Moment>,
-    limits: Limits,
-    fuel_consumption_trip: Volume,
+/// Policy limits relaxed for one flight, see [`Airplane::override_limits`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LimitOverride {
+    index: usize,
+    original: Limits,
+    relaxed: Limits,
+    justification: String,
 }
 
-impl Airplane {
-    pub fn new(
-        callsign: String,
-        moments: Vec<Moment>,
-        limits: Limits,
-        fuel_consumption_trip: Volume,
-    ) -> Airplane {
-        Airplane {
-            callsign,
-            moments,
-            limits,
-            fuel_consumption_trip,
-        }
+impl LimitOverride {
+    /// The index of the limits in [`Airplane::tagged_limits`].
+    pub fn index(&self) -> usize {
+        self.index
     }
 
-    pub fn limits(&self) -> &Limits {
-        &self.limits
+    pub fn original(&self) -> &Limits {
+        &self.original
     }
 
-    fn center_of_gravity(&self) -> CenterOfGravity {
-        let kg_mass = self.total_mass().kilo();
-        let kgm_moment = self.total_mass_moment().kgm();
+    pub fn relaxed(&self) -> &Limits {
+        &self.relaxed
+    }
 
-        CenterOfGravity::Meter(kgm_moment / kg_mass)
+    pub fn justification(&self) -> &str {
+        &self.justification
     }
+}
 
-    pub fn add_max_fuel_within_limits(
-        &mut self,
-        name: String,
-        arm: LeverArm,
-        fuel: FuelType,
-        volume: VolumeType,
-        max_volume: Option<Volume>,
-    ) -> &Moment {
-        let cg_limit = if arm.meter().ge(&0.5) {
-            self.limits().rearward_cg_limit().meter()
-        } else {
-            self.limits().forward_cg_limit().meter()
-        };
+/// What to do with a moment with the name of a moment the airplane already has.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum DuplicatePolicy {
+    /// Both moments are kept, as [`Airplane::add_moment`] does.
+    #[default]
+    Keep,
+    /// Sums the masses at the same arm into the first moment, the masses must both be kg or
+    /// both the same fuel.
+    Merge,
+    Reject,
+}
 
-        let kg_max_mass: f64 = (cg_limit * self.total_mass().kilo()
-            - self.total_mass_moment().kgm())
-            / (arm.meter() - cg_limit);
+// The moment with the mass of both, `None` when the arms or kind of mass differ
+fn merged(first: &Moment, second: &Moment) -> Option<Moment> {
+    if (first.lever_arm.meter() - second.lever_arm.meter()).abs() >= ARM_TOLERANCE_METER {
+        return None;
+    }
+    let in_unit_of = |unit: &Volume, liter: f64| match unit {
+        Volume::Liter(_) => Volume::Liter(liter),
+        Volume::Gallon(_) => Volume::Gallon(Volume::Liter(liter).to_gallon()),
+    };
+    let mass = match (&first.mass, &second.mass) {
+        (Mass::Kilo(a), Mass::Kilo(b)) => Mass::Kilo(a + b),
+        (Mass::Avgas(a), Mass::Avgas(b)) => Mass::Avgas(in_unit_of(a, a.to_liter() + b.to_liter())),
+        (Mass::Mogas(a), Mass::Mogas(b)) => Mass::Mogas(in_unit_of(a, a.to_liter() + b.to_liter())),
+        _ => return None,
+    };
+
+    Some(Moment {
+        mass,
+        ..first.clone()
+    })
+}
 
-        let max_mass = Mass::Kilo(
-            if kg_max_mass + self.total_mass().kilo() >= self.limits().mtow().kilo() {
-                self.limits().mtow().kilo() - self.total_mass().kilo()
-            } else {
-                kg_max_mass
-            },
-        );
-        let max_mass = match fuel {
-            FuelType::Mogas => max_mass.to_mogas(),
-            FuelType::Avgas => max_mass.to_avgas(),
-        };
+// The fuel of `moment` after `liter` is burned
+fn burned(moment: &Moment, liter: f64) -> Result<Mass, WeightBalanceError> {
+    match moment.mass() {
+        Mass::Mogas(v) => Ok(Mass::Mogas(Volume::Liter(v.to_liter() - liter))),
+        Mass::Avgas(v) => Ok(Mass::Avgas(Volume::Liter(v.to_liter() - liter))),
+        Mass::Kilo(_) => Err(WeightBalanceError::NotFuel(moment.name.clone())),
+    }
+}
 
-        let limited_max_mass = match max_volume {
-            Some(max_volume) => match max_mass {
-                Mass::Avgas(v) => {
-                    let volume_liter = if v.to_liter() > max_volume.to_liter() {
-                        max_volume.to_liter()
-                    } else {
-                        v.to_liter()
-                    };
-                    match volume {
-                        VolumeType::Liter => Mass::Avgas(Volume::Liter(volume_liter)),
-                        VolumeType::Gallon => {
-                            Mass::Avgas(Volume::Gallon(Volume::Liter(volume_liter).to_gallon()))
-                        }
-                    }
-                }
-                Mass::Mogas(v) => {
-                    let volume_liter = if v.to_liter() > max_volume.to_liter() {
-                        max_volume.to_liter()
-                    } else {
-                        v.to_liter()
-                    };
-                    match volume {
-                        VolumeType::Liter => Mass::Mogas(Volume::Liter(volume_liter)),
-                        VolumeType::Gallon => {
-                            Mass::Mogas(Volume::Gallon(Volume::Liter(volume_liter).to_gallon()))
-                        }
-                    }
-                }
-                _ => max_mass,
-            },
-            None => max_mass,
-        };
+/// A fuel tank, fuel moments with the name of the tank are the fuel in it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tank {
+    name: String,
+    usable: Volume,
+    total: Volume,
+}
 
-        let moment = Moment::new(name, arm, limited_max_mass);
-        self.moments.push(moment);
-        self.moments.last().unwrap()
+impl Tank {
+    pub fn new(name: String, usable: Volume, total: Volume) -> Tank {
+        Tank {
+            name,
+            usable,
+            total,
+        }
     }
 
-    pub fn total_mass_moment(&self) -> MassMoment {
-        MassMoment::KgM(self.moments.iter().map(|m| m.total().kgm()).sum())
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
-    pub fn total_mass(&self) -> Mass {
-        Mass::Kilo(self.moments.iter().map(|m| m.mass.kilo()).sum())
+    pub fn usable(&self) -> &Volume {
+        &self.usable
     }
 
-    pub fn total_mass_moment_landing(&self) -> MassMoment {
-        let fuel_moment = self.moments.last().expect("should be present");
-        let mass_moment_without_fuel = self.total_mass_moment().kgm() - fuel_moment.total().kgm();
+    /// The usable and unusable fuel.
+    pub fn total(&self) -> &Volume {
+        &self.total
+    }
 
-        let mass = match fuel_moment.mass() {
-            Mass::Mogas(v) => Mass::Mogas(Volume::Liter(
-                v.to_liter() - self.fuel_consumption_trip.to_liter(),
-            )),
-            Mass::Avgas(v) => Mass::Avgas(Volume::Liter(
-                v.to_liter() - self.fuel_consumption_trip.to_liter(),
-            )),
-            _ => panic!("should be fuel"),
-        };
+    pub fn unusable(&self) -> Volume {
+        Volume::Liter((self.total.to_liter() - self.usable.to_liter()).max(0.0))
+    }
+}
 
-        let fuel_moment = Moment::new("Fuel".to_string(), fuel_moment.lever_arm().clone(), mass);
+/// How the trip fuel is burned from the fuel moments for the landing figures.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum FuelBurn {
+    /// All trip fuel from the last moment.
+    #[default]
+    Last,
+    /// The named fuel moments burned down to the unusable fuel of their tanks one after the
+    /// other.
+    Sequence(Vec<String>),
+    /// Every fuel moment burns its share of the fuel on board.
+    Proportional,
+}
+
+/// How close to the limits the summary warns about figures that are still within them, set per
+/// operator with [`Airplane::with_warning_thresholds`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WarningThresholds {
+    mtow_fraction: f64,
+    cg_margin: CenterOfGravity,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    reserve: Option<(f64, Volume)>,
+}
 
-        MassMoment::KgM(mass_moment_without_fuel + fuel_moment.total().kgm())
+/// Within 2% of the MTOW and 5 mm of the CG limits, without a fuel reserve.
+impl Default for WarningThresholds {
+    fn default() -> WarningThresholds {
+        WarningThresholds {
+            mtow_fraction: 0.02,
+            cg_margin: CenterOfGravity::Millimeter(5.0),
+            reserve: None,
+        }
     }
+}
 
-    pub fn total_mass_landing(&self) -> Mass {
-        let fuel_moment = self.moments.last().expect("should be present");
-        let mass_without_fuel = self.total_mass().kilo() - fuel_moment.mass().kilo();
+impl WarningThresholds {
+    pub fn new() -> WarningThresholds {
+        WarningThresholds::default()
+    }
 
-        let mass = match fuel_moment.mass() {
-            Mass::Mogas(v) => Mass::Mogas(Volume::Liter(
-                v.to_liter() - self.fuel_consumption_trip.to_liter(),
-            )),
-            Mass::Avgas(v) => Mass::Avgas(Volume::Liter(
-                v.to_liter() - self.fuel_consumption_trip.to_liter(),
-            )),
-            _ => panic!("should be fuel"),
-        };
+    /// The fraction of the MTOW, 0.02 for 2%.
+    pub fn with_mtow_fraction(mut self, mtow_fraction: f64) -> WarningThresholds {
+        self.mtow_fraction = mtow_fraction;
+        self
+    }
 
-        Mass::Kilo(mass_without_fuel + mass.kilo())
+    pub fn with_cg_margin(mut self, cg_margin: CenterOfGravity) -> WarningThresholds {
+        self.cg_margin = cg_margin;
+        self
     }
-    pub fn within_limits(&self) -> bool {
-        let cg = self.center_of_gravity().meter();
-        self.total_mass().kilo() <= self.limits.mtow.kilo()
-            && cg <= self.limits.rearward_cg_limit.meter()
-            && cg >= self.limits.forward_cg_limit.meter()
+
+    /// Warns when the fuel at landing lasts less than `minutes` at `burn_rate` per hour.
+    pub fn with_reserve(mut self, minutes: f64, burn_rate: Volume) -> WarningThresholds {
+        self.reserve = Some((minutes, burn_rate));
+        self
     }
 
-    pub fn callsign(&self) -> &String {
-        &self.callsign
+    pub fn mtow_fraction(&self) -> f64 {
+        self.mtow_fraction
     }
 
-    pub fn moments(&self) -> &Vec<Moment> {
-        &self.moments
+    pub fn cg_margin(&self) -> &CenterOfGravity {
+        &self.cg_margin
     }
 
-    pub fn add_moment(&mut self, moment: Moment) {
-        self.moments.push(moment);
+    /// The minutes and the burn rate per hour.
+    pub fn reserve(&self) -> Option<&(f64, Volume)> {
+        self.reserve.as_ref()
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    fn airplane(within_limits: bool) -> Airplane {
-        let pilot_mass = if within_limits {
-            Mass::Kilo(80.0)
-        } else {
-            Mass::Kilo(95.0)
-        };
+/// Figures within the limits but close to them, apart from the limit checks of the summary.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Warning {
+    /// The take-off mass is close to the MTOW, with the mass that can still be added.
+    NearMtow(Mass),
+    /// The take-off CG is close to the forward limit, with its distance behind it.
+    NearForwardCgLimit(CenterOfGravity),
+    /// The take-off CG is close to the rearward limit, with its distance in front of it.
+    NearRearwardCgLimit(CenterOfGravity),
+    /// The fuel at landing is less than the reserve.
+    BelowReserve {
+        landing_fuel: Volume,
+        reserve: Volume,
+    },
+}
 
-        Airplane::new(
-            String::from("PHDHA"),
-            vec![
-                Moment::new(
-                    "test".to_string(),
-                    LeverArm::Meter(0.4294),
-                    Mass::Kilo(517.0),
-                ),
-                Moment::new("test".to_string(), LeverArm::Meter(0.515), pilot_mass),
-                Moment::new("test".to_string(), LeverArm::Meter(0.515), Mass::Kilo(89.0)),
-                Moment::new("test".to_string(), LeverArm::Meter(1.3), Mass::Kilo(5.0)),
-                Moment::new(
-                    "test".to_string(),
-                    LeverArm::Meter(0.325),
-                    Mass::Avgas(Volume::Liter(62.0)),
-                ),
-            ],
-            Limits::new(
-                Mass::Kilo(558.0),
-                Mass::Kilo(750.0),
-                CenterOfGravity::Millimeter(427.0),
-                CenterOfGravity::Millimeter(523.0),
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::NearMtow(margin) => {
+                write!(
+                    f,
+                    "take-off mass within {:.1} kg of the MTOW",
+                    margin.kilo()
+                )
+            }
+            Warning::NearForwardCgLimit(margin) => write!(
+                f,
+                "CG within {:.1} mm of the forward limit",
+                margin.meter() * 1000.0
             ),
-            Volume::Liter(17.0),
-        )
+            Warning::NearRearwardCgLimit(margin) => write!(
+                f,
+                "CG within {:.1} mm of the rearward limit",
+                margin.meter() * 1000.0
+            ),
+            Warning::BelowReserve {
+                landing_fuel,
+                reserve,
+            } => write!(
+                f,
+                "landing fuel {:.1} l is below the reserve of {:.1} l",
+                landing_fuel.to_liter(),
+                reserve.to_liter()
+            ),
+        }
+    }
+}
+
+/// The limit that bounds the fuel that can be added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Constraint {
+    Mtow,
+    ForwardCg,
+    RearwardCg,
+    TankCapacity,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MaxFuel {
+    moment: Moment,
+    constraint: Option<Constraint>,
+}
+
+impl MaxFuel {
+    pub fn moment(&self) -> &Moment {
+        &self.moment
+    }
+
+    /// `None` when no amount of fuel brings the airplane within the limits.
+    pub fn constraint(&self) -> Option<Constraint> {
+        self.constraint
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Airplane {
+    #[cfg_attr(feature = "serde", serde(rename = "callsign"))]
+    registration: Registration,
+    moments: Vec<Moment>,
+    limits: Limits,
+    fuel_consumption_trip: Volume,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tanks: Vec<Tank>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    journal: Option<Journal>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    fuel_burn: FuelBurn,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    fuel_consumption_diversion: Option<Volume>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    landing_fuel: Option<Volume>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    tagged_limits: Vec<Limits>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    limit_overrides: Vec<LimitOverride>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    warning_thresholds: WarningThresholds,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    observers: Observers,
+}
+
+impl Airplane {
+    pub fn new(
+        registration: Registration,
+        moments: Vec<Moment>,
+        limits: Limits,
+        fuel_consumption_trip: Volume,
+    ) -> Airplane {
+        Airplane {
+            registration,
+            moments,
+            limits,
+            fuel_consumption_trip,
+            tanks: Vec::new(),
+            journal: None,
+            fuel_burn: FuelBurn::Last,
+            fuel_consumption_diversion: None,
+            landing_fuel: None,
+            tagged_limits: Vec::new(),
+            limit_overrides: Vec::new(),
+            warning_thresholds: WarningThresholds::default(),
+            observers: Observers::default(),
+        }
+    }
+
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    /// Adds limits on top of the envelope of [`Airplane::limits`], which stays the one
+    /// [`Airplane::within_limits`] checks.
+    pub fn add_limits(&mut self, limits: Limits) {
+        self.tagged_limits.push(limits);
+    }
+
+    /// Adds the [`Limits::with_margins`] of the envelope as policy limits, which the summary
+    /// checks and the chart draws dashed inside the envelope.
+    pub fn add_margins(&mut self, mtow_fraction: f64, cg_margin: &CenterOfGravity) {
+        let margins = self.limits.with_margins(mtow_fraction, cg_margin);
+        self.add_limits(margins);
+    }
+
+    /// The airplane with the [`Limits::at_density_altitude`] of the envelope and every added
+    /// limit, for the figures of a take-off at `density_altitude_ft`.
+    pub fn at_density_altitude(&self, density_altitude_ft: f64) -> Airplane {
+        Airplane {
+            limits: self.limits.at_density_altitude(density_altitude_ft),
+            tagged_limits: self
+                .tagged_limits
+                .iter()
+                .map(|l| l.at_density_altitude(density_altitude_ft))
+                .collect(),
+            ..self.unobserved()
+        }
+    }
+
+    pub fn tagged_limits(&self) -> &Vec<Limits> {
+        &self.tagged_limits
+    }
+
+    /// Whether the take-off figures are within every added limit of `kind`, `None` without any.
+    pub fn within_tagged_limits(&self, kind: LimitKind) -> Option<bool> {
+        let (mass, cg) = (self.total_mass(), self.center_of_gravity());
+        let mut limits = self
+            .tagged_limits
+            .iter()
+            .filter(|l| l.kind == kind)
+            .peekable();
+        limits.peek()?;
+        Some(limits.all(|l| l.contains(&mass, &cg)))
+    }
+
+    /// Replaces the policy limits at `index` of [`Airplane::tagged_limits`] by `relaxed` until
+    /// [`Airplane::revert_limit_overrides`]. The override and its `justification` are kept with
+    /// the airplane, in its summary and in the journal. Certified limits are never overridden.
+    pub fn override_limits(
+        &mut self,
+        index: usize,
+        relaxed: Limits,
+        justification: String,
+    ) -> Result<(), WeightBalanceError> {
+        if justification.trim().is_empty() {
+            return Err(WeightBalanceError::NoJustification);
+        }
+        let limits = self
+            .tagged_limits
+            .get_mut(index)
+            .filter(|l| l.kind == LimitKind::Policy)
+            .ok_or(WeightBalanceError::NotPolicyLimits(index))?;
+
+        let relaxed = relaxed.with_kind(LimitKind::Policy);
+        let original = core::mem::replace(limits, relaxed.clone());
+        let limit_override = LimitOverride {
+            index,
+            original,
+            relaxed,
+            justification,
+        };
+        let change = Change::limits_overridden(&limit_override);
+        self.limit_overrides.push(limit_override);
+        self.record(change);
+        Ok(())
+    }
+
+    pub fn limit_overrides(&self) -> &Vec<LimitOverride> {
+        &self.limit_overrides
+    }
+
+    /// Restores the policy limits of every override, the journal keeps them.
+    pub fn revert_limit_overrides(&mut self) {
+        while let Some(o) = self.limit_overrides.pop() {
+            self.tagged_limits[o.index] = o.original;
+        }
+    }
+
+    pub fn fuel_consumption_trip(&self) -> &Volume {
+        &self.fuel_consumption_trip
+    }
+
+    pub fn with_fuel_consumption_trip(mut self, fuel_consumption_trip: Volume) -> Airplane {
+        self.fuel_consumption_trip = fuel_consumption_trip;
+        self
+    }
+
+    /// The fuel from the destination to the alternate, burned on top of the trip fuel for the
+    /// alternate landing figures.
+    pub fn with_fuel_consumption_diversion(
+        mut self,
+        fuel_consumption_diversion: Volume,
+    ) -> Airplane {
+        self.fuel_consumption_diversion = Some(fuel_consumption_diversion);
+        self
+    }
+
+    pub fn fuel_consumption_diversion(&self) -> Option<&Volume> {
+        self.fuel_consumption_diversion.as_ref()
+    }
+
+    /// The fuel expected on board at landing, e.g. from the totalizer, instead of the fuel on
+    /// board less the trip fuel. The landing figures burn the fuel on board down to it.
+    pub fn with_landing_fuel(mut self, landing_fuel: Volume) -> Airplane {
+        self.landing_fuel = Some(landing_fuel);
+        self
+    }
+
+    pub fn landing_fuel(&self) -> Option<&Volume> {
+        self.landing_fuel.as_ref()
+    }
+
+    // The liters of every fuel moment
+    fn fuel_on_board(&self) -> f64 {
+        self.moments
+            .iter()
+            .filter_map(|m| m.mass.volume())
+            .map(Volume::to_liter)
+            .sum()
+    }
+
+    /// The fuel burned until landing: the fuel on board less the landing fuel when it is given,
+    /// the trip fuel otherwise.
+    pub fn trip_fuel(&self) -> Volume {
+        match &self.landing_fuel {
+            Some(landing) => Volume::Liter(self.fuel_on_board() - landing.to_liter()),
+            None => self.fuel_consumption_trip.clone(),
+        }
+    }
+
+    /// Whether the landing fuel is at most the fuel on board and, when there is trip fuel as
+    /// well, within `tolerance` of the fuel on board less the trip fuel.
+    pub fn check_landing_fuel(&self, tolerance: &Volume) -> Result<(), WeightBalanceError> {
+        let Some(landing) = &self.landing_fuel else {
+            return Ok(());
+        };
+        let (landing, on_board) = (landing.to_liter(), self.fuel_on_board());
+        let tolerance = tolerance.to_liter();
+        let trip = self.fuel_consumption_trip.to_liter();
+
+        if landing > on_board + tolerance {
+            Err(WeightBalanceError::LandingFuelAboveOnBoard)
+        } else if trip != 0.0 && (on_board - trip - landing).abs() > tolerance {
+            Err(WeightBalanceError::InconsistentLandingFuel)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Not a number when the airplane has no mass.
+    pub fn center_of_gravity(&self) -> CenterOfGravity {
+        let kg_mass = self.total_mass().kilo();
+        let kgm_moment = self.total_mass_moment().kgm();
+
+        CenterOfGravity::Meter(kgm_moment / kg_mass)
+    }
+
+    /// Whether every mass, moment and CG that is charted or tabled is a finite number, the first
+    /// one that is not as [`WeightBalanceError::NotFinite`]. The CG is only checked when the
+    /// airplane has mass and the landing figures when they can be computed.
+    pub fn check_finite(&self) -> Result<(), WeightBalanceError> {
+        let not_finite = |figures: &[(&'static str, f64)]| {
+            figures
+                .iter()
+                .find(|(_, value)| !value.is_finite())
+                .map(|(what, _)| *what)
+        };
+
+        for moment in &self.moments {
+            let figures = [
+                ("lever arm", moment.lever_arm.meter()),
+                ("mass", moment.mass.kilo()),
+                ("mass moment", moment.total().kgm()),
+            ];
+            if let Some(what) = not_finite(&figures) {
+                return Err(WeightBalanceError::NotFinite(format!(
+                    "{} of {}",
+                    what, moment.name
+                )));
+            }
+        }
+
+        let mut figures = Vec::new();
+        for limits in core::iter::once(&self.limits).chain(&self.tagged_limits) {
+            figures.extend([
+                ("minimum weight", limits.minimum_weight.kilo()),
+                ("MTOW", limits.mtow.kilo()),
+                ("forward CG limit", limits.forward_cg_limit.meter()),
+                ("rearward CG limit", limits.rearward_cg_limit.meter()),
+            ]);
+        }
+        let (mass, moment) = (self.total_mass().kilo(), self.total_mass_moment().kgm());
+        figures.extend([("total mass", mass), ("total mass moment", moment)]);
+        if mass != 0.0 {
+            figures.push(("center of gravity", moment / mass));
+        }
+        if let Ok((mass, moment)) = self.landing(self.trip_fuel().to_liter()) {
+            figures.extend([
+                ("landing mass", mass.kilo()),
+                ("landing mass moment", moment.kgm()),
+            ]);
+        }
+
+        match not_finite(&figures) {
+            Some(what) => Err(WeightBalanceError::NotFinite(what.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    // The most mass that can be added at `arm` within the MTOW and both CG limits and the limit
+    // that binds it, `None` when no mass at `arm` brings the airplane within the limits. Every
+    // limit is a constraint c * mass <= d on the added mass.
+    pub(crate) fn max_mass_within_limits(&self, arm: &LeverArm) -> Option<(f64, Constraint)> {
+        let (mass, moment) = (self.total_mass().kilo(), self.total_mass_moment().kgm());
+        let (forward, rearward) = (
+            self.limits.forward_cg_limit.meter(),
+            self.limits.rearward_cg_limit.meter(),
+        );
+        let arm = arm.meter();
+
+        let constraints = [
+            (Constraint::Mtow, 1.0, self.limits.mtow.kilo() - mass),
+            (
+                Constraint::RearwardCg,
+                arm - rearward,
+                rearward * mass - moment,
+            ),
+            (
+                Constraint::ForwardCg,
+                forward - arm,
+                moment - forward * mass,
+            ),
+        ];
+
+        let mut lowest = 0.0_f64;
+        let mut highest = (f64::INFINITY, Constraint::Mtow);
+        for (constraint, c, d) in constraints {
+            if c.abs() < ARM_TOLERANCE_METER {
+                // Mass on the limit moves the CG towards it but never past it
+                if d < -ARM_TOLERANCE_METER * mass.max(1.0) {
+                    return None;
+                }
+            } else if c > 0.0 {
+                if d / c < highest.0 {
+                    highest = (d / c, constraint);
+                }
+            } else {
+                lowest = lowest.max(d / c);
+            }
+        }
+
+        (lowest <= highest.0).then_some(highest)
+    }
+
+    /// The most fuel that keeps the mass below the MTOW, the CG between both limits and the
+    /// volume within `max_volume`, wherever the tank is relative to the datum and the envelope,
+    /// and the constraint that binds it. Without `max_volume` the usable fuel of the tank named
+    /// `name` limits the volume. No fuel when no amount of fuel brings the airplane within the
+    /// limits.
+    pub fn max_fuel_analysis(
+        &self,
+        name: String,
+        arm: LeverArm,
+        fuel: FuelType,
+        volume: VolumeType,
+        max_volume: Option<Volume>,
+    ) -> MaxFuel {
+        let max_volume = max_volume.or_else(|| self.tank(&name).map(|t| t.usable().clone()));
+        let (kg_max_mass, constraint) = match self.max_mass_within_limits(&arm) {
+            Some((kg, constraint)) => (kg.max(0.0), Some(constraint)),
+            None => (0.0, None),
+        };
+        let max_mass = match fuel {
+            FuelType::Mogas => Mass::Kilo(kg_max_mass).to_mogas(),
+            FuelType::Avgas => Mass::Kilo(kg_max_mass).to_avgas(),
+        };
+
+        let constraint = match (&max_volume, &max_mass) {
+            (Some(max_volume), Mass::Avgas(v) | Mass::Mogas(v))
+                if constraint.is_some() && v.to_liter() > max_volume.to_liter() =>
+            {
+                Some(Constraint::TankCapacity)
+            }
+            _ => constraint,
+        };
+
+        let limited_max_mass = match max_volume {
+            Some(max_volume) => match max_mass {
+                Mass::Avgas(v) => {
+                    let volume_liter = if v.to_liter() > max_volume.to_liter() {
+                        max_volume.to_liter()
+                    } else {
+                        v.to_liter()
+                    };
+                    match volume {
+                        VolumeType::Liter => Mass::Avgas(Volume::Liter(volume_liter)),
+                        VolumeType::Gallon => {
+                            Mass::Avgas(Volume::Gallon(Volume::Liter(volume_liter).to_gallon()))
+                        }
+                    }
+                }
+                Mass::Mogas(v) => {
+                    let volume_liter = if v.to_liter() > max_volume.to_liter() {
+                        max_volume.to_liter()
+                    } else {
+                        v.to_liter()
+                    };
+                    match volume {
+                        VolumeType::Liter => Mass::Mogas(Volume::Liter(volume_liter)),
+                        VolumeType::Gallon => {
+                            Mass::Mogas(Volume::Gallon(Volume::Liter(volume_liter).to_gallon()))
+                        }
+                    }
+                }
+                _ => max_mass,
+            },
+            None => max_mass,
+        };
+
+        MaxFuel {
+            moment: Moment::new(name, arm, limited_max_mass),
+            constraint,
+        }
+    }
+
+    /// Adds the fuel of [`Airplane::max_fuel_analysis`].
+    pub fn add_max_fuel_within_limits(
+        &mut self,
+        name: String,
+        arm: LeverArm,
+        fuel: FuelType,
+        volume: VolumeType,
+        max_volume: Option<Volume>,
+    ) -> &Moment {
+        let max_fuel = self.max_fuel_analysis(name, arm, fuel, volume, max_volume);
+        self.add_moment(max_fuel.moment);
+        &self.moments[self.moments.len() - 1]
+    }
+
+    pub fn total_mass_moment(&self) -> MassMoment {
+        MassMoment::KgM(self.moments.iter().map(|m| m.total().kgm()).sum())
+    }
+
+    pub fn total_mass(&self) -> Mass {
+        Mass::Kilo(self.moments.iter().map(|m| m.mass.kilo()).sum())
+    }
+
+    // The fuel moment after `trip` liters are burned
+    fn landing_fuel_moment(&self, trip: f64) -> Result<(&Moment, Moment), WeightBalanceError> {
+        let fuel_moment = self.moments.last().ok_or(WeightBalanceError::NoMoments)?;
+        let mass = burned(fuel_moment, trip)?;
+
+        Ok((
+            fuel_moment,
+            Moment::new("Fuel".to_string(), fuel_moment.lever_arm().clone(), mass),
+        ))
+    }
+
+    // The fuel moments before and after `trip` liters are burned as set by the fuel burn
+    fn landing_fuel_moments(
+        &self,
+        trip: f64,
+    ) -> Result<Vec<(&Moment, Moment)>, WeightBalanceError> {
+        let landing = |m: &Moment, liter: f64| -> Result<Moment, WeightBalanceError> {
+            Ok(Moment {
+                mass: burned(m, liter)?,
+                ..m.clone()
+            })
+        };
+
+        match &self.fuel_burn {
+            FuelBurn::Last => Ok(alloc::vec![self.landing_fuel_moment(trip)?]),
+            FuelBurn::Sequence(names) => {
+                let mut remaining = trip;
+                let mut moments = Vec::new();
+                for name in names {
+                    let m = self
+                        .moments
+                        .iter()
+                        .find(|m| &m.name == name && m.mass.is_fuel())
+                        .ok_or_else(|| WeightBalanceError::UnknownTank(name.clone()))?;
+                    let burn = remaining.min(self.usable_fuel_on_board(m));
+                    remaining -= burn;
+                    moments.push((m, landing(m, burn)?));
+                }
+                if moments.is_empty() {
+                    return Err(WeightBalanceError::NoMoments);
+                }
+                if remaining > FUEL_TOLERANCE_LITER {
+                    return Err(WeightBalanceError::TripFuelAboveUsable);
+                }
+                Ok(moments)
+            }
+            FuelBurn::Proportional => self
+                .fuel_shares()?
+                .into_iter()
+                .map(|(m, share)| Ok((m, landing(m, trip * share)?)))
+                .collect(),
+        }
+    }
+
+    // Every fuel moment with its share of the fuel on board, equal shares when there is none
+    fn fuel_shares(&self) -> Result<Vec<(&Moment, f64)>, WeightBalanceError> {
+        let fuel: Vec<&Moment> = self.moments.iter().filter(|m| m.mass.is_fuel()).collect();
+        if fuel.is_empty() {
+            return Err(match self.moments.last() {
+                Some(m) => WeightBalanceError::NotFuel(m.name.clone()),
+                None => WeightBalanceError::NoMoments,
+            });
+        }
+
+        let liters: Vec<f64> = fuel
+            .iter()
+            .map(|m| m.mass.volume().map_or(0.0, Volume::to_liter))
+            .collect();
+        let on_board: f64 = liters.iter().sum();
+        let count = fuel.len() as f64;
+        Ok(fuel
+            .into_iter()
+            .zip(liters)
+            .map(|(m, l)| match on_board > 0.0 {
+                true => (m, l / on_board),
+                false => (m, 1.0 / count),
+            })
+            .collect())
+    }
+
+    /// The millimeters the CG moves for every kilogram added at `arm`, rearward when positive.
+    /// The rate for a small mass, so removing mass moves the CG the other way. Not a number when
+    /// the airplane has no mass.
+    pub fn cg_sensitivity(&self, arm: &LeverArm) -> f64 {
+        let mass = self.total_mass().kilo();
+        (arm.meter() - self.total_mass_moment().kgm() / mass) / mass * 1000.0
+    }
+
+    /// The [`Airplane::cg_sensitivity`] at the arm of every moment, in the order of the moments.
+    pub fn cg_sensitivity_per_station(&self) -> Vec<(&str, f64)> {
+        self.moments
+            .iter()
+            .map(|m| (m.name.as_str(), self.cg_sensitivity(&m.lever_arm)))
+            .collect()
+    }
+
+    // The liters burned at the start, at every tank switch and when the tanks of the fuel burn
+    // are down to their unusable fuel
+    fn burn_points(&self) -> Result<Vec<f64>, WeightBalanceError> {
+        match &self.fuel_burn {
+            FuelBurn::Last => {
+                let fuel_moment = self.moments.last().ok_or(WeightBalanceError::NoMoments)?;
+                match fuel_moment.mass.is_fuel() {
+                    true => Ok(alloc::vec![0.0, self.usable_fuel_on_board(fuel_moment)]),
+                    false => Err(WeightBalanceError::NotFuel(fuel_moment.name.clone())),
+                }
+            }
+            FuelBurn::Sequence(names) => {
+                let mut points = alloc::vec![0.0];
+                let mut burned = 0.0;
+                for name in names {
+                    let m = self
+                        .moments
+                        .iter()
+                        .find(|m| &m.name == name && m.mass.is_fuel())
+                        .ok_or_else(|| WeightBalanceError::UnknownTank(name.clone()))?;
+                    burned += self.usable_fuel_on_board(m);
+                    points.push(burned);
+                }
+                Ok(points)
+            }
+            // Every tank burns its share until the first is down to its unusable fuel, the CG
+            // moves one way in between
+            FuelBurn::Proportional => {
+                let end = self
+                    .fuel_shares()?
+                    .into_iter()
+                    .filter(|(_, share)| *share > 0.0)
+                    .map(|(m, share)| self.usable_fuel_on_board(m) / share)
+                    .fold(f64::INFINITY, f64::min);
+                Ok(alloc::vec![0.0, end])
+            }
+        }
+    }
+
+    /// The most forward and rearward CG while the fuel burns as set by [`Airplane::fuel_burn`],
+    /// from the fuel on board down to the unusable fuel of the tanks. The CG moves steadily one
+    /// way while one tank burns, towards the arm of the tank when fuel is added and away from it
+    /// when fuel burns, so both are at the start, at a tank switch or at the end of the burn.
+    pub fn worst_case_cg(&self) -> Result<WorstCaseCg, WeightBalanceError> {
+        let mut extremes: Option<((CenterOfGravity, Mass), (CenterOfGravity, Mass))> = None;
+
+        for burned in self.burn_points()? {
+            let (mass, moment) = self.landing(burned)?;
+            if mass.kilo() <= 0.0 {
+                continue;
+            }
+            let point = (
+                CenterOfGravity::Meter(moment.kgm() / mass.kilo()),
+                self.fuel_on_board_after(burned)?,
+            );
+            extremes = Some(match extremes {
+                None => (point.clone(), point),
+                Some((forward, rearward)) => {
+                    let cg = point.0.meter();
+                    match (cg < forward.0.meter(), cg > rearward.0.meter()) {
+                        (true, _) => (point, rearward),
+                        (_, true) => (forward, point),
+                        _ => (forward, rearward),
+                    }
+                }
+            });
+        }
+
+        let (forward, rearward) = extremes.ok_or(WeightBalanceError::NoMoments)?;
+        let within_limits = forward.0.meter() >= self.limits.forward_cg_limit.meter()
+            && rearward.0.meter() <= self.limits.rearward_cg_limit.meter();
+
+        Ok(WorstCaseCg {
+            forward_cg: forward.0,
+            forward_fuel: forward.1,
+            rearward_cg: rearward.0,
+            rearward_fuel: rearward.1,
+            within_limits,
+        })
+    }
+
+    // Every fuel moment after `burned` liters, in their fuel type when they are alike and in
+    // kilograms when not
+    fn fuel_on_board_after(&self, burned: f64) -> Result<Mass, WeightBalanceError> {
+        let landing = self.landing_fuel_moments(burned)?;
+        let fuel: Vec<Mass> = self
+            .moments
+            .iter()
+            .filter(|m| m.mass.is_fuel())
+            .map(|m| {
+                landing
+                    .iter()
+                    .find(|(takeoff, _)| core::ptr::eq(*takeoff, m))
+                    .map_or(m.mass.clone(), |(_, l)| l.mass.clone())
+            })
+            .collect();
+
+        let liter: f64 = fuel
+            .iter()
+            .filter_map(Mass::volume)
+            .map(Volume::to_liter)
+            .sum();
+        Ok(match fuel.first() {
+            Some(Mass::Avgas(_)) if fuel.iter().all(|f| matches!(f, Mass::Avgas(_))) => {
+                Mass::Avgas(Volume::Liter(liter))
+            }
+            Some(Mass::Mogas(_)) if fuel.iter().all(|f| matches!(f, Mass::Mogas(_))) => {
+                Mass::Mogas(Volume::Liter(liter))
+            }
+            _ => Mass::Kilo(fuel.iter().map(Mass::kilo).sum()),
+        })
+    }
+
+    /// The mass without any fuel moment, for the MZFW and the zero fuel CG.
+    pub fn total_mass_zero_fuel(&self) -> Mass {
+        Mass::Kilo(
+            self.moments
+                .iter()
+                .filter(|m| !m.mass.is_fuel())
+                .map(|m| m.mass.kilo())
+                .sum(),
+        )
+    }
+
+    pub fn total_mass_moment_zero_fuel(&self) -> MassMoment {
+        MassMoment::KgM(
+            self.moments
+                .iter()
+                .filter(|m| !m.mass.is_fuel())
+                .map(|m| m.total().kgm())
+                .sum(),
+        )
+    }
+
+    /// The CG without any fuel moment, not a number when there is only fuel.
+    pub fn center_of_gravity_zero_fuel(&self) -> CenterOfGravity {
+        CenterOfGravity::Meter(
+            self.total_mass_moment_zero_fuel().kgm() / self.total_mass_zero_fuel().kilo(),
+        )
+    }
+
+    // The mass and moment after `trip` liters are burned
+    fn landing(&self, trip: f64) -> Result<(Mass, MassMoment), WeightBalanceError> {
+        let (mass, moment) = self.landing_fuel_moments(trip)?.iter().fold(
+            (self.total_mass().kilo(), self.total_mass_moment().kgm()),
+            |(mass, moment), (takeoff, landing)| {
+                (
+                    mass - takeoff.mass().kilo() + landing.mass().kilo(),
+                    moment - takeoff.total().kgm() + landing.total().kgm(),
+                )
+            },
+        );
+
+        Ok((Mass::Kilo(mass), MassMoment::KgM(moment)))
+    }
+
+    pub fn total_mass_moment_landing(&self) -> Result<MassMoment, WeightBalanceError> {
+        Ok(self.landing(self.trip_fuel().to_liter())?.1)
+    }
+
+    pub fn total_mass_landing(&self) -> Result<Mass, WeightBalanceError> {
+        Ok(self.landing(self.trip_fuel().to_liter())?.0)
+    }
+
+    pub fn center_of_gravity_landing(&self) -> Result<CenterOfGravity, WeightBalanceError> {
+        let (mass, moment) = self.landing(self.trip_fuel().to_liter())?;
+        Ok(CenterOfGravity::Meter(moment.kgm() / mass.kilo()))
+    }
+
+    // The mass and moment at the alternate with the trip and diversion fuel burned
+    fn alternate_landing(&self) -> Result<(Mass, MassMoment), WeightBalanceError> {
+        let diversion = self
+            .fuel_consumption_diversion
+            .as_ref()
+            .ok_or(WeightBalanceError::NoDiversionFuel)?;
+        self.landing(self.trip_fuel().to_liter() + diversion.to_liter())
+    }
+
+    pub fn total_mass_moment_alternate_landing(&self) -> Result<MassMoment, WeightBalanceError> {
+        Ok(self.alternate_landing()?.1)
+    }
+
+    pub fn total_mass_alternate_landing(&self) -> Result<Mass, WeightBalanceError> {
+        Ok(self.alternate_landing()?.0)
+    }
+
+    /// Whether the landing at the alternate is below the MTOW and within the CG limits.
+    pub fn alternate_landing_within_limits(&self) -> Result<bool, WeightBalanceError> {
+        let (mass, moment) = self.alternate_landing()?;
+        let cg = CenterOfGravity::Meter(moment.kgm() / mass.kilo());
+
+        Ok(self.limits.contains(&mass, &cg))
+    }
+
+    // The liters of a fuel moment above the unusable fuel of its tank
+    fn usable_fuel_on_board(&self, fuel_moment: &Moment) -> f64 {
+        let on_board = fuel_moment.mass.volume().map_or(0.0, Volume::to_liter);
+        let unusable = self
+            .tank(&fuel_moment.name)
+            .map_or(0.0, |t| t.unusable().to_liter());
+        (on_board - unusable).max(0.0)
+    }
+
+    // The usable fuel of every fuel moment minus the reserve, in liters
+    fn usable_fuel_liter(&self, reserve: &Volume) -> Result<f64, WeightBalanceError> {
+        let fuel: Vec<&Moment> = self.moments.iter().filter(|m| m.mass.is_fuel()).collect();
+        if fuel.is_empty() {
+            return Err(match self.moments.last() {
+                Some(m) => WeightBalanceError::NotFuel(m.name.clone()),
+                None => WeightBalanceError::NoMoments,
+            });
+        }
+
+        let usable: f64 = fuel.iter().map(|m| self.usable_fuel_on_board(m)).sum();
+        Ok((usable - reserve.to_liter()).max(0.0))
+    }
+
+    /// Hours of flight on the usable fuel of every fuel moment minus the reserve at a burn rate
+    /// per hour.
+    pub fn endurance(
+        &self,
+        burn_rate: &Volume,
+        reserve: &Volume,
+    ) -> Result<f64, WeightBalanceError> {
+        Ok(self.usable_fuel_liter(reserve)? / burn_rate.to_liter())
+    }
+
+    /// Nautical miles at a true airspeed in knots in still air.
+    pub fn range(
+        &self,
+        burn_rate: &Volume,
+        reserve: &Volume,
+        true_airspeed_kt: f64,
+    ) -> Result<f64, WeightBalanceError> {
+        Ok(self.endurance(burn_rate, reserve)? * true_airspeed_kt)
+    }
+
+    pub fn with_warning_thresholds(mut self, warning_thresholds: WarningThresholds) -> Airplane {
+        self.warning_thresholds = warning_thresholds;
+        self
+    }
+
+    pub fn warning_thresholds(&self) -> &WarningThresholds {
+        &self.warning_thresholds
+    }
+
+    /// The figures within the limits that are closer to them than the warning thresholds.
+    pub fn warnings(&self) -> Vec<Warning> {
+        let thresholds = &self.warning_thresholds;
+        let mut warnings = Vec::new();
+
+        let mtow_margin = self.limits.mtow.kilo() - self.total_mass().kilo();
+        if (0.0..=thresholds.mtow_fraction * self.limits.mtow.kilo()).contains(&mtow_margin) {
+            warnings.push(Warning::NearMtow(Mass::Kilo(mtow_margin)));
+        }
+
+        let cg = self.center_of_gravity().meter();
+        let near = 0.0..=thresholds.cg_margin.meter();
+        let forward = cg - self.limits.forward_cg_limit.meter();
+        if near.contains(&forward) {
+            warnings.push(Warning::NearForwardCgLimit(CenterOfGravity::Meter(forward)));
+        }
+        let rearward = self.limits.rearward_cg_limit.meter() - cg;
+        if near.contains(&rearward) {
+            warnings.push(Warning::NearRearwardCgLimit(CenterOfGravity::Meter(
+                rearward,
+            )));
+        }
+
+        let fuel: Vec<f64> = self
+            .moments
+            .iter()
+            .filter_map(|m| match &m.mass {
+                Mass::Avgas(v) | Mass::Mogas(v) => Some(v.to_liter()),
+                Mass::Kilo(_) => None,
+            })
+            .collect();
+        if let (Some((minutes, burn_rate)), false) = (&thresholds.reserve, fuel.is_empty()) {
+            let landing = fuel.iter().sum::<f64>() - self.trip_fuel().to_liter();
+            let reserve = burn_rate.to_liter() * minutes / 60.0;
+            if landing < reserve {
+                warnings.push(Warning::BelowReserve {
+                    landing_fuel: Volume::Liter(landing),
+                    reserve: Volume::Liter(reserve),
+                });
+            }
+        }
+
+        warnings
+    }
+
+    pub fn within_limits(&self) -> bool {
+        let cg = self.center_of_gravity().meter();
+        self.total_mass().kilo() <= self.limits.mtow.kilo()
+            && cg <= self.limits.rearward_cg_limit.meter()
+            && cg >= self.limits.forward_cg_limit.meter()
+    }
+
+    /// The registration without a hyphen.
+    #[deprecated(note = "use registration")]
+    pub fn callsign(&self) -> &str {
+        self.registration.compact()
+    }
+
+    pub fn registration(&self) -> &Registration {
+        &self.registration
+    }
+
+    #[deprecated(note = "use iter, len and is_empty")]
+    pub fn moments(&self) -> &Vec<Moment> {
+        &self.moments
+    }
+
+    /// The moments in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &Moment> {
+        self.moments.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.moments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moments.is_empty()
+    }
+
+    pub fn add_moment(&mut self, moment: Moment) {
+        let change = Change::moment(None, &moment);
+        self.moments.push(moment);
+        self.record(change);
+    }
+
+    /// Adds `moment` following `policy` when the airplane has a moment with its name.
+    pub fn add_moment_with_policy(
+        &mut self,
+        moment: Moment,
+        policy: DuplicatePolicy,
+    ) -> Result<(), WeightBalanceError> {
+        let Some(index) = self.moments.iter().position(|m| m.name == moment.name) else {
+            self.add_moment(moment);
+            return Ok(());
+        };
+
+        match policy {
+            DuplicatePolicy::Keep => self.add_moment(moment),
+            DuplicatePolicy::Merge => {
+                let merged = merged(&self.moments[index], &moment)
+                    .ok_or(WeightBalanceError::DuplicateMoment(moment.name.clone()))?;
+                let change = Change::moment(Some(&self.moments[index]), &merged);
+                self.moments[index] = merged;
+                self.record(change);
+            }
+            DuplicatePolicy::Reject => {
+                return Err(WeightBalanceError::DuplicateMoment(moment.name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies `policy` to the moments that share a name, in order. Nothing changes when it
+    /// fails.
+    pub fn apply_duplicate_policy(
+        &mut self,
+        policy: DuplicatePolicy,
+    ) -> Result<(), WeightBalanceError> {
+        let mut deduplicated = Airplane {
+            moments: Vec::new(),
+            journal: None,
+            observers: Observers::default(),
+            ..self.clone()
+        };
+        for moment in self.moments.iter() {
+            deduplicated.add_moment_with_policy(moment.clone(), policy)?;
+        }
+        self.moments = deduplicated.moments;
+        Ok(())
+    }
+
+    /// Replaces the first moment named `name` and returns it, `None` when there is none.
+    pub fn update_moment(&mut self, name: &str, moment: Moment) -> Option<Moment> {
+        let index = self.moments.iter().position(|m| m.name == name)?;
+        let change = Change::moment(Some(&self.moments[index]), &moment);
+        let previous = core::mem::replace(&mut self.moments[index], moment);
+        self.record(change);
+        Some(previous)
+    }
+
+    // Passes `change` to the observers and keeps it in the journal
+    fn record(&mut self, change: Change) {
+        self.observers.notify(&change);
+        if let Some(journal) = self.journal.as_mut() {
+            journal.record(change);
+        }
+    }
+
+    // A clone that is not observed, for figures computed on the side
+    pub(crate) fn unobserved(&self) -> Airplane {
+        Airplane {
+            observers: Observers::default(),
+            ..self.clone()
+        }
+    }
+
+    // An unobserved clone with `mass` as the fuel on board, the last moment
+    pub(crate) fn with_fuel_on_board(&self, mass: Mass) -> Airplane {
+        let mut plane = self.unobserved();
+        if let Some(fuel) = plane.moments.last_mut() {
+            fuel.mass = mass;
+        }
+        plane
+    }
+
+    /// Records every later change of the moments.
+    pub fn with_journal(mut self) -> Airplane {
+        self.journal = Some(Journal::default());
+        self
+    }
+
+    /// Calls `observer` with every later change of the moments and limit overrides, the same
+    /// changes the journal records. Clones of the airplane share its observers.
+    pub fn with_observer(mut self, observer: impl Fn(&Change) + Send + Sync + 'static) -> Airplane {
+        self.observers.push(Arc::new(observer));
+        self
+    }
+
+    pub fn journal(&self) -> Option<&Journal> {
+        self.journal.as_ref()
+    }
+
+    /// Burns the trip fuel for the landing figures as `fuel_burn`, from the last moment without.
+    pub fn with_fuel_burn(mut self, fuel_burn: FuelBurn) -> Airplane {
+        self.fuel_burn = fuel_burn;
+        self
+    }
+
+    pub fn fuel_burn(&self) -> &FuelBurn {
+        &self.fuel_burn
+    }
+
+    pub fn add_tank(&mut self, tank: Tank) {
+        self.tanks.push(tank);
+    }
+
+    pub fn tanks(&self) -> &Vec<Tank> {
+        &self.tanks
+    }
+
+    pub fn tank(&self, name: &str) -> Option<&Tank> {
+        self.tanks.iter().find(|t| t.name == name)
+    }
+
+    /// Fails on the first fuel moment with more fuel than the usable fuel of its tank.
+    pub fn check_tank_capacity(&self) -> Result<(), WeightBalanceError> {
+        for m in self.moments.iter() {
+            let (Some(volume), Some(tank)) = (m.mass.volume(), self.tank(&m.name)) else {
+                continue;
+            };
+            if volume.to_liter() > tank.usable.to_liter() {
+                return Err(WeightBalanceError::AboveTankCapacity(m.name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Landing figures are only present when the last moment is the fuel moment.
+    pub fn summary(&self) -> WeightBalanceSummary {
+        let cg = self.center_of_gravity().meter();
+
+        WeightBalanceSummary {
+            takeoff_mass: self.total_mass(),
+            takeoff_mass_moment: self.total_mass_moment(),
+            takeoff_center_of_gravity: self.center_of_gravity(),
+            landing_mass: self.total_mass_landing().ok(),
+            landing_mass_moment: self.total_mass_moment_landing().ok(),
+            landing_center_of_gravity: self
+                .center_of_gravity_landing()
+                .ok()
+                .filter(|cg| cg.meter().is_finite()),
+            zero_fuel_center_of_gravity: Some(self.center_of_gravity_zero_fuel())
+                .filter(|cg| cg.meter().is_finite()),
+            alternate_landing_mass: self.total_mass_alternate_landing().ok(),
+            alternate_landing_mass_moment: self.total_mass_moment_alternate_landing().ok(),
+            alternate_landing_within_limits: self.alternate_landing_within_limits().ok(),
+            within_limits: self.within_limits(),
+            within_policy_limits: self.within_tagged_limits(LimitKind::Policy),
+            limit_overrides: self.limit_overrides.clone(),
+            mtow_margin: Mass::Kilo(self.limits.mtow.kilo() - self.total_mass().kilo()),
+            forward_cg_margin: CenterOfGravity::Meter(cg - self.limits.forward_cg_limit.meter()),
+            rearward_cg_margin: CenterOfGravity::Meter(self.limits.rearward_cg_limit.meter() - cg),
+            warnings: self.warnings(),
+        }
+    }
+}
+
+/// The CG extremes of [`Airplane::worst_case_cg`] with the fuel on board at each.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WorstCaseCg {
+    forward_cg: CenterOfGravity,
+    forward_fuel: Mass,
+    rearward_cg: CenterOfGravity,
+    rearward_fuel: Mass,
+    within_limits: bool,
+}
+
+impl WorstCaseCg {
+    pub fn forward_cg(&self) -> &CenterOfGravity {
+        &self.forward_cg
+    }
+
+    pub fn forward_fuel(&self) -> &Mass {
+        &self.forward_fuel
+    }
+
+    pub fn rearward_cg(&self) -> &CenterOfGravity {
+        &self.rearward_cg
+    }
+
+    pub fn rearward_fuel(&self) -> &Mass {
+        &self.rearward_fuel
+    }
+
+    /// Both extremes are within the CG limits.
+    pub fn within_limits(&self) -> bool {
+        self.within_limits
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WeightBalanceSummary {
+    takeoff_mass: Mass,
+    takeoff_mass_moment: MassMoment,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "undefined_cg", skip_serializing_if = "is_undefined")
+    )]
+    takeoff_center_of_gravity: CenterOfGravity,
+    landing_mass: Option<Mass>,
+    landing_mass_moment: Option<MassMoment>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    landing_center_of_gravity: Option<CenterOfGravity>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    zero_fuel_center_of_gravity: Option<CenterOfGravity>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    alternate_landing_mass: Option<Mass>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    alternate_landing_mass_moment: Option<MassMoment>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    alternate_landing_within_limits: Option<bool>,
+    within_limits: bool,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    within_policy_limits: Option<bool>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    limit_overrides: Vec<LimitOverride>,
+    mtow_margin: Mass,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "undefined_cg", skip_serializing_if = "is_undefined")
+    )]
+    forward_cg_margin: CenterOfGravity,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "undefined_cg", skip_serializing_if = "is_undefined")
+    )]
+    rearward_cg_margin: CenterOfGravity,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    warnings: Vec<Warning>,
+}
+
+// The CG of an airplane without mass, left out of serialized summaries
+#[cfg(feature = "serde")]
+fn undefined_cg() -> CenterOfGravity {
+    CenterOfGravity::Meter(f64::NAN)
+}
+
+#[cfg(feature = "serde")]
+fn is_undefined(cg: &CenterOfGravity) -> bool {
+    !cg.meter().is_finite()
+}
+
+impl WeightBalanceSummary {
+    pub fn takeoff_mass(&self) -> &Mass {
+        &self.takeoff_mass
+    }
+
+    pub fn takeoff_mass_moment(&self) -> &MassMoment {
+        &self.takeoff_mass_moment
+    }
+
+    /// Not a number when the airplane has no mass, as are the CG margins.
+    pub fn takeoff_center_of_gravity(&self) -> &CenterOfGravity {
+        &self.takeoff_center_of_gravity
+    }
+
+    pub fn landing_mass(&self) -> Option<&Mass> {
+        self.landing_mass.as_ref()
+    }
+
+    pub fn landing_mass_moment(&self) -> Option<&MassMoment> {
+        self.landing_mass_moment.as_ref()
+    }
+
+    /// `None` without landing figures or landing mass.
+    pub fn landing_center_of_gravity(&self) -> Option<&CenterOfGravity> {
+        self.landing_center_of_gravity.as_ref()
+    }
+
+    /// `None` when there is nothing but fuel on board.
+    pub fn zero_fuel_center_of_gravity(&self) -> Option<&CenterOfGravity> {
+        self.zero_fuel_center_of_gravity.as_ref()
+    }
+
+    /// Only present with the diversion fuel set.
+    pub fn alternate_landing_mass(&self) -> Option<&Mass> {
+        self.alternate_landing_mass.as_ref()
+    }
+
+    pub fn alternate_landing_mass_moment(&self) -> Option<&MassMoment> {
+        self.alternate_landing_mass_moment.as_ref()
+    }
+
+    pub fn alternate_landing_within_limits(&self) -> Option<bool> {
+        self.alternate_landing_within_limits
+    }
+
+    pub fn within_limits(&self) -> bool {
+        self.within_limits
+    }
+
+    /// Reported apart from [`WeightBalanceSummary::within_limits`], `None` without policy limits.
+    pub fn within_policy_limits(&self) -> Option<bool> {
+        self.within_policy_limits
+    }
+
+    /// The policy limits that were relaxed for these figures.
+    pub fn limit_overrides(&self) -> &Vec<LimitOverride> {
+        &self.limit_overrides
+    }
+
+    /// The mass that can still be added at take-off, negative above the MTOW.
+    pub fn mtow_margin(&self) -> &Mass {
+        &self.mtow_margin
+    }
+
+    /// The distance of the take-off CG behind the forward limit, negative in front of it.
+    pub fn forward_cg_margin(&self) -> &CenterOfGravity {
+        &self.forward_cg_margin
+    }
+
+    /// The distance of the take-off CG in front of the rearward limit, negative behind it.
+    pub fn rearward_cg_margin(&self) -> &CenterOfGravity {
+        &self.rearward_cg_margin
+    }
+
+    /// See [`Airplane::warnings`], apart from [`WeightBalanceSummary::within_limits`].
+    pub fn warnings(&self) -> &Vec<Warning> {
+        &self.warnings
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn airplane(within_limits: bool) -> Airplane {
+        let pilot_mass = if within_limits {
+            Mass::Kilo(80.0)
+        } else {
+            Mass::Kilo(95.0)
+        };
+
+        Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![
+                Moment::new(
+                    "test".to_string(),
+                    LeverArm::Meter(0.4294),
+                    Mass::Kilo(517.0),
+                ),
+                Moment::new("test".to_string(), LeverArm::Meter(0.515), pilot_mass),
+                Moment::new("test".to_string(), LeverArm::Meter(0.515), Mass::Kilo(89.0)),
+                Moment::new("test".to_string(), LeverArm::Meter(1.3), Mass::Kilo(5.0)),
+                Moment::new(
+                    "test".to_string(),
+                    LeverArm::Meter(0.325),
+                    Mass::Avgas(Volume::Liter(62.0)),
+                ),
+            ],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(17.0),
+        )
     }
 
     fn calculate_maximum_mass() {
         let mut plane = Airplane::new(
-            String::from("PHDHA"),
+            Registration::parse("PHDHA").unwrap(),
             vec![
                 Moment::new("test".to_string(), LeverArm::Meter(2.0), Mass::Kilo(10.0)),
                 Moment::new("test".to_string(), LeverArm::Meter(3.0), Mass::Kilo(5.0)),
@@ -436,7 +2126,7 @@ mod test {
     #[test]
     fn calculate_maximum_mass_mtow() {
         let mut plane = Airplane::new(
-            String::from("PHDHA"),
+            Registration::parse("PHDHA").unwrap(),
             vec![
                 Moment::new("test".to_string(), LeverArm::Meter(2.0), Mass::Kilo(10.0)),
                 Moment::new("test".to_string(), LeverArm::Meter(3.0), Mass::Kilo(5.0)),
@@ -476,6 +2166,63 @@ mod test {
         assert_eq!(517.0 * 0.4294, kgm);
     }
 
+    #[test]
+    fn moment_builder() {
+        let moment = Moment::builder()
+            .name("Pilot".to_string())
+            .lever_arm(LeverArm::Meter(0.515))
+            .mass(Mass::Kilo(80.0))
+            .note("headset".to_string())
+            .build()
+            .unwrap();
+        assert_eq!(
+            Moment::new(
+                "Pilot".to_string(),
+                LeverArm::Meter(0.515),
+                Mass::Kilo(80.0)
+            )
+            .with_note("headset".to_string()),
+            moment
+        );
+
+        let builder = Moment::builder()
+            .name("Pilot".to_string())
+            .lever_arm(LeverArm::Meter(0.515));
+        assert_eq!(
+            Err(MomentError::Missing("Pilot".to_string(), "mass")),
+            builder.clone().build()
+        );
+        assert_eq!(
+            Err(MomentError::InvalidMass("Pilot".to_string())),
+            builder.clone().mass(Mass::Kilo(-1.0)).build()
+        );
+        assert_eq!(
+            Err(MomentError::InvalidMass("Pilot".to_string())),
+            builder.mass(Mass::Kilo(f64::NAN)).build()
+        );
+        assert_eq!(
+            Err(MomentError::InvalidLeverArm("Pilot".to_string())),
+            Moment::builder()
+                .name("Pilot".to_string())
+                .lever_arm(LeverArm::Meter(f64::INFINITY))
+                .mass(Mass::Kilo(80.0))
+                .build()
+        );
+        assert_eq!(Err(MomentError::EmptyName), Moment::builder().build());
+    }
+
+    #[test]
+    fn mass_moment_units() {
+        let moment =
+            MassMoment::from_mass_and_arm(&Mass::from_pound(10.0), &LeverArm::from_inch(20.0));
+        assert!((moment.lb_in() - 200.0).abs() < 1e-9);
+        assert!((MassMoment::from_lb_in(200.0).kgm() - moment.kgm()).abs() < 1e-12);
+
+        let moment = MassMoment::from_kg_mm(80.0 * 515.0);
+        assert!((moment.kgm() - 41.2).abs() < 1e-9);
+        assert!((moment.kg_mm() - 41200.0).abs() < 1e-9);
+    }
+
     #[test]
     fn calculate_cg() {
         assert_eq!(
@@ -498,4 +2245,764 @@ mod test {
     fn inside_of_limits() {
         assert!(airplane(true).within_limits());
     }
+
+    #[test]
+    fn landing_needs_fuel_last() {
+        let mut plane = airplane(true);
+        assert!(plane.total_mass_landing().is_ok());
+
+        plane.add_moment(Moment::new(
+            "Baggage".to_string(),
+            LeverArm::Meter(1.3),
+            Mass::Kilo(2.0),
+        ));
+        assert_eq!(
+            Err(WeightBalanceError::NotFuel("Baggage".to_string())),
+            plane.total_mass_moment_landing().map(|_| ())
+        );
+        assert!(plane.summary().landing_mass().is_none());
+    }
+
+    #[test]
+    fn landing_with_fuel_burn() {
+        let fuel = |name: &str, arm: f64, liter: f64| {
+            Moment::new(
+                name.to_string(),
+                LeverArm::Meter(arm),
+                Mass::Avgas(Volume::Liter(liter)),
+            )
+        };
+        let plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![
+                Moment::new("Empty".to_string(), LeverArm::Meter(1.0), Mass::Kilo(600.0)),
+                fuel("Aux", 2.0, 10.0),
+                fuel("Main", 1.0, 30.0),
+            ],
+            Limits::new(
+                Mass::Kilo(500.0),
+                Mass::Kilo(800.0),
+                CenterOfGravity::Meter(0.5),
+                CenterOfGravity::Meter(1.5),
+            ),
+            Volume::Liter(20.0),
+        );
+        let landing = |plane: &Airplane| {
+            (
+                plane.total_mass_landing().unwrap().kilo(),
+                plane.total_mass_moment_landing().unwrap().kgm(),
+            )
+        };
+        let takeoff = plane.total_mass_moment().kgm();
+
+        // All from the main tank
+        let (mass, moment) = landing(&plane);
+        assert!((mass - (600.0 + 20.0 * 0.72)).abs() < 1e-9);
+        assert!((moment - (takeoff - 20.0 * 0.72)).abs() < 1e-9);
+
+        // The aux tank empty first, then 10 L from the main tank
+        let sequence = plane.clone().with_fuel_burn(FuelBurn::Sequence(vec![
+            "Aux".to_string(),
+            "Main".to_string(),
+        ]));
+        let (mass, moment) = landing(&sequence);
+        assert!((mass - (600.0 + 20.0 * 0.72)).abs() < 1e-9);
+        assert!((moment - (takeoff - 10.0 * 0.72 * 2.0 - 10.0 * 0.72)).abs() < 1e-9);
+
+        // 5 L from the aux and 15 L from the main tank
+        let proportional = plane.clone().with_fuel_burn(FuelBurn::Proportional);
+        let (_, moment) = landing(&proportional);
+        assert!((moment - (takeoff - 5.0 * 0.72 * 2.0 - 15.0 * 0.72)).abs() < 1e-9);
+
+        let unknown = plane
+            .clone()
+            .with_fuel_burn(FuelBurn::Sequence(vec!["Tip".to_string()]));
+        assert_eq!(
+            Err(WeightBalanceError::UnknownTank("Tip".to_string())),
+            unknown.total_mass_landing().map(|_| ())
+        );
+
+        // The main tank holds 30 L with 12 L unusable, too little for the 19 L after the aux
+        let mut short = sequence.with_fuel_consumption_trip(Volume::Liter(29.0));
+        short.add_tank(Tank::new(
+            "Main".to_string(),
+            Volume::Liter(18.0),
+            Volume::Liter(30.0),
+        ));
+        assert_eq!(
+            Err(WeightBalanceError::TripFuelAboveUsable),
+            short.total_mass_landing().map(|_| ())
+        );
+        let (mass, _) = landing(&short.with_fuel_consumption_trip(Volume::Liter(28.0)));
+        assert!((mass - (600.0 + 12.0 * 0.72)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn imposed_landing_fuel() {
+        let plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![
+                Moment::new("Empty".to_string(), LeverArm::Meter(1.0), Mass::Kilo(600.0)),
+                Moment::new(
+                    "Fuel".to_string(),
+                    LeverArm::Meter(2.0),
+                    Mass::Avgas(Volume::Liter(40.0)),
+                ),
+            ],
+            Limits::new(
+                Mass::Kilo(500.0),
+                Mass::Kilo(800.0),
+                CenterOfGravity::Meter(0.5),
+                CenterOfGravity::Meter(1.5),
+            ),
+            Volume::Liter(20.0),
+        );
+        assert_eq!(Volume::Liter(20.0), plane.trip_fuel());
+        assert_eq!(Ok(()), plane.check_landing_fuel(&Volume::Liter(1.0)));
+
+        // The totalizer expects 18 L at landing, 22 L burned instead of the planned 20 L
+        let totalizer = plane.clone().with_landing_fuel(Volume::Liter(18.0));
+        assert_eq!(Volume::Liter(22.0), totalizer.trip_fuel());
+        let landing = totalizer.total_mass_landing().unwrap().kilo();
+        assert!((landing - (600.0 + 18.0 * 0.72)).abs() < 1e-9);
+        assert_eq!(Ok(()), totalizer.check_landing_fuel(&Volume::Liter(2.0)));
+        assert_eq!(
+            Err(WeightBalanceError::InconsistentLandingFuel),
+            totalizer.check_landing_fuel(&Volume::Liter(1.0))
+        );
+
+        // Without trip fuel only the fuel on board is checked
+        let unplanned = totalizer.with_fuel_consumption_trip(Volume::Liter(0.0));
+        assert_eq!(Ok(()), unplanned.check_landing_fuel(&Volume::Liter(0.0)));
+        let above = unplanned.with_landing_fuel(Volume::Liter(45.0));
+        assert_eq!(
+            Err(WeightBalanceError::LandingFuelAboveOnBoard),
+            above.check_landing_fuel(&Volume::Liter(1.0))
+        );
+    }
+
+    #[test]
+    fn alternate_landing() {
+        let plane = airplane(true);
+        assert_eq!(
+            Err(WeightBalanceError::NoDiversionFuel),
+            plane.total_mass_alternate_landing()
+        );
+        assert!(plane.summary().alternate_landing_mass().is_none());
+
+        let plane = plane.with_fuel_consumption_diversion(Volume::Liter(10.0));
+        let landing = plane.total_mass_landing().unwrap().kilo();
+        let alternate = plane.total_mass_alternate_landing().unwrap().kilo();
+        assert!((landing - alternate - 10.0 * 0.72).abs() < 1e-9);
+        assert!(
+            (plane.total_mass_moment_landing().unwrap().kgm()
+                - plane.total_mass_moment_alternate_landing().unwrap().kgm()
+                - 10.0 * 0.72 * 0.325)
+                .abs()
+                < 1e-9
+        );
+
+        let summary = plane.summary();
+        assert_eq!(
+            Some(&Mass::Kilo(alternate)),
+            summary.alternate_landing_mass()
+        );
+        assert_eq!(
+            plane.alternate_landing_within_limits().ok(),
+            summary.alternate_landing_within_limits()
+        );
+    }
+
+    #[test]
+    fn policy_limits_apart_from_certified_limits() {
+        let mut plane = airplane(true);
+        assert_eq!(None, plane.within_tagged_limits(LimitKind::Policy));
+        assert_eq!(None, plane.summary().within_policy_limits());
+
+        let certified = plane.limits().clone();
+        plane.add_limits(
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(plane.total_mass().kilo() - 1.0),
+                certified.forward_cg_limit().clone(),
+                certified.rearward_cg_limit().clone(),
+            )
+            .with_kind(LimitKind::Policy),
+        );
+        assert!(plane.within_limits());
+        assert_eq!(&certified, plane.limits());
+        assert_eq!(Some(false), plane.within_tagged_limits(LimitKind::Policy));
+        assert_eq!(None, plane.within_tagged_limits(LimitKind::Operational));
+
+        let summary = plane.summary();
+        assert!(summary.within_limits());
+        assert_eq!(Some(false), summary.within_policy_limits());
+    }
+
+    #[test]
+    fn override_policy_limits_with_justification() {
+        let mut plane = airplane(true).with_journal();
+        let policy = Limits::new(
+            Mass::Kilo(558.0),
+            Mass::Kilo(600.0),
+            CenterOfGravity::Millimeter(427.0),
+            CenterOfGravity::Millimeter(523.0),
+        )
+        .with_kind(LimitKind::Policy);
+        plane.add_limits(policy.clone().with_kind(LimitKind::Operational));
+        plane.add_limits(policy.clone());
+        let relaxed = Limits::new(
+            Mass::Kilo(558.0),
+            Mass::Kilo(750.0),
+            CenterOfGravity::Millimeter(427.0),
+            CenterOfGravity::Millimeter(523.0),
+        );
+
+        assert_eq!(
+            Err(WeightBalanceError::NoJustification),
+            plane.override_limits(1, relaxed.clone(), " ".to_string())
+        );
+        assert_eq!(
+            Err(WeightBalanceError::NotPolicyLimits(0)),
+            plane.override_limits(0, relaxed.clone(), "Ferry flight".to_string())
+        );
+        assert_eq!(Some(false), plane.within_tagged_limits(LimitKind::Policy));
+
+        plane
+            .override_limits(1, relaxed.clone(), "Ferry flight".to_string())
+            .unwrap();
+        assert_eq!(LimitKind::Policy, plane.tagged_limits()[1].kind());
+        assert_eq!(Some(true), plane.within_tagged_limits(LimitKind::Policy));
+        let summary = plane.summary();
+        assert_eq!("Ferry flight", summary.limit_overrides()[0].justification());
+        assert!(matches!(
+            plane.journal().unwrap().entries()[0].change(),
+            crate::journal::Change::LimitsOverridden { index: 1, .. }
+        ));
+
+        plane.revert_limit_overrides();
+        assert_eq!(&policy, &plane.tagged_limits()[1]);
+        assert!(plane.limit_overrides().is_empty());
+        assert_eq!(1, plane.journal().unwrap().entries().len());
+    }
+
+    #[test]
+    fn endurance_and_range() {
+        let plane = airplane(true);
+        let burn_rate = Volume::Liter(20.0);
+
+        let endurance = plane.endurance(&burn_rate, &Volume::Liter(12.0)).unwrap();
+        assert!((endurance - 2.5).abs() < 1e-9);
+        let range = plane
+            .range(&burn_rate, &Volume::Liter(12.0), 100.0)
+            .unwrap();
+        assert!((range - 250.0).abs() < 1e-9);
+        assert_eq!(Ok(0.0), plane.endurance(&burn_rate, &Volume::Liter(80.0)));
+
+        // 14 L of the 20 L in the aux tank are usable
+        let mut aux = plane.clone();
+        aux.add_moment(Moment::new(
+            "Aux".to_string(),
+            LeverArm::Meter(0.6),
+            Mass::Avgas(Volume::Liter(20.0)),
+        ));
+        aux.add_tank(Tank::new(
+            "Aux".to_string(),
+            Volume::Liter(14.0),
+            Volume::Liter(20.0),
+        ));
+        let endurance = aux.endurance(&burn_rate, &Volume::Liter(12.0)).unwrap();
+        assert!((endurance - 3.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn calculate_maximum_mass_on_cg_limit() {
+        let mut plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![Moment::new(
+                "test".to_string(),
+                LeverArm::Meter(2.0),
+                Mass::Kilo(10.0),
+            )],
+            Limits::new(
+                Mass::Kilo(10.0),
+                Mass::Kilo(24.0),
+                CenterOfGravity::Meter(1.0),
+                CenterOfGravity::Meter(3.0),
+            ),
+            Volume::Liter(17.0),
+        );
+
+        let max_moment = plane.add_max_fuel_within_limits(
+            "test".to_string(),
+            LeverArm::Meter(3.0),
+            FuelType::Avgas,
+            VolumeType::Liter,
+            None,
+        );
+        assert!((max_moment.mass().kilo() - 14.0).abs() < 1e-9);
+        assert!(plane.within_limits());
+    }
+
+    #[test]
+    fn max_fuel_binding_constraint() {
+        let plane = |mtow: f64| {
+            Airplane::new(
+                Registration::parse("PHDHA").unwrap(),
+                vec![
+                    Moment::new("test".to_string(), LeverArm::Meter(2.0), Mass::Kilo(10.0)),
+                    Moment::new("test".to_string(), LeverArm::Meter(3.0), Mass::Kilo(5.0)),
+                ],
+                Limits::new(
+                    Mass::Kilo(10.0),
+                    Mass::Kilo(mtow),
+                    CenterOfGravity::Meter(1.0),
+                    CenterOfGravity::Meter(3.0),
+                ),
+                Volume::Liter(17.0),
+            )
+        };
+        let analysis = |plane: &Airplane, arm: f64, max_volume: Option<Volume>| {
+            plane
+                .max_fuel_analysis(
+                    "Fuel".to_string(),
+                    LeverArm::Meter(arm),
+                    FuelType::Avgas,
+                    VolumeType::Liter,
+                    max_volume,
+                )
+                .constraint()
+        };
+
+        assert_eq!(Some(Constraint::Mtow), analysis(&plane(24.0), 4.0, None));
+        assert_eq!(
+            Some(Constraint::RearwardCg),
+            analysis(&plane(40.0), 4.0, None)
+        );
+        assert_eq!(
+            Some(Constraint::TankCapacity),
+            analysis(&plane(40.0), 4.0, Some(Volume::Liter(5.0)))
+        );
+        assert_eq!(
+            Some(Constraint::ForwardCg),
+            analysis(&plane(40.0), -2.0, None)
+        );
+
+        // No amount of fuel brings an airplane above the MTOW within the limits
+        let heavy = plane(14.0);
+        let max_fuel = heavy.max_fuel_analysis(
+            "Fuel".to_string(),
+            LeverArm::Meter(4.0),
+            FuelType::Avgas,
+            VolumeType::Liter,
+            None,
+        );
+        assert_eq!(None, max_fuel.constraint());
+        assert_eq!(0.0, max_fuel.moment().mass().kilo());
+    }
+
+    #[test]
+    fn duplicate_moments() {
+        let empty = || {
+            Airplane::new(
+                Registration::parse("PHDHA").unwrap(),
+                vec![Moment::new(
+                    "Empty".to_string(),
+                    LeverArm::Meter(0.4294),
+                    Mass::Kilo(517.0),
+                )],
+                Limits::new(
+                    Mass::Kilo(558.0),
+                    Mass::Kilo(750.0),
+                    CenterOfGravity::Millimeter(427.0),
+                    CenterOfGravity::Millimeter(523.0),
+                ),
+                Volume::Liter(17.0),
+            )
+        };
+        let bag =
+            |kg: f64| Moment::new("Baggage".to_string(), LeverArm::Meter(1.3), Mass::Kilo(kg));
+
+        let mut plane = empty();
+        plane
+            .add_moment_with_policy(bag(3.0), DuplicatePolicy::Keep)
+            .unwrap();
+        plane
+            .add_moment_with_policy(bag(2.0), DuplicatePolicy::Merge)
+            .unwrap();
+        assert_eq!(2, plane.len());
+        assert_eq!(
+            Some(&Mass::Kilo(5.0)),
+            plane.iter().nth(1).map(Moment::mass)
+        );
+        assert_eq!(
+            Err(WeightBalanceError::DuplicateMoment("Baggage".to_string())),
+            plane.add_moment_with_policy(bag(1.0), DuplicatePolicy::Reject)
+        );
+
+        // Merged fuel keeps the unit of the first moment
+        let fuel =
+            |v: Volume| Moment::new("Fuel".to_string(), LeverArm::Meter(0.325), Mass::Avgas(v));
+        let mut fueled = empty();
+        fueled.add_moment(fuel(Volume::Gallon(1.0)));
+        fueled.add_moment(fuel(Volume::Gallon(2.0)));
+        let mut merged = fueled.clone();
+        merged
+            .apply_duplicate_policy(DuplicatePolicy::Merge)
+            .unwrap();
+        assert_eq!(2, merged.len());
+        assert!(matches!(
+            merged.iter().last().unwrap().mass(),
+            Mass::Avgas(Volume::Gallon(g)) if (g - 3.0).abs() < 1e-9
+        ));
+
+        // Fuel at another arm cannot be merged, a failed policy changes nothing
+        fueled.add_moment(Moment::new(
+            "Fuel".to_string(),
+            LeverArm::Meter(0.6),
+            Mass::Avgas(Volume::Liter(1.0)),
+        ));
+        assert!(fueled
+            .clone()
+            .apply_duplicate_policy(DuplicatePolicy::Merge)
+            .is_err());
+        assert!(fueled
+            .apply_duplicate_policy(DuplicatePolicy::Reject)
+            .is_err());
+        assert_eq!(4, fueled.len());
+    }
+
+    #[test]
+    fn worst_case_cg_at_either_end_of_the_burn() {
+        // Fuel ahead of the CG, the CG moves aft as it burns
+        let plane = airplane(true);
+        let worst = plane.worst_case_cg().unwrap();
+        assert_eq!(&plane.center_of_gravity(), worst.forward_cg());
+        assert_eq!(&Mass::Avgas(Volume::Liter(62.0)), worst.forward_fuel());
+        assert!(
+            (worst.rearward_cg().meter() - plane.total_mass_moment_zero_fuel().kgm() / 691.0).abs()
+                < 1e-9
+        );
+        assert_eq!(0.0, worst.rearward_fuel().kilo());
+        assert!(worst.within_limits());
+
+        let mut baggage_last = plane.clone();
+        baggage_last.add_moment(Moment::new(
+            "Baggage".to_string(),
+            LeverArm::Meter(1.3),
+            Mass::Kilo(1.0),
+        ));
+        assert!(baggage_last.worst_case_cg().is_err());
+    }
+
+    #[test]
+    fn worst_case_cg_at_a_tank_switch() {
+        let fuel = |name: &str, arm: f64, liter: f64| {
+            Moment::new(
+                name.to_string(),
+                LeverArm::Meter(arm),
+                Mass::Avgas(Volume::Liter(liter)),
+            )
+        };
+        let mut plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![
+                Moment::new("Empty".to_string(), LeverArm::Meter(1.0), Mass::Kilo(600.0)),
+                fuel("Aux", 2.0, 10.0),
+                fuel("Main", 0.5, 30.0),
+            ],
+            Limits::new(
+                Mass::Kilo(500.0),
+                Mass::Kilo(800.0),
+                CenterOfGravity::Meter(0.985),
+                CenterOfGravity::Meter(1.5),
+            ),
+            Volume::Liter(20.0),
+        )
+        .with_fuel_burn(FuelBurn::Sequence(vec![
+            "Aux".to_string(),
+            "Main".to_string(),
+        ]));
+        plane.add_tank(Tank::new(
+            "Main".to_string(),
+            Volume::Liter(27.0),
+            Volume::Liter(30.0),
+        ));
+
+        // The aft aux tank burns first, the CG is most forward when the main tank takes over
+        let worst = plane.worst_case_cg().unwrap();
+        assert!((worst.forward_cg().meter() - 610.8 / 621.6).abs() < 1e-9);
+        assert_eq!(&Mass::Avgas(Volume::Liter(30.0)), worst.forward_fuel());
+        assert!(worst.forward_cg().meter() < plane.center_of_gravity().meter());
+        assert!(plane
+            .limits()
+            .contains(&plane.total_mass(), &plane.center_of_gravity()));
+        assert!(!worst.within_limits());
+
+        // The unusable fuel of the main tank stays on board
+        let (mass, moment) = (600.0 + 3.0 * 0.72, 600.0 + 3.0 * 0.72 * 0.5);
+        assert!((worst.rearward_cg().meter() - moment / mass).abs() < 1e-9);
+        assert!((worst.rearward_fuel().kilo() - 3.0 * 0.72).abs() < 1e-9);
+
+        // Burning both at once the CG moves aft from the start, until the main tank is down to
+        // its unusable fuel with 1 L left in the aux tank
+        let proportional = plane.with_fuel_burn(FuelBurn::Proportional);
+        let worst = proportional.worst_case_cg().unwrap();
+        assert_eq!(&proportional.center_of_gravity(), worst.forward_cg());
+        assert!((worst.rearward_fuel().kilo() - 4.0 * 0.72).abs() < 1e-9);
+        assert!(worst.within_limits());
+    }
+
+    #[test]
+    fn zero_fuel_mass_and_moment() {
+        let mut plane = airplane(true);
+        plane.add_moment(Moment::new(
+            "Aux".to_string(),
+            LeverArm::Meter(0.6),
+            Mass::Mogas(Volume::Liter(20.0)),
+        ));
+
+        assert_eq!(
+            517.0 + 80.0 + 89.0 + 5.0,
+            plane.total_mass_zero_fuel().kilo()
+        );
+        let moment = 517.0 * 0.4294 + (80.0 + 89.0) * 0.515 + 5.0 * 1.3;
+        assert!((plane.total_mass_moment_zero_fuel().kgm() - moment).abs() < 1e-9);
+
+        let cg = moment / (517.0 + 80.0 + 89.0 + 5.0);
+        assert!((plane.center_of_gravity_zero_fuel().meter() - cg).abs() < 1e-9);
+        let summary = plane.summary();
+        assert_eq!(
+            Some(&plane.center_of_gravity_zero_fuel()),
+            summary.zero_fuel_center_of_gravity()
+        );
+        let landing = plane.total_mass_moment_landing().unwrap().kgm()
+            / plane.total_mass_landing().unwrap().kilo();
+        assert!((summary.landing_center_of_gravity().unwrap().meter() - landing).abs() < 1e-9);
+    }
+
+    #[test]
+    fn tank_capacity_caps_max_fuel() {
+        let mut plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![
+                Moment::new(
+                    "Empty".to_string(),
+                    LeverArm::Meter(0.4294),
+                    Mass::Kilo(517.0),
+                ),
+                Moment::new(
+                    "Pilot".to_string(),
+                    LeverArm::Meter(0.515),
+                    Mass::Kilo(90.0),
+                ),
+            ],
+            Limits::new(
+                Mass::Kilo(558.0),
+                Mass::Kilo(750.0),
+                CenterOfGravity::Millimeter(427.0),
+                CenterOfGravity::Millimeter(523.0),
+            ),
+            Volume::Liter(17.0),
+        );
+        plane.add_tank(Tank::new(
+            "Fuel".to_string(),
+            Volume::Liter(109.0),
+            Volume::Liter(113.0),
+        ));
+
+        let max_fuel = plane.max_fuel_analysis(
+            "Fuel".to_string(),
+            LeverArm::Meter(0.325),
+            FuelType::Avgas,
+            VolumeType::Liter,
+            None,
+        );
+        assert_eq!(Some(Constraint::TankCapacity), max_fuel.constraint());
+        assert_eq!(&Mass::Avgas(Volume::Liter(109.0)), max_fuel.moment().mass());
+
+        plane.add_moment(Moment::new(
+            "Fuel".to_string(),
+            LeverArm::Meter(0.325),
+            Mass::Avgas(Volume::Gallon(30.0)),
+        ));
+        assert_eq!(
+            Err(WeightBalanceError::AboveTankCapacity("Fuel".to_string())),
+            plane.check_tank_capacity()
+        );
+    }
+
+    #[test]
+    fn calculate_maximum_mass_forward_tank() {
+        // Datum at the firewall with the tank ahead of it
+        let mut plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![Moment::new(
+                "Empty".to_string(),
+                LeverArm::Meter(1.0),
+                Mass::Kilo(500.0),
+            )],
+            Limits::new(
+                Mass::Kilo(400.0),
+                Mass::Kilo(800.0),
+                CenterOfGravity::Meter(0.8),
+                CenterOfGravity::Meter(1.2),
+            ),
+            Volume::Liter(0.0),
+        );
+
+        // The forward limit binds at (500 kg m - 0.8 m * 500 kg) / (0.8 m + 0.5 m)
+        let max_moment = plane.add_max_fuel_within_limits(
+            "Fuel".to_string(),
+            LeverArm::Meter(-0.5),
+            FuelType::Avgas,
+            VolumeType::Liter,
+            None,
+        );
+        assert!((max_moment.mass().kilo() - 100.0 / 1.3).abs() < 1e-9);
+        assert!(plane.within_limits());
+    }
+
+    #[test]
+    fn calculate_maximum_mass_aft_of_rearward_limit_below_half_meter() {
+        let mut plane = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![Moment::new(
+                "Empty".to_string(),
+                LeverArm::Meter(0.2),
+                Mass::Kilo(500.0),
+            )],
+            Limits::new(
+                Mass::Kilo(400.0),
+                Mass::Kilo(1000.0),
+                CenterOfGravity::Meter(0.1),
+                CenterOfGravity::Meter(0.3),
+            ),
+            Volume::Liter(0.0),
+        );
+
+        // The old solver took the forward limit for arms below 0.5 m
+        plane.add_max_fuel_within_limits(
+            "Fuel".to_string(),
+            LeverArm::Meter(0.45),
+            FuelType::Avgas,
+            VolumeType::Liter,
+            None,
+        );
+        assert!((plane.center_of_gravity().meter() - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn compare_and_print() {
+        let plane = airplane(true);
+        assert_eq!(plane, plane.clone());
+        assert_ne!(plane, airplane(false));
+        assert!(format!("{:?}", plane).contains("PHDHA"));
+        assert_eq!(CenterOfGravity::Meter(0.427), CenterOfGravity::Meter(0.427));
+    }
+
+    #[test]
+    fn iterate_moments() {
+        let plane = airplane(true);
+        assert_eq!(5, plane.len());
+        assert!(!plane.is_empty());
+        assert_eq!("PHDHA", plane.registration().compact());
+        assert!(plane.iter().last().unwrap().mass().is_fuel());
+    }
+
+    #[test]
+    fn finite_figures() {
+        assert_eq!(Ok(()), airplane(true).check_finite());
+
+        let mut plane = airplane(true);
+        plane.add_moment(Moment::new(
+            "Baggage".to_string(),
+            LeverArm::Meter(f64::INFINITY),
+            Mass::Kilo(0.0),
+        ));
+        assert_eq!(
+            Err(WeightBalanceError::NotFinite(
+                "lever arm of Baggage".to_string()
+            )),
+            plane.check_finite()
+        );
+
+        let empty = Airplane::new(
+            Registration::parse("PHDHA").unwrap(),
+            vec![],
+            airplane(true).limits().clone(),
+            Volume::Liter(17.0),
+        );
+        assert_eq!(Ok(()), empty.check_finite());
+    }
+
+    #[test]
+    fn warnings_near_the_limits() {
+        let plane = airplane(true);
+        assert!(plane.within_limits());
+        let warnings = plane.summary().warnings().clone();
+        assert_eq!(1, warnings.len());
+        assert!(matches!(&warnings[0], Warning::NearMtow(m) if (m.kilo() - 14.36).abs() < 1e-9));
+        assert_eq!(
+            "take-off mass within 14.4 kg of the MTOW",
+            warnings[0].to_string()
+        );
+        assert!(airplane(false).warnings().is_empty());
+
+        let plane = airplane(true).with_warning_thresholds(
+            WarningThresholds::new()
+                .with_mtow_fraction(0.01)
+                .with_cg_margin(CenterOfGravity::Millimeter(25.0))
+                .with_reserve(45.0, Volume::Liter(75.0)),
+        );
+        let warnings = plane.warnings();
+        assert!(matches!(warnings[0], Warning::NearForwardCgLimit(_)));
+        assert_eq!(
+            Warning::BelowReserve {
+                landing_fuel: Volume::Liter(45.0),
+                reserve: Volume::Liter(56.25),
+            },
+            warnings[1]
+        );
+        assert_eq!(2, warnings.len());
+    }
+
+    #[test]
+    fn training_margins() {
+        let mut plane = airplane(true);
+        plane.add_margins(0.02, &CenterOfGravity::Millimeter(10.0));
+
+        let margins = &plane.tagged_limits()[0];
+        assert_eq!(LimitKind::Policy, margins.kind());
+        assert!((margins.mtow().kilo() - 735.0).abs() < 1e-9);
+        assert!((margins.forward_cg_limit().meter() - 0.437).abs() < 1e-9);
+        assert!((margins.rearward_cg_limit().meter() - 0.513).abs() < 1e-9);
+
+        // 735.64 kg is within the MTOW but not within the margins
+        let summary = plane.summary();
+        assert!(summary.within_limits());
+        assert_eq!(Some(false), summary.within_policy_limits());
+    }
+
+    #[test]
+    fn hot_and_high() {
+        let limits = airplane(true)
+            .limits()
+            .clone()
+            .with_altitude_mtow(6000.0, Mass::Kilo(730.0))
+            .with_altitude_mtow(4000.0, Mass::Kilo(740.0));
+        let mtow = |altitude: f64| limits.at_density_altitude(altitude).mtow().kilo();
+        assert_eq!(750.0, mtow(3000.0));
+        assert_eq!(740.0, mtow(4000.0));
+        assert_eq!(730.0, mtow(8000.0));
+
+        let plane = Airplane {
+            limits,
+            ..airplane(true)
+        };
+        assert!(plane.at_density_altitude(4000.0).within_limits());
+        assert!(!plane.at_density_altitude(6500.0).within_limits());
+        let hot = plane.at_density_altitude(6500.0);
+        assert_eq!(2, hot.limits().altitude_mtows().len());
+    }
 }
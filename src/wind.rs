@@ -0,0 +1,88 @@
+//! Headwind and crosswind components of the wind on a runway.
+//!
+//! Directions are in degrees and both the wind and the runway heading must use the same
+//! reference, magnetic for the wind from the tower or ATIS and true for a METAR.
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Wind {
+    direction_deg: f64,
+    speed_kt: f64,
+}
+
+impl Wind {
+    /// The direction the wind is blowing from.
+    pub fn new(direction_deg: f64, speed_kt: f64) -> Wind {
+        Wind {
+            direction_deg,
+            speed_kt,
+        }
+    }
+
+    pub fn direction_deg(&self) -> f64 {
+        self.direction_deg
+    }
+
+    pub fn speed_kt(&self) -> f64 {
+        self.speed_kt
+    }
+
+    pub fn components(&self, runway_heading_deg: f64) -> WindComponents {
+        let angle = (self.direction_deg - runway_heading_deg).to_radians();
+        WindComponents {
+            headwind_kt: self.speed_kt * angle.cos(),
+            crosswind_kt: self.speed_kt * angle.sin(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindComponents {
+    headwind_kt: f64,
+    crosswind_kt: f64,
+}
+
+impl WindComponents {
+    /// A negative headwind is a tailwind.
+    pub fn headwind_kt(&self) -> f64 {
+        self.headwind_kt
+    }
+
+    /// A positive crosswind is from the right, a negative crosswind from the left.
+    pub fn crosswind_kt(&self) -> f64 {
+        self.crosswind_kt
+    }
+}
+
+/// The headwind and crosswind components on a runway.
+pub fn components(
+    runway_heading_deg: f64,
+    wind_direction_deg: f64,
+    wind_speed_kt: f64,
+) -> WindComponents {
+    Wind::new(wind_direction_deg, wind_speed_kt).components(runway_heading_deg)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn runway_components() {
+        let straight = components(240.0, 240.0, 15.0);
+        assert!((straight.headwind_kt() - 15.0).abs() < 1e-9);
+        assert!(straight.crosswind_kt().abs() < 1e-9);
+
+        let right = components(240.0, 270.0, 20.0);
+        assert!((right.headwind_kt() - 20.0 * 3.0_f64.sqrt() / 2.0).abs() < 1e-9);
+        assert!((right.crosswind_kt() - 10.0).abs() < 1e-9);
+
+        // Across north, wind from 330 on runway 06 is a left crosswind
+        let left = components(60.0, 330.0, 10.0);
+        assert!(left.headwind_kt().abs() < 1e-9);
+        assert!((left.crosswind_kt() + 10.0).abs() < 1e-9);
+
+        assert!((components(90.0, 270.0, 5.0).headwind_kt() + 5.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,161 @@
+// Just enough XML for the importers and the snapshots: element names and attributes of start and
+// empty tags, end tags and text in document order. Namespaces and DTDs are not interpreted.
+
+pub(crate) struct Element {
+    pub(crate) name: String,
+    pub(crate) attributes: Vec<(String, String)>,
+}
+
+impl Element {
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+pub(crate) enum Node {
+    // A start tag, or an empty tag followed by its end
+    Start(Element),
+    End(String),
+    // Text and CDATA sections between the tags, without the whitespace around it
+    Text(String),
+}
+
+pub(crate) fn elements(xml: &str) -> Result<Vec<Element>, String> {
+    Ok(nodes(xml)?
+        .into_iter()
+        .filter_map(|node| match node {
+            Node::Start(element) => Some(element),
+            _ => None,
+        })
+        .collect())
+}
+
+fn push_text(nodes: &mut Vec<Node>, text: &str) {
+    let text = text.trim();
+    if !text.is_empty() {
+        nodes.push(Node::Text(unescape(text)));
+    }
+}
+
+pub(crate) fn nodes(xml: &str) -> Result<Vec<Node>, String> {
+    let mut nodes = vec![];
+    let mut rest = xml;
+
+    while let Some(start) = rest.find('<') {
+        push_text(&mut nodes, &rest[..start]);
+        rest = &rest[start + 1..];
+
+        // Comments, processing instructions and declarations are left out
+        if let Some(comment) = rest.strip_prefix("!--") {
+            let end = comment.find("-->").ok_or("unterminated comment")?;
+            rest = &comment[end + 3..];
+            continue;
+        }
+        if let Some(cdata) = rest.strip_prefix("![CDATA[") {
+            let end = cdata.find("]]>").ok_or("unterminated CDATA section")?;
+            let text = cdata[..end].trim();
+            if !text.is_empty() {
+                nodes.push(Node::Text(text.to_string()));
+            }
+            rest = &cdata[end + 3..];
+            continue;
+        }
+        if let Some(end_tag) = rest.strip_prefix('/') {
+            let end = end_tag.find('>').ok_or("unterminated tag")?;
+            nodes.push(Node::End(end_tag[..end].trim().to_string()));
+            rest = &end_tag[end + 1..];
+            continue;
+        }
+        if rest.starts_with(['?', '!']) {
+            let end = rest.find('>').ok_or("unterminated tag")?;
+            rest = &rest[end + 1..];
+            continue;
+        }
+
+        let end = rest.find('>').ok_or("unterminated tag")?;
+        let empty = rest[..end].ends_with('/');
+        let tag = rest[..end].trim_end_matches('/');
+        rest = &rest[end + 1..];
+
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        let name = tag[..name_end].to_string();
+        let mut attributes = vec![];
+
+        let mut attrs = tag[name_end..].trim_start();
+        while !attrs.is_empty() {
+            let eq = attrs
+                .find('=')
+                .ok_or_else(|| format!("attribute without value in <{}>", name))?;
+            let key = attrs[..eq].trim().to_string();
+            let value = attrs[eq + 1..].trim_start();
+            let quote = value
+                .chars()
+                .next()
+                .filter(|c| *c == '"' || *c == '\'')
+                .ok_or_else(|| format!("unquoted attribute {} in <{}>", key, name))?;
+            let close = value[1..]
+                .find(quote)
+                .ok_or_else(|| format!("unterminated attribute {} in <{}>", key, name))?;
+
+            attributes.push((key, unescape(&value[1..close + 1])));
+            attrs = value[close + 2..].trim_start();
+        }
+
+        if empty {
+            nodes.push(Node::Start(Element {
+                name: name.clone(),
+                attributes,
+            }));
+            nodes.push(Node::End(name));
+        } else {
+            nodes.push(Node::Start(Element { name, attributes }));
+        }
+    }
+    push_text(&mut nodes, rest);
+
+    Ok(nodes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn start_and_empty_tags() {
+        let elements = elements(
+            r#"<?xml version="1.0"?>
+<!-- profile -->
+<root a="1"><child name='A &amp; B' arm = "2.5" /></root>"#,
+        )
+        .unwrap();
+
+        assert_eq!(2, elements.len());
+        assert_eq!("root", elements[0].name);
+        assert_eq!(Some("A & B"), elements[1].attribute("name"));
+        assert_eq!(Some("2.5"), elements[1].attribute("arm"));
+        assert!(super::elements("<root a=1>").is_err());
+    }
+
+    #[test]
+    fn text_and_end_tags() {
+        let nodes = nodes("<a><b/> x &lt; y <![CDATA[<json>]]></a>").unwrap();
+
+        assert_eq!(6, nodes.len());
+        assert!(matches!(&nodes[2], Node::End(name) if name == "b"));
+        assert!(matches!(&nodes[3], Node::Text(text) if text == "x < y"));
+        assert!(matches!(&nodes[4], Node::Text(text) if text == "<json>"));
+        assert!(matches!(&nodes[5], Node::End(name) if name == "a"));
+    }
+}